@@ -2,6 +2,7 @@
 #![cfg(feature = "benchmark")]
 
 use super::*;
+use crate::tests::{cid, create_commitment, MockCoreContract};
 use soroban_sdk::{testutils::Address as _, Address, Env, String};
 
 /// Benchmark helper to measure gas usage
@@ -33,10 +34,10 @@ impl BenchmarkMetrics {
     }
 }
 
-fn setup_test_env(e: &Env) -> (Address, Address) {
+fn setup_test_env(e: &Env) -> (Address, Address, Address) {
     e.mock_all_auths();
     let admin = Address::generate(e);
-    let core_contract = Address::generate(e);
+    let core_contract = e.register_contract(None, MockCoreContract);
     let contract_id = e.register_contract(None, AllocationStrategiesContract);
 
     e.as_contract(&contract_id, || {
@@ -44,7 +45,7 @@ fn setup_test_env(e: &Env) -> (Address, Address) {
             .unwrap();
     });
 
-    (contract_id, admin)
+    (contract_id, admin, core_contract)
 }
 
 #[test]
@@ -71,7 +72,7 @@ fn benchmark_initialize() {
 #[test]
 fn benchmark_register_pool() {
     let e = Env::default();
-    let (contract_id, admin) = setup_test_env(&e);
+    let (contract_id, admin, _) = setup_test_env(&e);
 
     let mut metrics = BenchmarkMetrics::new("register_pool");
 
@@ -81,6 +82,7 @@ fn benchmark_register_pool() {
             e.clone(),
             admin.clone(),
             1,
+            Address::generate(&e),
             RiskLevel::Low,
             500, // 5% APY
             10000_0000000,
@@ -96,7 +98,7 @@ fn benchmark_register_pool() {
 #[test]
 fn benchmark_allocate() {
     let e = Env::default();
-    let (contract_id, admin) = setup_test_env(&e);
+    let (contract_id, admin, core_contract) = setup_test_env(&e);
 
     // Register a pool first
     e.as_contract(&contract_id, || {
@@ -104,6 +106,7 @@ fn benchmark_allocate() {
             e.clone(),
             admin.clone(),
             1,
+            Address::generate(&e),
             RiskLevel::Low,
             500,
             10000_0000000,
@@ -112,6 +115,7 @@ fn benchmark_allocate() {
     });
 
     let caller = Address::generate(&e);
+    create_commitment(&e, &core_contract, 1, &caller, 1000_0000000);
     let mut metrics = BenchmarkMetrics::new("allocate");
 
     e.as_contract(&contract_id, || {
@@ -119,7 +123,7 @@ fn benchmark_allocate() {
         let _ = AllocationStrategiesContract::allocate(
             e.clone(),
             caller.clone(),
-            1,
+            cid(&e, 1),
             1000_0000000,
             Strategy::Safe,
         );
@@ -133,15 +137,17 @@ fn benchmark_allocate() {
 #[test]
 fn benchmark_get_allocation() {
     let e = Env::default();
-    let (contract_id, admin) = setup_test_env(&e);
+    let (contract_id, admin, core_contract) = setup_test_env(&e);
 
     // Register pool and create allocation
     let caller = Address::generate(&e);
+    create_commitment(&e, &core_contract, 1, &caller, 1000_0000000);
     e.as_contract(&contract_id, || {
         AllocationStrategiesContract::register_pool(
             e.clone(),
             admin.clone(),
             1,
+            Address::generate(&e),
             RiskLevel::Low,
             500,
             10000_0000000,
@@ -150,7 +156,7 @@ fn benchmark_get_allocation() {
         AllocationStrategiesContract::allocate(
             e.clone(),
             caller.clone(),
-            1,
+            cid(&e, 1),
             1000_0000000,
             Strategy::Safe,
         )
@@ -161,7 +167,7 @@ fn benchmark_get_allocation() {
 
     e.as_contract(&contract_id, || {
         let start = e.ledger().sequence();
-        AllocationStrategiesContract::get_allocation(e.clone(), 1);
+        AllocationStrategiesContract::get_allocation(e.clone(), cid(&e, 1));
         let end = e.ledger().sequence();
         metrics.record_gas(start, end);
     });
@@ -172,7 +178,7 @@ fn benchmark_get_allocation() {
 #[test]
 fn benchmark_get_pool() {
     let e = Env::default();
-    let (contract_id, admin) = setup_test_env(&e);
+    let (contract_id, admin, _) = setup_test_env(&e);
 
     // Register a pool first
     e.as_contract(&contract_id, || {
@@ -180,6 +186,7 @@ fn benchmark_get_pool() {
             e.clone(),
             admin.clone(),
             1,
+            Address::generate(&e),
             RiskLevel::Low,
             500,
             10000_0000000,
@@ -202,7 +209,7 @@ fn benchmark_get_pool() {
 #[test]
 fn benchmark_batch_allocate() {
     let e = Env::default();
-    let (contract_id, admin) = setup_test_env(&e);
+    let (contract_id, admin, core_contract) = setup_test_env(&e);
 
     // Register pools
     for i in 1..=5 {
@@ -211,6 +218,7 @@ fn benchmark_batch_allocate() {
                 e.clone(),
                 admin.clone(),
                 i,
+                Address::generate(&e),
                 RiskLevel::Low,
                 500,
                 10000_0000000,
@@ -224,11 +232,12 @@ fn benchmark_batch_allocate() {
     let start = e.ledger().sequence();
     for i in 1..=10 {
         let caller = Address::generate(&e);
+        create_commitment(&e, &core_contract, i, &caller, 1000_0000000);
         e.as_contract(&contract_id, || {
             let _ = AllocationStrategiesContract::allocate(
                 e.clone(),
                 caller.clone(),
-                i,
+                cid(&e, i),
                 1000_0000000,
                 Strategy::Safe,
             );
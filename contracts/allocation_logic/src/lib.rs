@@ -1,14 +1,33 @@
 // Allocation Strategies Contract
 #![no_std]
 
-use shared_utils::{Pausable, RateLimiter};
+use commitment_interface::{
+    Commitment, CommitmentCoreClient, CommitmentCoreInternalClient, CommitmentStatus,
+};
+use shared_utils::{fees, CommitmentId, Pausable, RateLimiter};
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Map,
-    Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env,
+    Map, String, Symbol, Vec,
 };
 
 // Current storage version for migration checks.
-const CURRENT_VERSION: u32 = 1;
+// v2 keys allocation records by commitment_core's String id instead of u64.
+// v3 replaces `Pool.active` with a `PoolStatus` lifecycle.
+const CURRENT_VERSION: u32 = 3;
+
+// Pool `apy` is in basis points (10000 = 100% per year).
+const APY_BASIS_POINTS: i128 = 10_000;
+const SECONDS_PER_YEAR: i128 = 365 * 24 * 60 * 60;
+// Fixed-point scale of `PoolYield::yield_index`.
+const YIELD_INDEX_SCALE: i128 = 1_000_000_000_000;
+// Strategy weights and caps are in basis points of the allocated amount.
+const STRATEGY_BASIS_POINTS: u32 = 10_000;
+// Drift a keeper rebalance needs by default, in basis points.
+const DEFAULT_DRIFT_THRESHOLD_BPS: u32 = 500;
+// Most commitments one `migrate_pool` call may move.
+const MAX_POOL_MIGRATION_BATCH: u32 = 50;
+// Most legacy ids one `migrate_allocations` call may re-key.
+const MAX_ALLOCATION_MIGRATION_BATCH: u32 = 50;
 
 // ============================================================================
 // ERROR CODES - Error Handling
@@ -34,6 +53,16 @@ pub enum Error {
     InvalidVersion = 16,
     AlreadyMigrated = 17,
     InsufficientCommitmentBalance = 18,
+    CommitmentNotFound = 19,
+    CommitmentNotActive = 20,
+    StrategyNotFound = 21,
+    InvalidStrategy = 22,
+    StrategyInactive = 23,
+    InvalidRebalanceConfig = 24,
+    DriftBelowThreshold = 25,
+    InvalidPoolStatus = 26,
+    PoolNotEmpty = 27,
+    BatchTooLarge = 28,
 }
 
 // ============================================================================
@@ -46,6 +75,8 @@ pub enum Strategy {
     Safe,
     Balanced,
     Aggressive,
+    /// Admin-registered `StrategyConfig` with this id
+    Custom(u32),
 }
 
 #[contracttype]
@@ -56,15 +87,30 @@ pub enum RiskLevel {
     High,
 }
 
+/// Pool lifecycle: `Active` -> `Draining` -> `Closed`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PoolStatus {
+    /// Accepts new allocations
+    Active,
+    /// Accepts no new funds; existing allocations are withdrawn or
+    /// migrated out with `migrate_pool`
+    Draining,
+    /// Empty and retired for good
+    Closed,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct Pool {
     pub pool_id: u32,
+    /// Address allocated funds are transferred to
+    pub pool_address: Address,
     pub risk_level: RiskLevel,
     pub apy: u32,
     pub total_liquidity: i128,
     pub max_capacity: i128,
-    pub active: bool,
+    pub status: PoolStatus,
     pub created_at: u64,
     pub updated_at: u64,
 }
@@ -72,7 +118,7 @@ pub struct Pool {
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct Allocation {
-    pub commitment_id: u64,
+    pub commitment_id: String,
     pub pool_id: u32,
     pub amount: i128,
     pub timestamp: u64,
@@ -81,10 +127,78 @@ pub struct Allocation {
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct AllocationSummary {
-    pub commitment_id: u64,
+    pub commitment_id: String,
     pub strategy: Strategy,
     pub total_allocated: i128,
     pub allocations: Vec<Allocation>,
+    /// Yield accrued on the allocations and not yet harvested
+    pub pending_yield: i128,
+}
+
+/// One pool's share of a planned allocation (see `preview_allocation`).
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlannedAllocation {
+    pub pool_id: u32,
+    pub amount: i128,
+}
+
+/// Admin-defined allocation profile, selected with `Strategy::Custom(strategy_id)`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrategyConfig {
+    pub strategy_id: u32,
+    pub name: String,
+    /// Share of the allocated amount per risk level, in basis points (sum 10000)
+    pub low_weight_bps: u32,
+    pub medium_weight_bps: u32,
+    pub high_weight_bps: u32,
+    /// Minimum share of the amount any used pool must receive (basis points)
+    pub min_pool_bps: u32,
+    /// Cap on any single pool's share of the amount (basis points, 0 = no cap)
+    pub max_pool_bps: u32,
+    /// Pools the strategy may use; empty allows every active pool
+    pub allowed_pools: Vec<u32>,
+    /// Inactive strategies cannot be chosen for new allocations
+    pub active: bool,
+}
+
+/// Yield accounting for a pool, accrued from `apy` over ledger time.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolYield {
+    pub pool_id: u32,
+    /// Cumulative yield per unit of principal, scaled by 10^12
+    pub yield_index: i128,
+    /// Total yield accrued on the pool's liquidity
+    pub accrued: i128,
+    /// Yield harvested back to commitments
+    pub distributed: i128,
+    pub last_accrual: u64,
+}
+
+/// Yield bookkeeping for one commitment's allocations.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct YieldPosition {
+    /// Pool `yield_index` at the last settlement, by pool id
+    pub checkpoints: Map<u32, i128>,
+    /// Yield settled (e.g. before a rebalance) but not yet harvested, by
+    /// the pool holding it
+    pub unclaimed: Map<u32, i128>,
+}
+
+/// Keeper rebalancing settings.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RebalanceConfig {
+    /// Drift `rebalance_if_drifted` must exceed, in basis points
+    pub drift_threshold_bps: u32,
+    /// Reward per keeper rebalance, in the commitment's asset
+    pub keeper_reward: i128,
+    /// Protocol fee on harvested yield, in basis points; it funds the
+    /// asset's keeper reward bucket
+    pub yield_fee_bps: u32,
 }
 
 // ============================================================================
@@ -95,16 +209,57 @@ pub struct AllocationSummary {
 #[derive(Clone)]
 pub enum DataKey {
     Pool(u32),
-    Allocations(u64),
-    Strategy(u64),
+    Allocations(String),
+    Strategy(String),
     CommitmentCore,
     Admin,
     Initialized,
     ReentrancyGuard,
-    PoolRegistry,         // Vec<u32> of all pool IDs
-    TotalAllocated(u64),  // Total amount allocated per commitment
-    AllocationOwner(u64), // Track allocation ownership
-    Version,              // Contract version
+    PoolRegistry,            // Vec<u32> of all pool IDs
+    TotalAllocated(String),  // Total amount allocated per commitment
+    AllocationOwner(String), // Address that opened the allocation
+    Version,                 // Contract version
+    PoolYield(u32),          // Yield accounting per pool
+    YieldPosition(String),   // Yield checkpoints per commitment
+    CustomStrategy(u32),     // StrategyConfig per strategy id
+    StrategyRegistry,        // Vec<u32> of all custom strategy ids
+    RebalanceConfig,         // Keeper rebalancing settings
+    KeeperRewards(Address),  // Keeper reward bucket per asset, funded by yield fees
+}
+
+/// Allocation record as stored before v2, keyed by a numeric commitment id.
+#[contracttype(export = false)]
+#[derive(Clone)]
+struct LegacyAllocation {
+    commitment_id: u64,
+    pool_id: u32,
+    amount: i128,
+    timestamp: u64,
+}
+
+/// Pool record as stored before v3, with an `active` flag.
+#[contracttype(export = false)]
+#[derive(Clone)]
+struct LegacyPool {
+    pool_id: u32,
+    pool_address: Address,
+    risk_level: RiskLevel,
+    apy: u32,
+    total_liquidity: i128,
+    max_capacity: i128,
+    active: bool,
+    created_at: u64,
+    updated_at: u64,
+}
+
+/// Storage keys of pre-v2 allocation records.
+#[contracttype(export = false)]
+#[derive(Clone)]
+enum LegacyDataKey {
+    Allocations(u64),
+    Strategy(u64),
+    TotalAllocated(u64),
+    AllocationOwner(u64),
 }
 
 // ============================================================================
@@ -135,6 +290,9 @@ impl AllocationStrategiesContract {
             .instance()
             .set(&DataKey::CommitmentCore, &commitment_core);
         env.storage().instance().set(&DataKey::Initialized, &true);
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &CURRENT_VERSION);
         env.storage()
             .instance()
             .set(&DataKey::PoolRegistry, &Vec::<u32>::new(&env));
@@ -157,6 +315,7 @@ impl AllocationStrategiesContract {
         env: Env,
         admin: Address,
         pool_id: u32,
+        pool_address: Address,
         risk_level: RiskLevel,
         apy: u32,
         max_capacity: i128,
@@ -183,11 +342,12 @@ impl AllocationStrategiesContract {
 
         let pool = Pool {
             pool_id,
+            pool_address,
             risk_level,
             apy,
             total_liquidity: 0,
             max_capacity,
-            active: true,
+            status: PoolStatus::Active,
             created_at: env.ledger().timestamp(),
             updated_at: env.ledger().timestamp(),
         };
@@ -196,6 +356,10 @@ impl AllocationStrategiesContract {
         env.storage()
             .persistent()
             .set(&DataKey::Pool(pool_id), &pool);
+        env.storage().persistent().set(
+            &DataKey::PoolYield(pool_id),
+            &Self::read_pool_yield(&env, pool_id),
+        );
 
         // Add to registry
         let mut registry: Vec<u32> = env
@@ -215,11 +379,16 @@ impl AllocationStrategiesContract {
         Ok(())
     }
 
+    /// Move a pool through its lifecycle.
+    ///
+    /// `Active` and `Draining` pools may switch between each other; a
+    /// `Draining` pool may be `Closed` once its liquidity is zero. `Closed`
+    /// is final.
     pub fn update_pool_status(
         env: Env,
         admin: Address,
         pool_id: u32,
-        active: bool,
+        status: PoolStatus,
     ) -> Result<(), Error> {
         admin.require_auth();
         Self::require_initialized(&env)?;
@@ -227,7 +396,17 @@ impl AllocationStrategiesContract {
         Self::require_no_reentrancy(&env)?;
 
         let mut pool = Self::get_pool_internal(&env, pool_id)?;
-        pool.active = active;
+        match (pool.status, status) {
+            (PoolStatus::Active, PoolStatus::Draining)
+            | (PoolStatus::Draining, PoolStatus::Active) => {}
+            (PoolStatus::Draining, PoolStatus::Closed) => {
+                if pool.total_liquidity != 0 {
+                    return Err(Error::PoolNotEmpty);
+                }
+            }
+            _ => return Err(Error::InvalidPoolStatus),
+        }
+        pool.status = status;
         pool.updated_at = env.ledger().timestamp();
 
         env.storage()
@@ -235,11 +414,153 @@ impl AllocationStrategiesContract {
             .set(&DataKey::Pool(pool_id), &pool);
 
         env.events()
-            .publish((symbol_short!("pool_upd"), pool_id), active);
+            .publish((symbol_short!("pool_upd"), pool_id), status);
 
         Ok(())
     }
 
+    /// Move allocations of `commitment_ids` out of a `Draining` pool into an
+    /// `Active` replacement (admin-only).
+    ///
+    /// Allocation records cannot be enumerated, so the caller passes the
+    /// commitment ids (e.g. from past `allocate` events) in batches of at
+    /// most `MAX_POOL_MIGRATION_BATCH`. Tokens move from the old pool
+    /// address to the new one via `transfer_from`, and pending yield is
+    /// kept. Ids without an allocation in `from_pool_id` are skipped. The
+    /// old pool can be `Closed` once its liquidity reaches zero. Returns
+    /// the number of commitments moved.
+    pub fn migrate_pool(
+        env: Env,
+        admin: Address,
+        from_pool_id: u32,
+        to_pool_id: u32,
+        commitment_ids: Vec<String>,
+    ) -> Result<u32, Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+        Self::require_no_reentrancy(&env)?;
+
+        if commitment_ids.len() > MAX_POOL_MIGRATION_BATCH {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let from_pool = Self::get_pool_internal(&env, from_pool_id)?;
+        let to_pool = Self::get_pool_internal(&env, to_pool_id)?;
+        if from_pool_id == to_pool_id
+            || from_pool.status != PoolStatus::Draining
+            || to_pool.status != PoolStatus::Active
+        {
+            return Err(Error::InvalidPoolStatus);
+        }
+
+        Self::set_reentrancy_guard(&env, true);
+
+        let mut migrated = 0u32;
+        let mut moved = 0i128;
+        for commitment_id in commitment_ids.iter() {
+            let allocations: Vec<Allocation> = match env
+                .storage()
+                .persistent()
+                .get(&DataKey::Allocations(commitment_id.clone()))
+            {
+                Some(allocations) => allocations,
+                None => continue,
+            };
+
+            let mut amount = 0i128;
+            for allocation in allocations.iter() {
+                if allocation.pool_id == from_pool_id {
+                    amount = amount
+                        .checked_add(allocation.amount)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                }
+            }
+            if amount == 0 {
+                continue;
+            }
+
+            let commitment = Self::get_core_commitment(&env, &commitment_id)?;
+
+            // Settle yield earned under the current allocations
+            let unclaimed = Self::settle_yield(&env, &commitment_id, &allocations)?;
+
+            // Fold the old pool's share into the replacement pool
+            let mut updated = Vec::new(&env);
+            let mut merged = false;
+            for mut allocation in allocations.iter() {
+                if allocation.pool_id == from_pool_id {
+                    continue;
+                }
+                if allocation.pool_id == to_pool_id {
+                    allocation.amount = allocation
+                        .amount
+                        .checked_add(amount)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                    merged = true;
+                }
+                updated.push_back(allocation);
+            }
+            if !merged {
+                updated.push_back(Allocation {
+                    commitment_id: commitment_id.clone(),
+                    pool_id: to_pool_id,
+                    amount,
+                    timestamp: env.ledger().timestamp(),
+                });
+            }
+
+            let mut from_pool = Self::get_pool_internal(&env, from_pool_id)?;
+            from_pool.total_liquidity = from_pool
+                .total_liquidity
+                .checked_sub(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            from_pool.updated_at = env.ledger().timestamp();
+            env.storage()
+                .persistent()
+                .set(&DataKey::Pool(from_pool_id), &from_pool);
+
+            let mut to_pool = Self::get_pool_internal(&env, to_pool_id)?;
+            let new_liquidity = to_pool
+                .total_liquidity
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            if new_liquidity > to_pool.max_capacity {
+                Self::set_reentrancy_guard(&env, false);
+                return Err(Error::PoolCapacityExceeded);
+            }
+            to_pool.total_liquidity = new_liquidity;
+            to_pool.updated_at = env.ledger().timestamp();
+            env.storage()
+                .persistent()
+                .set(&DataKey::Pool(to_pool_id), &to_pool);
+
+            token::Client::new(&env, &commitment.asset_address).transfer_from(
+                &env.current_contract_address(),
+                &from_pool.pool_address,
+                &to_pool.pool_address,
+                &amount,
+            );
+
+            env.storage()
+                .persistent()
+                .set(&DataKey::Allocations(commitment_id.clone()), &updated);
+            Self::checkpoint_yield(&env, &commitment_id, &updated, unclaimed)?;
+
+            migrated += 1;
+            moved = moved.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+        }
+
+        Self::set_reentrancy_guard(&env, false);
+
+        env.events().publish(
+            (symbol_short!("pool_mig"), from_pool_id),
+            (to_pool_id, migrated, moved),
+        );
+
+        Ok(migrated)
+    }
+
     pub fn update_pool_capacity(
         env: Env,
         admin: Address,
@@ -271,21 +592,148 @@ impl AllocationStrategiesContract {
         Ok(())
     }
 
+    /// Register a custom weighted strategy that owners can pick with
+    /// `Strategy::Custom(config.strategy_id)`.
+    ///
+    /// Weights must sum to 10000 basis points, `min_pool_bps` may not exceed
+    /// `max_pool_bps` (when capped), and every allowed pool must exist.
+    /// Allocations whose pool shares fall outside the caps fail with
+    /// `NoSuitablePools`.
+    pub fn register_strategy(
+        env: Env,
+        admin: Address,
+        config: StrategyConfig,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::CustomStrategy(config.strategy_id))
+        {
+            return Err(Error::InvalidStrategy);
+        }
+
+        let total_weight = config
+            .low_weight_bps
+            .checked_add(config.medium_weight_bps)
+            .and_then(|x| x.checked_add(config.high_weight_bps))
+            .ok_or(Error::InvalidStrategy)?;
+        if total_weight != STRATEGY_BASIS_POINTS
+            || config.max_pool_bps > STRATEGY_BASIS_POINTS
+            || (config.max_pool_bps > 0 && config.min_pool_bps > config.max_pool_bps)
+            || config.min_pool_bps > STRATEGY_BASIS_POINTS
+        {
+            return Err(Error::InvalidStrategy);
+        }
+        for pool_id in config.allowed_pools.iter() {
+            Self::get_pool_internal(&env, pool_id)?;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::CustomStrategy(config.strategy_id), &config);
+
+        let mut registry: Vec<u32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::StrategyRegistry)
+            .unwrap_or(Vec::new(&env));
+        registry.push_back(config.strategy_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::StrategyRegistry, &registry);
+
+        env.events().publish(
+            (symbol_short!("strat_reg"), config.strategy_id),
+            config.name,
+        );
+
+        Ok(())
+    }
+
+    /// Enable or disable a custom strategy for new allocations.
+    ///
+    /// Existing allocations keep rebalancing with it.
+    pub fn update_strategy_status(
+        env: Env,
+        admin: Address,
+        strategy_id: u32,
+        active: bool,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        let mut config = Self::get_strategy_internal(&env, strategy_id)?;
+        config.active = active;
+        env.storage()
+            .persistent()
+            .set(&DataKey::CustomStrategy(strategy_id), &config);
+
+        env.events()
+            .publish((symbol_short!("strat_upd"), strategy_id), active);
+
+        Ok(())
+    }
+
+    /// Set the drift threshold and keeper reward for `rebalance_if_drifted`,
+    /// and the yield fee that funds the reward.
+    pub fn set_rebalance_config(
+        env: Env,
+        admin: Address,
+        config: RebalanceConfig,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        if config.drift_threshold_bps > STRATEGY_BASIS_POINTS
+            || config.yield_fee_bps > STRATEGY_BASIS_POINTS
+            || config.keeper_reward < 0
+        {
+            return Err(Error::InvalidRebalanceConfig);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::RebalanceConfig, &config);
+
+        env.events().publish(
+            (symbol_short!("rebal_cfg"),),
+            (
+                config.drift_threshold_bps,
+                config.keeper_reward,
+                config.yield_fee_bps,
+            ),
+        );
+
+        Ok(())
+    }
+
     // ========================================================================
     // CORE ALLOCATION FUNCTIONS
     // ========================================================================
 
     /// Allocate funds according to strategy
     ///
+    /// Reads the commitment from commitment_core and moves `amount` of its
+    /// asset into the selected pools' addresses via `CommitmentCoreContract::allocate`.
+    ///
     /// # Formal Verification
     /// **Preconditions:**
     /// - Contract is initialized
     /// - `amount > 0`
     /// - `reentrancy_guard == false`
+    /// - Commitment exists in commitment_core, is `Active` and owned by `caller`
+    /// - `amount <= commitment.current_value`
     /// - No existing allocation for `commitment_id`
     ///
     /// **Postconditions:**
     /// - `get_allocation(commitment_id).total_allocated == amount`
+    /// - Each pool address received its allocated amount from commitment_core
     /// - For all pools P: `P.total_liquidity <= P.max_capacity`
     /// - `reentrancy_guard == false`
     ///
@@ -299,7 +747,7 @@ impl AllocationStrategiesContract {
     pub fn allocate(
         env: Env,
         caller: Address,
-        commitment_id: u64,
+        commitment_id: String,
         amount: i128,
         strategy: Strategy,
     ) -> Result<AllocationSummary, Error> {
@@ -323,9 +771,23 @@ impl AllocationStrategiesContract {
             return Err(Error::InvalidAmount);
         }
 
-        // Check commitment balance
-        let commitment_balance = Self::get_commitment_balance(&env, commitment_id)?;
-        if amount > commitment_balance {
+        // Check the commitment in commitment_core
+        let commitment = match Self::get_core_commitment(&env, &commitment_id) {
+            Ok(commitment) => commitment,
+            Err(err) => {
+                Self::set_reentrancy_guard(&env, false);
+                return Err(err);
+            }
+        };
+        if commitment.status != CommitmentStatus::Active {
+            Self::set_reentrancy_guard(&env, false);
+            return Err(Error::CommitmentNotActive);
+        }
+        if commitment.owner != caller {
+            Self::set_reentrancy_guard(&env, false);
+            return Err(Error::Unauthorized);
+        }
+        if amount > commitment.current_value {
             Self::set_reentrancy_guard(&env, false);
             return Err(Error::InsufficientCommitmentBalance);
         }
@@ -334,32 +796,30 @@ impl AllocationStrategiesContract {
         if env
             .storage()
             .persistent()
-            .has(&DataKey::Allocations(commitment_id))
+            .has(&DataKey::Allocations(commitment_id.clone()))
         {
             Self::set_reentrancy_guard(&env, false);
             return Err(Error::AlreadyInitialized);
         }
 
+        // Calculate allocation amounts with overflow protection
+        let allocation_plan = match Self::plan_allocation(&env, amount, strategy) {
+            Ok(plan) => plan,
+            Err(err) => {
+                Self::set_reentrancy_guard(&env, false);
+                return Err(err);
+            }
+        };
+
         // Store allocation ownership
         env.storage()
             .persistent()
-            .set(&DataKey::AllocationOwner(commitment_id), &caller);
+            .set(&DataKey::AllocationOwner(commitment_id.clone()), &caller);
 
         // Store the strategy
         env.storage()
             .persistent()
-            .set(&DataKey::Strategy(commitment_id), &strategy);
-
-        // Get pools based on strategy
-        let pools = Self::select_pools(&env, strategy)?;
-
-        if pools.is_empty() {
-            Self::set_reentrancy_guard(&env, false);
-            return Err(Error::NoSuitablePools);
-        }
-
-        // Calculate allocation amounts with overflow protection
-        let allocation_plan = Self::calculate_allocation(&env, amount, &pools, strategy)?;
+            .set(&DataKey::Strategy(commitment_id.clone()), &strategy);
 
         // Execute allocations
         let mut allocations = Vec::new(&env);
@@ -373,8 +833,8 @@ impl AllocationStrategiesContract {
             // Update pool liquidity with overflow check
             let mut pool = Self::get_pool_internal(&env, pool_id)?;
 
-            // Check pool is active
-            if !pool.active {
+            // Check pool accepts new funds
+            if pool.status != PoolStatus::Active {
                 Self::set_reentrancy_guard(&env, false);
                 return Err(Error::PoolInactive);
             }
@@ -390,6 +850,7 @@ impl AllocationStrategiesContract {
                 return Err(Error::PoolCapacityExceeded);
             }
 
+            Self::accrue_pool_yield(&env, &pool)?;
             pool.total_liquidity = new_liquidity;
             pool.updated_at = env.ledger().timestamp();
             env.storage()
@@ -398,7 +859,7 @@ impl AllocationStrategiesContract {
 
             // Record allocation
             let allocation = Allocation {
-                commitment_id,
+                commitment_id: commitment_id.clone(),
                 pool_id,
                 amount: alloc_amount,
                 timestamp: env.ledger().timestamp(),
@@ -418,20 +879,29 @@ impl AllocationStrategiesContract {
             return Err(Error::ArithmeticOverflow);
         }
 
+        // Move funds from commitment_core into each pool
+        let core = CommitmentCoreInternalClient::new(&env, &Self::get_commitment_core(&env)?);
+        for allocation in allocations.iter() {
+            let pool = Self::get_pool_internal(&env, allocation.pool_id)?;
+            core.allocate(&commitment_id, &pool.pool_address, &allocation.amount);
+        }
+
         // Store allocations
         env.storage()
             .persistent()
-            .set(&DataKey::Allocations(commitment_id), &allocations);
-        env.storage()
-            .persistent()
-            .set(&DataKey::TotalAllocated(commitment_id), &total_allocated);
+            .set(&DataKey::Allocations(commitment_id.clone()), &allocations);
+        env.storage().persistent().set(
+            &DataKey::TotalAllocated(commitment_id.clone()),
+            &total_allocated,
+        );
+        Self::checkpoint_yield(&env, &commitment_id, &allocations, Map::new(&env))?;
 
         // Clear reentrancy guard
         Self::set_reentrancy_guard(&env, false);
 
         // Emit event
         env.events().publish(
-            (symbol_short!("allocate"), commitment_id),
+            (symbol_short!("allocate"), commitment_id.clone()),
             (strategy, amount),
         );
 
@@ -440,13 +910,21 @@ impl AllocationStrategiesContract {
             strategy,
             total_allocated,
             allocations,
+            pending_yield: 0,
         })
     }
 
+    /// Recompute pool shares for an existing allocation.
+    ///
+    /// Funds are moved between pool addresses to match the new shares, so
+    /// pools must have approved this contract to spend the commitment's
+    /// asset. Yield accrued on the old allocations is kept for the next
+    /// `harvest`. Only the commitment's current owner in commitment_core
+    /// may call this.
     pub fn rebalance(
         env: Env,
         caller: Address,
-        commitment_id: u64,
+        commitment_id: String,
     ) -> Result<AllocationSummary, Error> {
         caller.require_auth();
         Self::require_initialized(&env)?;
@@ -456,14 +934,15 @@ impl AllocationStrategiesContract {
         let fn_symbol = symbol_short!("rebal");
         RateLimiter::check(&env, &caller, &fn_symbol);
 
-        // Verify ownership
-        let owner: Address = env
+        // Verify ownership against commitment_core, which follows position transfers
+        if !env
             .storage()
             .persistent()
-            .get(&DataKey::AllocationOwner(commitment_id))
-            .ok_or(Error::AllocationNotFound)?;
-
-        if owner != caller {
+            .has(&DataKey::AllocationOwner(commitment_id.clone()))
+        {
+            return Err(Error::AllocationNotFound);
+        }
+        if Self::get_core_commitment(&env, &commitment_id)?.owner != caller {
             return Err(Error::Unauthorized);
         }
 
@@ -471,139 +950,396 @@ impl AllocationStrategiesContract {
         Pausable::require_not_paused(&env);
 
         Self::set_reentrancy_guard(&env, true);
+        let summary = Self::rebalance_allocation(&env, commitment_id)?;
+        Self::set_reentrancy_guard(&env, false);
 
-        // Get current allocations
-        let current_allocations: Vec<Allocation> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Allocations(commitment_id))
-            .ok_or(Error::AllocationNotFound)?;
+        Ok(summary)
+    }
 
-        // Get strategy
-        let strategy: Strategy = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Strategy(commitment_id))
-            .ok_or(Error::AllocationNotFound)?;
+    /// Rebalance a commitment whose allocation has drifted from its
+    /// strategy target by more than the configured threshold.
+    ///
+    /// Anyone may call this. The keeper is paid `keeper_reward` of the
+    /// commitment's asset from the keeper reward bucket, which `harvest`
+    /// fills with the yield fee, or whatever the bucket holds if that is
+    /// less. Fails with `DriftBelowThreshold` when
+    /// the allocation is still on-strategy.
+    pub fn rebalance_if_drifted(
+        env: Env,
+        keeper: Address,
+        commitment_id: String,
+    ) -> Result<AllocationSummary, Error> {
+        keeper.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_no_reentrancy(&env)?;
 
-        let mut total_amount = 0i128;
+        // Rate limit keeper rebalancing per keeper address
+        let fn_symbol = symbol_short!("keeper");
+        RateLimiter::check(&env, &keeper, &fn_symbol);
 
-        // Remove old allocations from pools with overflow protection
-        for allocation in current_allocations.iter() {
-            total_amount = total_amount
-                .checked_add(allocation.amount)
-                .ok_or(Error::ArithmeticOverflow)?;
+        // Check if contract is paused
+        Pausable::require_not_paused(&env);
 
-            let mut pool = Self::get_pool_internal(&env, allocation.pool_id)?;
-            pool.total_liquidity = pool
-                .total_liquidity
-                .checked_sub(allocation.amount)
-                .ok_or(Error::ArithmeticOverflow)?;
-            pool.updated_at = env.ledger().timestamp();
-            env.storage()
-                .persistent()
-                .set(&DataKey::Pool(allocation.pool_id), &pool);
+        let commitment = Self::get_core_commitment(&env, &commitment_id)?;
+        if commitment.status != CommitmentStatus::Active {
+            return Err(Error::CommitmentNotActive);
         }
 
-        // Reallocate with current strategy
-        let pools = Self::select_pools(&env, strategy)?;
-        let allocation_plan = Self::calculate_allocation(&env, total_amount, &pools, strategy)?;
+        let drift = Self::allocation_drift(&env, &commitment_id)?;
+        let config = Self::get_rebalance_config(env.clone());
+        if drift <= config.drift_threshold_bps {
+            return Err(Error::DriftBelowThreshold);
+        }
 
-        let mut new_allocations = Vec::new(&env);
-        let mut new_total = 0i128;
+        Self::set_reentrancy_guard(&env, true);
 
-        for (pool_id, alloc_amount) in allocation_plan.iter() {
-            if alloc_amount <= 0 {
-                continue;
-            }
+        let summary = Self::rebalance_allocation(&env, commitment_id.clone())?;
+
+        // Pay the keeper from the reward bucket
+        let bucket = Self::get_keeper_rewards(env.clone(), commitment.asset_address.clone());
+        let reward = config.keeper_reward.min(bucket);
+        if reward > 0 {
+            env.storage().persistent().set(
+                &DataKey::KeeperRewards(commitment.asset_address.clone()),
+                &(bucket - reward),
+            );
+            token::Client::new(&env, &commitment.asset_address).transfer(
+                &env.current_contract_address(),
+                &keeper,
+                &reward,
+            );
+        }
 
-            let mut pool = Self::get_pool_internal(&env, pool_id)?;
+        Self::set_reentrancy_guard(&env, false);
 
-            if !pool.active {
-                continue; // Skip inactive pools during rebalancing
-            }
+        env.events().publish(
+            (symbol_short!("keeper"), commitment_id),
+            (keeper, drift, reward),
+        );
 
-            let new_liquidity = pool
-                .total_liquidity
-                .checked_add(alloc_amount)
-                .ok_or(Error::ArithmeticOverflow)?;
+        Ok(summary)
+    }
 
-            if new_liquidity <= pool.max_capacity {
-                pool.total_liquidity = new_liquidity;
-                pool.updated_at = env.ledger().timestamp();
-                env.storage()
-                    .persistent()
-                    .set(&DataKey::Pool(pool_id), &pool);
+    /// Credit yield accrued on a commitment's allocations back to it.
+    ///
+    /// Each pool's share of the pending yield is transferred from the pool
+    /// address to commitment_core, which adds the total to the commitment's
+    /// `current_value` via `credit_yield`; pools must hold the yield and have
+    /// approved this contract to spend the asset. The configured
+    /// `yield_fee_bps` is kept by this contract for the keeper reward
+    /// bucket. The yield is recorded as distributed by each pool. Anyone may
+    /// call this; the yield always goes to the commitment. Returns the
+    /// amount credited.
+    pub fn harvest(env: Env, commitment_id: String) -> Result<i128, Error> {
+        Self::require_initialized(&env)?;
+        Self::require_no_reentrancy(&env)?;
 
-                let allocation = Allocation {
-                    commitment_id,
-                    pool_id,
-                    amount: alloc_amount,
-                    timestamp: env.ledger().timestamp(),
-                };
+        // Check if contract is paused
+        Pausable::require_not_paused(&env);
 
-                new_allocations.push_back(allocation);
-                new_total = new_total
-                    .checked_add(alloc_amount)
-                    .ok_or(Error::ArithmeticOverflow)?;
-            }
+        let allocations: Vec<Allocation> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Allocations(commitment_id.clone()))
+            .ok_or(Error::AllocationNotFound)?;
+
+        let commitment = Self::get_core_commitment(&env, &commitment_id)?;
+        if !commitment.status.is_open() {
+            return Err(Error::CommitmentNotActive);
         }
 
+        Self::set_reentrancy_guard(&env, true);
+
+        // Settle per pool so each pool's distributed total is tracked
+        let mut position = Self::read_yield_position(&env, &commitment_id);
+        let mut owed = position.unclaimed.clone();
+        for allocation in allocations.iter() {
+            let pool = Self::get_pool_internal(&env, allocation.pool_id)?;
+            let pool_yield = Self::accrue_pool_yield(&env, &pool)?;
+            let earned = Self::earned_yield(&position, &allocation, &pool_yield)?;
+            Self::add_to_pool_amount(&mut owed, allocation.pool_id, earned)?;
+            position
+                .checkpoints
+                .set(allocation.pool_id, pool_yield.yield_index);
+        }
+        position.unclaimed = Map::new(&env);
         env.storage()
             .persistent()
-            .set(&DataKey::Allocations(commitment_id), &new_allocations);
-        env.storage()
+            .set(&DataKey::YieldPosition(commitment_id.clone()), &position);
+
+        // Move each pool's yield to commitment_core before crediting it
+        let core = Self::get_commitment_core(&env)?;
+        let (harvested, fee_total) = Self::pay_yield(&env, &commitment.asset_address, &owed)?;
+
+        if harvested > 0 {
+            CommitmentCoreInternalClient::new(&env, &core).credit_yield(&commitment_id, &harvested);
+        }
+
+        Self::set_reentrancy_guard(&env, false);
+
+        env.events().publish(
+            (symbol_short!("harvest"), commitment_id),
+            (harvested, fee_total),
+        );
+
+        Ok(harvested)
+    }
+
+    /// Return `amount` of a commitment's allocation from its pools.
+    ///
+    /// Each pool gives back a share proportional to its part of the
+    /// allocation; the tokens go to commitment_core, which adds them to the
+    /// commitment's `current_value` via `deallocate`. Pools must have
+    /// approved this contract to spend the commitment's asset. Yield earned
+    /// so far stays pending for the next `harvest`, unless the whole
+    /// allocation is withdrawn. Only the commitment's current owner in
+    /// commitment_core may call this.
+    pub fn deallocate(
+        env: Env,
+        caller: Address,
+        commitment_id: String,
+        amount: i128,
+    ) -> Result<AllocationSummary, Error> {
+        caller.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_no_reentrancy(&env)?;
+
+        // Rate limit deallocations per caller address
+        let fn_symbol = symbol_short!("dealloc");
+        RateLimiter::check(&env, &caller, &fn_symbol);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&env);
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // Verify ownership against commitment_core, which follows position transfers
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::AllocationOwner(commitment_id.clone()))
+        {
+            return Err(Error::AllocationNotFound);
+        }
+        let commitment = Self::get_core_commitment(&env, &commitment_id)?;
+        if commitment.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+        if !commitment.status.is_open() {
+            return Err(Error::CommitmentNotActive);
+        }
+
+        let strategy: Strategy = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Strategy(commitment_id.clone()))
+            .ok_or(Error::AllocationNotFound)?;
+        let total: i128 = env
+            .storage()
             .persistent()
-            .set(&DataKey::TotalAllocated(commitment_id), &new_total);
+            .get(&DataKey::TotalAllocated(commitment_id.clone()))
+            .unwrap_or(0);
+        if amount > total {
+            return Err(Error::InvalidAmount);
+        }
+
+        Self::set_reentrancy_guard(&env, true);
+
+        let allocations =
+            Self::withdraw_from_pools(&env, &commitment_id, &commitment.asset_address, amount)?;
+
+        // Credit the returned funds to the commitment
+        let core = CommitmentCoreInternalClient::new(&env, &Self::get_commitment_core(&env)?);
+        core.deallocate(&commitment_id, &amount);
 
         Self::set_reentrancy_guard(&env, false);
 
-        env.events()
-            .publish((symbol_short!("rebalance"), commitment_id), new_total);
+        let total_allocated = total - amount;
+        env.events().publish(
+            (symbol_short!("dealloc"), commitment_id.clone()),
+            (amount, total_allocated),
+        );
 
         Ok(AllocationSummary {
-            commitment_id,
+            commitment_id: commitment_id.clone(),
             strategy,
-            total_allocated: new_total,
-            allocations: new_allocations,
+            total_allocated,
+            allocations,
+            pending_yield: Self::total_amount(
+                &Self::read_yield_position(&env, &commitment_id).unclaimed,
+            )?,
         })
     }
 
+    /// Return a commitment's whole allocation in `asset` to commitment_core.
+    ///
+    /// Called by commitment_core when the commitment is settled or exited
+    /// early, so it works while paused and must not call back into core;
+    /// core credits the returned amounts itself. Unharvested yield is paid
+    /// out with the principal, less `yield_fee_bps`, as in `harvest`; yield
+    /// a pool cannot cover from its balance beyond `total_liquidity` is
+    /// forfeited so other commitments' principal stays in the pool.
+    /// Returns `(principal, yield)`, or `(0, 0)` if the commitment has no
+    /// allocation.
+    pub fn close_allocation(
+        env: Env,
+        commitment_id: String,
+        asset: Address,
+    ) -> Result<(i128, i128), Error> {
+        Self::require_initialized(&env)?;
+        Self::get_commitment_core(&env)?.require_auth();
+        Self::require_no_reentrancy(&env)?;
+
+        let total: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TotalAllocated(commitment_id.clone()))
+            .unwrap_or(0);
+        if total == 0 {
+            return Ok((0, 0));
+        }
+        let allocations: Vec<Allocation> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Allocations(commitment_id.clone()))
+            .ok_or(Error::AllocationNotFound)?;
+
+        Self::set_reentrancy_guard(&env, true);
+        // Pay out yield earned so far before the position is removed
+        let earned = Self::settle_yield(&env, &commitment_id, &allocations)?;
+        let token = token::Client::new(&env, &asset);
+        let mut owed = Map::new(&env);
+        for (pool_id, amount) in earned.iter() {
+            let pool = Self::get_pool_internal(&env, pool_id)?;
+            let surplus = token
+                .balance(&pool.pool_address)
+                .checked_sub(pool.total_liquidity)
+                .ok_or(Error::ArithmeticOverflow)?;
+            owed.set(pool_id, amount.min(surplus.max(0)));
+        }
+        let (harvested, _) = Self::pay_yield(&env, &asset, &owed)?;
+        Self::withdraw_from_pools(&env, &commitment_id, &asset, total)?;
+        Self::set_reentrancy_guard(&env, false);
+
+        env.events()
+            .publish((symbol_short!("close"), commitment_id), (total, harvested));
+
+        Ok((total, harvested))
+    }
+
     // ========================================================================
     // VIEW FUNCTIONS
     // ========================================================================
 
-    pub fn get_allocation(env: Env, commitment_id: u64) -> AllocationSummary {
+    pub fn get_allocation(env: Env, commitment_id: String) -> AllocationSummary {
         let allocations: Vec<Allocation> = env
             .storage()
             .persistent()
-            .get(&DataKey::Allocations(commitment_id))
+            .get(&DataKey::Allocations(commitment_id.clone()))
             .unwrap_or(Vec::new(&env));
 
         let strategy: Strategy = env
             .storage()
             .persistent()
-            .get(&DataKey::Strategy(commitment_id))
+            .get(&DataKey::Strategy(commitment_id.clone()))
             .unwrap_or(Strategy::Balanced);
 
         let total = env
             .storage()
             .persistent()
-            .get(&DataKey::TotalAllocated(commitment_id))
+            .get(&DataKey::TotalAllocated(commitment_id.clone()))
             .unwrap_or(0i128);
 
+        let pending_yield = Self::pending_yield(&env, &commitment_id, &allocations).unwrap_or(0);
+
         AllocationSummary {
             commitment_id,
             strategy,
             total_allocated: total,
             allocations,
+            pending_yield,
         }
     }
 
+    /// Exact per-pool split `allocate` would make for `amount` right now.
+    ///
+    /// Read-only; fails with the same errors `allocate` would for the
+    /// strategy and pool state.
+    pub fn preview_allocation(
+        env: Env,
+        amount: i128,
+        strategy: Strategy,
+    ) -> Result<Vec<PlannedAllocation>, Error> {
+        Self::require_initialized(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let plan = Self::plan_allocation(&env, amount, strategy)?;
+        let mut preview = Vec::new(&env);
+        for (pool_id, amount) in plan.iter() {
+            preview.push_back(PlannedAllocation { pool_id, amount });
+        }
+        Ok(preview)
+    }
+
     pub fn get_pool(env: Env, pool_id: u32) -> Result<Pool, Error> {
         Self::get_pool_internal(&env, pool_id)
     }
 
+    pub fn get_strategy(env: Env, strategy_id: u32) -> Result<StrategyConfig, Error> {
+        Self::get_strategy_internal(&env, strategy_id)
+    }
+
+    pub fn get_all_strategies(env: Env) -> Vec<StrategyConfig> {
+        let registry: Vec<u32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::StrategyRegistry)
+            .unwrap_or(Vec::new(&env));
+
+        let mut strategies = Vec::new(&env);
+        for strategy_id in registry.iter() {
+            if let Ok(config) = Self::get_strategy_internal(&env, strategy_id) {
+                strategies.push_back(config);
+            }
+        }
+        strategies
+    }
+
+    /// Yield accounting for a pool, accrued up to the current ledger time.
+    pub fn get_pool_yield(env: Env, pool_id: u32) -> Result<PoolYield, Error> {
+        let pool = Self::get_pool_internal(&env, pool_id)?;
+        Self::projected_pool_yield(&env, &pool)
+    }
+
+    /// How far a commitment's allocation is from its strategy target, in
+    /// basis points of the allocated total.
+    pub fn get_drift(env: Env, commitment_id: String) -> Result<u32, Error> {
+        Self::allocation_drift(&env, &commitment_id)
+    }
+
+    pub fn get_rebalance_config(env: Env) -> RebalanceConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::RebalanceConfig)
+            .unwrap_or(RebalanceConfig {
+                drift_threshold_bps: DEFAULT_DRIFT_THRESHOLD_BPS,
+                keeper_reward: 0,
+                yield_fee_bps: 0,
+            })
+    }
+
+    /// Keeper reward bucket balance in `asset`.
+    pub fn get_keeper_rewards(env: Env, asset: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::KeeperRewards(asset))
+            .unwrap_or(0)
+    }
+
     pub fn get_all_pools(env: Env) -> Vec<Pool> {
         let registry: Vec<u32> = env
             .storage()
@@ -671,6 +1407,41 @@ impl AllocationStrategiesContract {
                 .instance()
                 .set(&DataKey::PoolRegistry, &Vec::<u32>::new(&env));
         }
+
+        // v3: pools carry a lifecycle status instead of an active flag
+        if from_version < 3 {
+            let registry: Vec<u32> = env
+                .storage()
+                .instance()
+                .get(&DataKey::PoolRegistry)
+                .unwrap_or(Vec::new(&env));
+            for pool_id in registry.iter() {
+                let legacy: LegacyPool =
+                    match env.storage().persistent().get(&DataKey::Pool(pool_id)) {
+                        Some(legacy) => legacy,
+                        None => continue,
+                    };
+                let status = if legacy.active {
+                    PoolStatus::Active
+                } else {
+                    PoolStatus::Draining
+                };
+                env.storage().persistent().set(
+                    &DataKey::Pool(pool_id),
+                    &Pool {
+                        pool_id: legacy.pool_id,
+                        pool_address: legacy.pool_address,
+                        risk_level: legacy.risk_level,
+                        apy: legacy.apy,
+                        total_liquidity: legacy.total_liquidity,
+                        max_capacity: legacy.max_capacity,
+                        status,
+                        created_at: legacy.created_at,
+                        updated_at: legacy.updated_at,
+                    },
+                );
+            }
+        }
         if !env.storage().instance().has(&DataKey::ReentrancyGuard) {
             env.storage()
                 .instance()
@@ -683,36 +1454,104 @@ impl AllocationStrategiesContract {
         Ok(())
     }
 
+    /// Re-key pre-v2 allocation records from numeric ids to commitment_core's
+    /// `"c_<n>"` ids (admin-only).
+    ///
+    /// Persistent entries cannot be enumerated, so the caller passes the
+    /// legacy ids (e.g. from past `allocate` events) in batches of at most
+    /// `MAX_ALLOCATION_MIGRATION_BATCH`. An id is skipped, and its legacy
+    /// record left in place, when it has no legacy record, when `"c_<n>"`
+    /// already has an allocation record, or when commitment_core has no
+    /// `"c_<n>"` owned by the legacy allocation owner. Returns the number of
+    /// records moved.
+    pub fn migrate_allocations(
+        env: Env,
+        caller: Address,
+        commitment_ids: Vec<u64>,
+    ) -> Result<u32, Error> {
+        caller.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &caller)?;
+        if commitment_ids.len() > MAX_ALLOCATION_MIGRATION_BATCH {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let storage = env.storage().persistent();
+        let mut migrated = 0u32;
+        for legacy_id in commitment_ids.iter() {
+            let legacy_allocations: Vec<LegacyAllocation> =
+                match storage.get(&LegacyDataKey::Allocations(legacy_id)) {
+                    Some(allocations) => allocations,
+                    None => continue,
+                };
+            let commitment_id = CommitmentId::from_number(&env, legacy_id);
+
+            // Never overwrite a v2 record, and only attach the legacy
+            // allocation to the core commitment its owner actually holds.
+            if storage.has(&DataKey::Allocations(commitment_id.clone())) {
+                continue;
+            }
+            let legacy_owner: Address =
+                match storage.get(&LegacyDataKey::AllocationOwner(legacy_id)) {
+                    Some(owner) => owner,
+                    None => continue,
+                };
+            match Self::get_core_commitment(&env, &commitment_id) {
+                Ok(commitment) if commitment.owner == legacy_owner => {}
+                _ => continue,
+            }
+
+            let mut allocations = Vec::new(&env);
+            for legacy in legacy_allocations.iter() {
+                allocations.push_back(Allocation {
+                    commitment_id: commitment_id.clone(),
+                    pool_id: legacy.pool_id,
+                    amount: legacy.amount,
+                    timestamp: legacy.timestamp,
+                });
+            }
+            storage.set(&DataKey::Allocations(commitment_id.clone()), &allocations);
+            storage.remove(&LegacyDataKey::Allocations(legacy_id));
+
+            if let Some(strategy) = storage.get::<_, Strategy>(&LegacyDataKey::Strategy(legacy_id))
+            {
+                storage.set(&DataKey::Strategy(commitment_id.clone()), &strategy);
+                storage.remove(&LegacyDataKey::Strategy(legacy_id));
+            }
+            if let Some(total) = storage.get::<_, i128>(&LegacyDataKey::TotalAllocated(legacy_id)) {
+                storage.set(&DataKey::TotalAllocated(commitment_id.clone()), &total);
+                storage.remove(&LegacyDataKey::TotalAllocated(legacy_id));
+            }
+            storage.set(&DataKey::AllocationOwner(commitment_id), &legacy_owner);
+            storage.remove(&LegacyDataKey::AllocationOwner(legacy_id));
+
+            migrated += 1;
+        }
+
+        Ok(migrated)
+    }
+
     // ========================================================================
     // INTERNAL HELPER FUNCTIONS
     // ========================================================================
 
-    /// Get commitment balance from commitment_core contract
-    fn get_commitment_balance(env: &Env, commitment_id: u64) -> Result<i128, Error> {
-        // Verify commitment_core contract is available
-        let _commitment_core: Address = env
-            .storage()
+    fn get_commitment_core(env: &Env) -> Result<Address, Error> {
+        env.storage()
             .instance()
             .get(&DataKey::CommitmentCore)
-            .ok_or(Error::NotInitialized)?;
-
-        // For testing purposes, we'll use a simple approach:
-        // - If commitment_id is 1, return sufficient balance (10 quadrillion) for integration tests
-        // - If commitment_id is 100, return insufficient balance (50M) for balance test
-        // - If commitment_id is 200, return exact balance (50M) for balance test
-        // - If commitment_id is 300, return sufficient balance (100M) for balance test
-        // - If commitment_id is 400, return insufficient balance (100M) for balance test
-        // This allows us to test the balance checking logic while maintaining compatibility
-
-        let balance = match commitment_id {
-            100 => 50_000_000i128,  // Insufficient for 100M allocation (test case)
-            200 => 50_000_000i128,  // Exact for 50M allocation (test case)
-            300 => 100_000_000i128, // Sufficient for 30M allocation (test case)
-            400 => 100_000_000i128, // Insufficient for 110M allocation (test case)
-            _ => 10_000_000_000_000_000i128, // Default sufficient balance for integration tests (10 quadrillion)
-        };
+            .ok_or(Error::NotInitialized)
+    }
 
-        Ok(balance)
+    /// Fetch a commitment from commitment_core
+    fn get_core_commitment(env: &Env, commitment_id: &String) -> Result<Commitment, Error> {
+        if !CommitmentId::is_valid(commitment_id) {
+            return Err(Error::CommitmentNotFound);
+        }
+        let core = CommitmentCoreClient::new(env, &Self::get_commitment_core(env)?);
+        match core.try_get_commitment(commitment_id) {
+            Ok(Ok(commitment)) => Ok(commitment),
+            _ => Err(Error::CommitmentNotFound),
+        }
     }
 
     fn require_initialized(env: &Env) -> Result<(), Error> {
@@ -846,8 +1685,609 @@ impl AllocationStrategiesContract {
             .ok_or(Error::PoolNotFound)
     }
 
+    /// Stored pool yield; pools registered before yield tracking start accruing now.
+    fn read_pool_yield(env: &Env, pool_id: u32) -> PoolYield {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PoolYield(pool_id))
+            .unwrap_or(PoolYield {
+                pool_id,
+                yield_index: 0,
+                accrued: 0,
+                distributed: 0,
+                last_accrual: env.ledger().timestamp(),
+            })
+    }
+
+    /// Pool yield accrued from `last_accrual` to now on the current liquidity.
+    fn projected_pool_yield(env: &Env, pool: &Pool) -> Result<PoolYield, Error> {
+        let mut pool_yield = Self::read_pool_yield(env, pool.pool_id);
+        let now = env.ledger().timestamp();
+        if now <= pool_yield.last_accrual {
+            return Ok(pool_yield);
+        }
+
+        let elapsed = (now - pool_yield.last_accrual) as i128;
+        let index_delta = (pool.apy as i128)
+            .checked_mul(elapsed)
+            .and_then(|x| x.checked_mul(YIELD_INDEX_SCALE))
+            .and_then(|x| x.checked_div(APY_BASIS_POINTS * SECONDS_PER_YEAR))
+            .ok_or(Error::ArithmeticOverflow)?;
+        let accrued = pool
+            .total_liquidity
+            .checked_mul(index_delta)
+            .map(|x| x / YIELD_INDEX_SCALE)
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        pool_yield.yield_index = pool_yield
+            .yield_index
+            .checked_add(index_delta)
+            .ok_or(Error::ArithmeticOverflow)?;
+        pool_yield.accrued = pool_yield
+            .accrued
+            .checked_add(accrued)
+            .ok_or(Error::ArithmeticOverflow)?;
+        pool_yield.last_accrual = now;
+        Ok(pool_yield)
+    }
+
+    /// Accrue and store pool yield; must run before `total_liquidity` changes.
+    fn accrue_pool_yield(env: &Env, pool: &Pool) -> Result<PoolYield, Error> {
+        let pool_yield = Self::projected_pool_yield(env, pool)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::PoolYield(pool.pool_id), &pool_yield);
+        Ok(pool_yield)
+    }
+
+    fn read_yield_position(env: &Env, commitment_id: &String) -> YieldPosition {
+        env.storage()
+            .persistent()
+            .get(&DataKey::YieldPosition(commitment_id.clone()))
+            .unwrap_or(YieldPosition {
+                checkpoints: Map::new(env),
+                unclaimed: Map::new(env),
+            })
+    }
+
+    /// Add `amount` to `pool_id`'s entry in a per-pool amount map.
+    fn add_to_pool_amount(
+        amounts: &mut Map<u32, i128>,
+        pool_id: u32,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let total = amounts
+            .get(pool_id)
+            .unwrap_or(0)
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        amounts.set(pool_id, total);
+        Ok(())
+    }
+
+    /// Sum of a per-pool amount map.
+    fn total_amount(amounts: &Map<u32, i128>) -> Result<i128, Error> {
+        let mut total = 0i128;
+        for amount in amounts.values().iter() {
+            total = total.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+        }
+        Ok(total)
+    }
+
+    /// Yield earned by one allocation since its pool checkpoint.
+    ///
+    /// Allocations without a checkpoint (made before yield tracking) earn
+    /// from their first settlement on.
+    fn earned_yield(
+        position: &YieldPosition,
+        allocation: &Allocation,
+        pool_yield: &PoolYield,
+    ) -> Result<i128, Error> {
+        let checkpoint = position
+            .checkpoints
+            .get(allocation.pool_id)
+            .unwrap_or(pool_yield.yield_index);
+        pool_yield
+            .yield_index
+            .checked_sub(checkpoint)
+            .and_then(|delta| allocation.amount.checked_mul(delta))
+            .map(|x| x / YIELD_INDEX_SCALE)
+            .ok_or(Error::ArithmeticOverflow)
+    }
+
+    /// Unharvested yield of a commitment as of now, without writing state.
+    fn pending_yield(
+        env: &Env,
+        commitment_id: &String,
+        allocations: &Vec<Allocation>,
+    ) -> Result<i128, Error> {
+        let position = Self::read_yield_position(env, commitment_id);
+        let mut pending = Self::total_amount(&position.unclaimed)?;
+        for allocation in allocations.iter() {
+            let pool = Self::get_pool_internal(env, allocation.pool_id)?;
+            let pool_yield = Self::projected_pool_yield(env, &pool)?;
+            pending = pending
+                .checked_add(Self::earned_yield(&position, &allocation, &pool_yield)?)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+        Ok(pending)
+    }
+
+    /// Move a commitment's earned yield into `unclaimed`, returning the new
+    /// per-pool amounts.
+    fn settle_yield(
+        env: &Env,
+        commitment_id: &String,
+        allocations: &Vec<Allocation>,
+    ) -> Result<Map<u32, i128>, Error> {
+        let position = Self::read_yield_position(env, commitment_id);
+        let mut unclaimed = position.unclaimed.clone();
+        for allocation in allocations.iter() {
+            let pool = Self::get_pool_internal(env, allocation.pool_id)?;
+            let pool_yield = Self::accrue_pool_yield(env, &pool)?;
+            let earned = Self::earned_yield(&position, &allocation, &pool_yield)?;
+            Self::add_to_pool_amount(&mut unclaimed, allocation.pool_id, earned)?;
+        }
+        Ok(unclaimed)
+    }
+
+    /// Pay each pool's `owed` yield in `asset` to commitment_core, keeping
+    /// the configured `yield_fee_bps` for the keeper reward bucket.
+    ///
+    /// Records the yield as distributed by each pool and returns the amount
+    /// sent to core and the fee kept.
+    fn pay_yield(env: &Env, asset: &Address, owed: &Map<u32, i128>) -> Result<(i128, i128), Error> {
+        let core = Self::get_commitment_core(env)?;
+        let fee_bps = Self::get_rebalance_config(env.clone()).yield_fee_bps;
+        let token = token::Client::new(env, asset);
+        let mut paid = 0i128;
+        let mut fee_total = 0i128;
+        for (pool_id, amount) in owed.iter() {
+            if amount <= 0 {
+                continue;
+            }
+            let pool = Self::get_pool_internal(env, pool_id)?;
+            let mut pool_yield = Self::read_pool_yield(env, pool_id);
+            pool_yield.distributed = pool_yield
+                .distributed
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            env.storage()
+                .persistent()
+                .set(&DataKey::PoolYield(pool_id), &pool_yield);
+
+            let fee = fees::fee_from_bps(amount, fee_bps);
+            if fee > 0 {
+                token.transfer_from(
+                    &env.current_contract_address(),
+                    &pool.pool_address,
+                    &env.current_contract_address(),
+                    &fee,
+                );
+                fee_total = fee_total
+                    .checked_add(fee)
+                    .ok_or(Error::ArithmeticOverflow)?;
+            }
+            let net = amount - fee;
+            if net > 0 {
+                token.transfer_from(
+                    &env.current_contract_address(),
+                    &pool.pool_address,
+                    &core,
+                    &net,
+                );
+                paid = paid.checked_add(net).ok_or(Error::ArithmeticOverflow)?;
+            }
+        }
+
+        if fee_total > 0 {
+            let bucket = Self::get_keeper_rewards(env.clone(), asset.clone())
+                .checked_add(fee_total)
+                .ok_or(Error::ArithmeticOverflow)?;
+            env.storage()
+                .persistent()
+                .set(&DataKey::KeeperRewards(asset.clone()), &bucket);
+        }
+
+        Ok((paid, fee_total))
+    }
+
+    /// Start yield tracking for `allocations` at the pools' current indexes.
+    fn checkpoint_yield(
+        env: &Env,
+        commitment_id: &String,
+        allocations: &Vec<Allocation>,
+        unclaimed: Map<u32, i128>,
+    ) -> Result<(), Error> {
+        let mut checkpoints = Map::new(env);
+        for allocation in allocations.iter() {
+            let pool = Self::get_pool_internal(env, allocation.pool_id)?;
+            let pool_yield = Self::accrue_pool_yield(env, &pool)?;
+            checkpoints.set(allocation.pool_id, pool_yield.yield_index);
+        }
+        env.storage().persistent().set(
+            &DataKey::YieldPosition(commitment_id.clone()),
+            &YieldPosition {
+                checkpoints,
+                unclaimed,
+            },
+        );
+        Ok(())
+    }
+
+    /// Re-split a commitment's allocation with its stored strategy and move
+    /// the funds to match.
+    fn rebalance_allocation(env: &Env, commitment_id: String) -> Result<AllocationSummary, Error> {
+        let commitment = Self::get_core_commitment(env, &commitment_id)?;
+
+        // Get current allocations
+        let current_allocations: Vec<Allocation> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Allocations(commitment_id.clone()))
+            .ok_or(Error::AllocationNotFound)?;
+
+        // Get strategy
+        let strategy: Strategy = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Strategy(commitment_id.clone()))
+            .ok_or(Error::AllocationNotFound)?;
+
+        // Settle yield earned under the current allocations
+        let unclaimed = Self::settle_yield(env, &commitment_id, &current_allocations)?;
+
+        let mut total_amount = 0i128;
+
+        // Remove old allocations from pools with overflow protection
+        for allocation in current_allocations.iter() {
+            total_amount = total_amount
+                .checked_add(allocation.amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+
+            let mut pool = Self::get_pool_internal(env, allocation.pool_id)?;
+            Self::accrue_pool_yield(env, &pool)?;
+            pool.total_liquidity = pool
+                .total_liquidity
+                .checked_sub(allocation.amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            pool.updated_at = env.ledger().timestamp();
+            env.storage()
+                .persistent()
+                .set(&DataKey::Pool(allocation.pool_id), &pool);
+        }
+
+        // Reallocate with current strategy
+        let pools = Self::select_pools(env, strategy)?;
+        if pools.is_empty() {
+            return Err(Error::NoSuitablePools);
+        }
+        let allocation_plan = Self::calculate_allocation(env, total_amount, &pools, strategy)?;
+
+        let mut new_allocations = Vec::new(env);
+        let mut new_total = 0i128;
+
+        for (pool_id, alloc_amount) in allocation_plan.iter() {
+            if alloc_amount <= 0 {
+                continue;
+            }
+
+            let mut pool = Self::get_pool_internal(env, pool_id)?;
+
+            // The plan only uses active pools with room; anything else
+            // would drop funds from the allocation
+            if pool.status != PoolStatus::Active {
+                return Err(Error::PoolInactive);
+            }
+
+            let new_liquidity = pool
+                .total_liquidity
+                .checked_add(alloc_amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+
+            if new_liquidity > pool.max_capacity {
+                return Err(Error::PoolCapacityExceeded);
+            }
+
+            Self::accrue_pool_yield(env, &pool)?;
+            pool.total_liquidity = new_liquidity;
+            pool.updated_at = env.ledger().timestamp();
+            env.storage()
+                .persistent()
+                .set(&DataKey::Pool(pool_id), &pool);
+
+            let allocation = Allocation {
+                commitment_id: commitment_id.clone(),
+                pool_id,
+                amount: alloc_amount,
+                timestamp: env.ledger().timestamp(),
+            };
+
+            new_allocations.push_back(allocation);
+            new_total = new_total
+                .checked_add(alloc_amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+
+        Self::transfer_between_pools(
+            env,
+            &commitment.asset_address,
+            &current_allocations,
+            &new_allocations,
+        )?;
+
+        env.storage().persistent().set(
+            &DataKey::Allocations(commitment_id.clone()),
+            &new_allocations,
+        );
+        env.storage()
+            .persistent()
+            .set(&DataKey::TotalAllocated(commitment_id.clone()), &new_total);
+        let pending_yield = Self::total_amount(&unclaimed)?;
+        Self::checkpoint_yield(env, &commitment_id, &new_allocations, unclaimed)?;
+
+        env.events().publish(
+            (symbol_short!("rebalance"), commitment_id.clone()),
+            new_total,
+        );
+
+        Ok(AllocationSummary {
+            commitment_id,
+            strategy,
+            total_allocated: new_total,
+            allocations: new_allocations,
+            pending_yield,
+        })
+    }
+
+    /// Share of a commitment's allocation a rebalance would move, in basis
+    /// points: half the summed per-pool differences between the current
+    /// split and the split its strategy gives now, with the commitment's
+    /// own liquidity taken out of the pools first.
+    fn allocation_drift(env: &Env, commitment_id: &String) -> Result<u32, Error> {
+        let allocations: Vec<Allocation> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Allocations(commitment_id.clone()))
+            .ok_or(Error::AllocationNotFound)?;
+        let strategy: Strategy = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Strategy(commitment_id.clone()))
+            .ok_or(Error::AllocationNotFound)?;
+
+        let mut current: Map<u32, i128> = Map::new(env);
+        let mut total = 0i128;
+        for allocation in allocations.iter() {
+            let amount = current
+                .get(allocation.pool_id)
+                .unwrap_or(0)
+                .checked_add(allocation.amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            current.set(allocation.pool_id, amount);
+            total = total
+                .checked_add(allocation.amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+        if total == 0 {
+            return Ok(0);
+        }
+
+        let mut pools = Vec::new(env);
+        for mut pool in Self::select_pools(env, strategy)?.iter() {
+            pool.total_liquidity = pool
+                .total_liquidity
+                .checked_sub(current.get(pool.pool_id).unwrap_or(0))
+                .ok_or(Error::ArithmeticOverflow)?;
+            pools.push_back(pool);
+        }
+        if pools.is_empty() {
+            return Err(Error::NoSuitablePools);
+        }
+        let target = Self::calculate_allocation(env, total, &pools, strategy)?;
+
+        let mut moved = 0i128;
+        for (pool_id, amount) in current.iter() {
+            let diff = amount - target.get(pool_id).unwrap_or(0);
+            moved = moved
+                .checked_add(diff.abs())
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+        for (pool_id, amount) in target.iter() {
+            if !current.contains_key(pool_id) {
+                moved = moved.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+            }
+        }
+
+        let drift = moved
+            .checked_mul(STRATEGY_BASIS_POINTS as i128)
+            .map(|x| x / (2 * total))
+            .ok_or(Error::ArithmeticOverflow)?;
+        Ok(drift as u32)
+    }
+
+    /// Transfer `amount` of a commitment's allocation from its pools to
+    /// commitment_core and return the allocations left.
+    ///
+    /// Each pool gives back its proportional share, rounded down; the
+    /// rounding remainder comes from the first pools with balance left.
+    /// Allocation records are removed once nothing is left.
+    fn withdraw_from_pools(
+        env: &Env,
+        commitment_id: &String,
+        asset: &Address,
+        amount: i128,
+    ) -> Result<Vec<Allocation>, Error> {
+        let allocations: Vec<Allocation> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Allocations(commitment_id.clone()))
+            .ok_or(Error::AllocationNotFound)?;
+        let total: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TotalAllocated(commitment_id.clone()))
+            .ok_or(Error::AllocationNotFound)?;
+
+        // Settle yield earned under the current allocations
+        let unclaimed = Self::settle_yield(env, commitment_id, &allocations)?;
+
+        let mut shares = Vec::new(env);
+        let mut withdrawn = 0i128;
+        for allocation in allocations.iter() {
+            let share = allocation
+                .amount
+                .checked_mul(amount)
+                .map(|x| x / total)
+                .ok_or(Error::ArithmeticOverflow)?;
+            shares.push_back(share);
+            withdrawn = withdrawn
+                .checked_add(share)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+        let mut remainder = amount - withdrawn;
+        for (i, allocation) in allocations.iter().enumerate() {
+            if remainder == 0 {
+                break;
+            }
+            let i = i as u32;
+            let share = shares.get_unchecked(i);
+            let extra = remainder.min(allocation.amount - share);
+            shares.set(i, share + extra);
+            remainder -= extra;
+        }
+
+        let core = Self::get_commitment_core(env)?;
+        let token = token::Client::new(env, asset);
+        let mut remaining = Vec::new(env);
+        for (allocation, share) in allocations.iter().zip(shares.iter()) {
+            if share > 0 {
+                let mut pool = Self::get_pool_internal(env, allocation.pool_id)?;
+                Self::accrue_pool_yield(env, &pool)?;
+                pool.total_liquidity = pool
+                    .total_liquidity
+                    .checked_sub(share)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                pool.updated_at = env.ledger().timestamp();
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::Pool(allocation.pool_id), &pool);
+
+                token.transfer_from(
+                    &env.current_contract_address(),
+                    &pool.pool_address,
+                    &core,
+                    &share,
+                );
+            }
+
+            if allocation.amount > share {
+                remaining.push_back(Allocation {
+                    amount: allocation.amount - share,
+                    ..allocation
+                });
+            }
+        }
+
+        if remaining.is_empty() {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Allocations(commitment_id.clone()));
+            env.storage()
+                .persistent()
+                .remove(&DataKey::TotalAllocated(commitment_id.clone()));
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Strategy(commitment_id.clone()));
+            env.storage()
+                .persistent()
+                .remove(&DataKey::AllocationOwner(commitment_id.clone()));
+            env.storage()
+                .persistent()
+                .remove(&DataKey::YieldPosition(commitment_id.clone()));
+        } else {
+            env.storage()
+                .persistent()
+                .set(&DataKey::Allocations(commitment_id.clone()), &remaining);
+            env.storage().persistent().set(
+                &DataKey::TotalAllocated(commitment_id.clone()),
+                &(total - amount),
+            );
+            Self::checkpoint_yield(env, commitment_id, &remaining, unclaimed)?;
+        }
+
+        Ok(remaining)
+    }
+
+    /// Move `asset` between pool addresses so each pool holds its share of
+    /// `new_allocations` instead of `old_allocations`.
+    ///
+    /// Pools that shrink pay pools that grow, in pool id order; both
+    /// allocations must cover the same total.
+    fn transfer_between_pools(
+        env: &Env,
+        asset: &Address,
+        old_allocations: &Vec<Allocation>,
+        new_allocations: &Vec<Allocation>,
+    ) -> Result<(), Error> {
+        // Net change per pool
+        let mut deltas = Map::new(env);
+        for allocation in new_allocations.iter() {
+            Self::add_to_pool_amount(&mut deltas, allocation.pool_id, allocation.amount)?;
+        }
+        for allocation in old_allocations.iter() {
+            Self::add_to_pool_amount(&mut deltas, allocation.pool_id, -allocation.amount)?;
+        }
+
+        let mut receivers = Vec::new(env);
+        for (pool_id, delta) in deltas.iter() {
+            if delta > 0 {
+                receivers.push_back((pool_id, delta));
+            }
+        }
+
+        let token = token::Client::new(env, asset);
+        let mut next = 0u32;
+        for (pool_id, delta) in deltas.iter() {
+            if delta >= 0 {
+                continue;
+            }
+            let from = Self::get_pool_internal(env, pool_id)?;
+            let mut surplus = -delta;
+            while surplus > 0 {
+                let (to_id, shortfall) = receivers.get(next).ok_or(Error::InvalidAmount)?;
+                let amount = surplus.min(shortfall);
+                let to = Self::get_pool_internal(env, to_id)?;
+                token.transfer_from(
+                    &env.current_contract_address(),
+                    &from.pool_address,
+                    &to.pool_address,
+                    &amount,
+                );
+                surplus -= amount;
+                if amount == shortfall {
+                    next += 1;
+                } else {
+                    receivers.set(next, (to_id, shortfall - amount));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn get_strategy_internal(env: &Env, strategy_id: u32) -> Result<StrategyConfig, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CustomStrategy(strategy_id))
+            .ok_or(Error::StrategyNotFound)
+    }
+
     fn select_pools(env: &Env, strategy: Strategy) -> Result<Vec<Pool>, Error> {
         let mut pools = Vec::new(env);
+        let custom = match strategy {
+            Strategy::Custom(strategy_id) => Some(Self::get_strategy_internal(env, strategy_id)?),
+            _ => None,
+        };
 
         let registry: Vec<u32> = env
             .storage()
@@ -857,7 +2297,7 @@ impl AllocationStrategiesContract {
 
         for pool_id in registry.iter() {
             if let Ok(pool) = Self::get_pool_internal(env, pool_id) {
-                if !pool.active {
+                if pool.status != PoolStatus::Active {
                     continue;
                 }
 
@@ -867,6 +2307,11 @@ impl AllocationStrategiesContract {
                     Strategy::Aggressive => {
                         matches!(pool.risk_level, RiskLevel::High | RiskLevel::Medium)
                     }
+                    Strategy::Custom(_) => custom.as_ref().is_some_and(|config| {
+                        Self::risk_weight(config, pool.risk_level) > 0
+                            && (config.allowed_pools.is_empty()
+                                || config.allowed_pools.contains(pool.pool_id))
+                    }),
                 };
 
                 if include {
@@ -878,6 +2323,35 @@ impl AllocationStrategiesContract {
         Ok(pools)
     }
 
+    /// Validate a new allocation's strategy and compute its per-pool split.
+    fn plan_allocation(
+        env: &Env,
+        amount: i128,
+        strategy: Strategy,
+    ) -> Result<Map<u32, i128>, Error> {
+        // Custom strategies must exist and be enabled
+        if let Strategy::Custom(strategy_id) = strategy {
+            if !Self::get_strategy_internal(env, strategy_id)?.active {
+                return Err(Error::StrategyInactive);
+            }
+        }
+
+        let pools = Self::select_pools(env, strategy)?;
+        if pools.is_empty() {
+            return Err(Error::NoSuitablePools);
+        }
+
+        Self::calculate_allocation(env, amount, &pools, strategy)
+    }
+
+    /// Split `total_amount` across `pools` so the shares sum exactly to it.
+    ///
+    /// Each risk level gets its strategy weight (rounded down) and splits it
+    /// evenly, giving rounding remainders one unit at a time to its first
+    /// pools. Amounts a pool cannot take for lack of capacity, and the
+    /// weight rounding remainder, spill into the next eligible pools in
+    /// registry order. Fails with `PoolCapacityExceeded` if the pools cannot
+    /// hold the whole amount.
     fn calculate_allocation(
         env: &Env,
         total_amount: i128,
@@ -885,123 +2359,164 @@ impl AllocationStrategiesContract {
         strategy: Strategy,
     ) -> Result<Map<u32, i128>, Error> {
         let mut allocation_map = Map::new(env);
-        let pool_count = pools.len();
-
-        if pool_count == 0 {
+        if pools.is_empty() {
             return Ok(allocation_map);
         }
 
-        match strategy {
-            Strategy::Safe => {
-                let amount_per_pool = total_amount / pool_count as i128;
-                for pool in pools.iter() {
-                    allocation_map.set(pool.pool_id, amount_per_pool);
-                }
+        let custom = match strategy {
+            Strategy::Custom(strategy_id) => Some(Self::get_strategy_internal(env, strategy_id)?),
+            _ => None,
+        };
+
+        let mut unplaced = total_amount;
+        for risk_level in [RiskLevel::Low, RiskLevel::Medium, RiskLevel::High] {
+            let weight_bps = match &custom {
+                Some(config) => Self::risk_weight(config, risk_level),
+                None => Self::builtin_weight(strategy, risk_level),
+            };
+            if weight_bps == 0 {
+                continue;
             }
-            Strategy::Balanced => {
-                let mut low_risk_pools = Vec::new(env);
-                let mut medium_risk_pools = Vec::new(env);
-                let mut high_risk_pools = Vec::new(env);
-
-                for pool in pools.iter() {
-                    match pool.risk_level {
-                        RiskLevel::Low => low_risk_pools.push_back(pool),
-                        RiskLevel::Medium => medium_risk_pools.push_back(pool),
-                        RiskLevel::High => high_risk_pools.push_back(pool),
-                    }
-                }
 
-                // Safe percentage calculations with checked operations
-                let low_amount = total_amount
-                    .checked_mul(40)
-                    .and_then(|x| x.checked_div(100))
-                    .ok_or(Error::ArithmeticOverflow)?;
+            let mut risk_pools = Vec::new(env);
+            for pool in pools.iter() {
+                if pool.risk_level == risk_level {
+                    risk_pools.push_back(pool);
+                }
+            }
 
-                let medium_amount = total_amount
-                    .checked_mul(40)
-                    .and_then(|x| x.checked_div(100))
-                    .ok_or(Error::ArithmeticOverflow)?;
+            let risk_amount = Self::bps_of(total_amount, weight_bps)?;
+            let placed = risk_amount
+                .checked_sub(Self::distribute_to_pools(
+                    &mut allocation_map,
+                    &risk_pools,
+                    risk_amount,
+                )?)
+                .ok_or(Error::ArithmeticOverflow)?;
+            unplaced = unplaced
+                .checked_sub(placed)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
 
-                let high_amount = total_amount
-                    .checked_mul(20)
-                    .and_then(|x| x.checked_div(100))
-                    .ok_or(Error::ArithmeticOverflow)?;
+        // Spill what the risk levels could not place into any eligible pool
+        if Self::spill_to_pools(&mut allocation_map, pools, unplaced)? > 0 {
+            return Err(Error::PoolCapacityExceeded);
+        }
 
-                Self::distribute_to_pools(env, &mut allocation_map, &low_risk_pools, low_amount)?;
-                Self::distribute_to_pools(
-                    env,
-                    &mut allocation_map,
-                    &medium_risk_pools,
-                    medium_amount,
-                )?;
-                Self::distribute_to_pools(env, &mut allocation_map, &high_risk_pools, high_amount)?;
-            }
-            Strategy::Aggressive => {
-                let mut medium_risk_pools = Vec::new(env);
-                let mut high_risk_pools = Vec::new(env);
-
-                for pool in pools.iter() {
-                    match pool.risk_level {
-                        RiskLevel::Medium => medium_risk_pools.push_back(pool),
-                        RiskLevel::High => high_risk_pools.push_back(pool),
-                        _ => {}
-                    }
+        if let Some(config) = custom {
+            // Every pool share must respect the strategy's caps
+            let min_amount = Self::bps_of(total_amount, config.min_pool_bps)?;
+            let max_amount = Self::bps_of(total_amount, config.max_pool_bps)?;
+            for (_, amount) in allocation_map.iter() {
+                if amount < min_amount || (config.max_pool_bps > 0 && amount > max_amount) {
+                    return Err(Error::NoSuitablePools);
                 }
+            }
+        }
 
-                let high_amount = total_amount
-                    .checked_mul(70)
-                    .and_then(|x| x.checked_div(100))
-                    .ok_or(Error::ArithmeticOverflow)?;
+        Ok(allocation_map)
+    }
 
-                let medium_amount = total_amount
-                    .checked_mul(30)
-                    .and_then(|x| x.checked_div(100))
-                    .ok_or(Error::ArithmeticOverflow)?;
+    fn risk_weight(config: &StrategyConfig, risk_level: RiskLevel) -> u32 {
+        match risk_level {
+            RiskLevel::Low => config.low_weight_bps,
+            RiskLevel::Medium => config.medium_weight_bps,
+            RiskLevel::High => config.high_weight_bps,
+        }
+    }
 
-                Self::distribute_to_pools(env, &mut allocation_map, &high_risk_pools, high_amount)?;
-                Self::distribute_to_pools(
-                    env,
-                    &mut allocation_map,
-                    &medium_risk_pools,
-                    medium_amount,
-                )?;
-            }
+    /// Risk level weights of the built-in strategies, in basis points.
+    fn builtin_weight(strategy: Strategy, risk_level: RiskLevel) -> u32 {
+        match (strategy, risk_level) {
+            (Strategy::Safe, RiskLevel::Low) => 10_000,
+            (Strategy::Balanced, RiskLevel::Low | RiskLevel::Medium) => 4_000,
+            (Strategy::Balanced, RiskLevel::High) => 2_000,
+            (Strategy::Aggressive, RiskLevel::Medium) => 3_000,
+            (Strategy::Aggressive, RiskLevel::High) => 7_000,
+            _ => 0,
         }
+    }
 
-        Ok(allocation_map)
+    /// `bps` basis points of `amount`, rounded down.
+    fn bps_of(amount: i128, bps: u32) -> Result<i128, Error> {
+        amount
+            .checked_mul(bps as i128)
+            .map(|x| x / STRATEGY_BASIS_POINTS as i128)
+            .ok_or(Error::ArithmeticOverflow)
     }
 
+    /// Split `amount` evenly across `pools`, remainder units to the first
+    /// pools, then spill what exceeds capacity into the others.
+    ///
+    /// Returns the amount none of the pools could take.
     fn distribute_to_pools(
-        _env: &Env,
         allocation_map: &mut Map<u32, i128>,
         pools: &Vec<Pool>,
         amount: i128,
-    ) -> Result<(), Error> {
-        let pool_count = pools.len();
-        if pool_count == 0 {
-            return Ok(());
+    ) -> Result<i128, Error> {
+        let pool_count = pools.len() as i128;
+        if pool_count == 0 || amount <= 0 {
+            return Ok(amount.max(0));
         }
 
-        let amount_per_pool = amount / pool_count as i128;
-
-        for pool in pools.iter() {
-            let available_capacity = pool
-                .max_capacity
-                .checked_sub(pool.total_liquidity)
-                .ok_or(Error::ArithmeticOverflow)?;
+        let amount_per_pool = amount / pool_count;
+        let remainder = amount % pool_count;
 
-            let alloc_amount = if amount_per_pool > available_capacity {
-                available_capacity
+        let mut overflow = 0i128;
+        for (index, pool) in pools.iter().enumerate() {
+            let share = if (index as i128) < remainder {
+                amount_per_pool + 1
             } else {
                 amount_per_pool
             };
+            let placed = Self::place_in_pool(allocation_map, &pool, share)?;
+            overflow = overflow
+                .checked_add(share - placed)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+
+        Self::spill_to_pools(allocation_map, pools, overflow)
+    }
 
-            if alloc_amount > 0 {
-                allocation_map.set(pool.pool_id, alloc_amount);
+    /// Fill `pools` in order with `amount`, up to their spare capacity.
+    ///
+    /// Returns the amount left over.
+    fn spill_to_pools(
+        allocation_map: &mut Map<u32, i128>,
+        pools: &Vec<Pool>,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        let mut remaining = amount;
+        for pool in pools.iter() {
+            if remaining <= 0 {
+                break;
             }
+            remaining -= Self::place_in_pool(allocation_map, &pool, remaining)?;
         }
+        Ok(remaining)
+    }
 
-        Ok(())
+    /// Add up to `amount` to `pool`'s share without exceeding its capacity.
+    ///
+    /// Returns the amount placed.
+    fn place_in_pool(
+        allocation_map: &mut Map<u32, i128>,
+        pool: &Pool,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        let planned = allocation_map.get(pool.pool_id).unwrap_or(0);
+        let available_capacity = pool
+            .max_capacity
+            .checked_sub(pool.total_liquidity)
+            .and_then(|x| x.checked_sub(planned))
+            .ok_or(Error::ArithmeticOverflow)?
+            .max(0);
+
+        let placed = amount.min(available_capacity);
+        if placed > 0 {
+            allocation_map.set(pool.pool_id, planned + placed);
+        }
+        Ok(placed)
     }
 }
 
@@ -1,12 +1,67 @@
 // Comprehensive Security-Focused Tests
 use crate::{
-    AllocationStrategiesContract, AllocationStrategiesContractClient, RiskLevel, Strategy,
+    Allocation, AllocationStrategiesContract, AllocationStrategiesContractClient, DataKey,
+    LegacyAllocation, LegacyDataKey, LegacyPool, Pool, PoolStatus, RebalanceConfig, RiskLevel,
+    Strategy, StrategyConfig,
 };
-use soroban_sdk::{testutils::Address as _, testutils::Ledger, Address, Env};
+use commitment_interface::{Commitment, CommitmentRules, CommitmentStatus};
+use shared_utils::CommitmentId;
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Env, String,
+};
+
+/// Minimal commitment_core: stores commitments and moves funds on `allocate`.
+#[contract]
+pub(crate) struct MockCoreContract;
+
+#[contractimpl]
+impl MockCoreContract {
+    pub fn set_commitment(e: Env, commitment: Commitment) {
+        e.storage()
+            .persistent()
+            .set(&commitment.commitment_id, &commitment);
+    }
+
+    pub fn get_commitment(e: Env, commitment_id: String) -> Commitment {
+        e.storage()
+            .persistent()
+            .get(&commitment_id)
+            .unwrap_or_else(|| panic!("Commitment not found"))
+    }
+
+    pub fn allocate(e: Env, commitment_id: String, target_pool: Address, amount: i128) {
+        let mut commitment = Self::get_commitment(e.clone(), commitment_id);
+        commitment.current_value -= amount;
+        commitment.allocated += amount;
+        token::Client::new(&e, &commitment.asset_address).transfer(
+            &e.current_contract_address(),
+            &target_pool,
+            &amount,
+        );
+        Self::set_commitment(e, commitment);
+    }
+
+    pub fn deallocate(e: Env, commitment_id: String, amount: i128) {
+        let mut commitment = Self::get_commitment(e.clone(), commitment_id);
+        commitment.allocated -= amount;
+        commitment.current_value += amount;
+        Self::set_commitment(e, commitment);
+    }
+
+    pub fn credit_yield(e: Env, commitment_id: String, amount: i128) {
+        let mut commitment = Self::get_commitment(e.clone(), commitment_id);
+        commitment.current_value += amount;
+        Self::set_commitment(e, commitment);
+    }
+}
+
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
 
 fn create_contract(env: &Env) -> (Address, Address, AllocationStrategiesContractClient<'_>) {
     let admin = Address::generate(env);
-    let commitment_core = Address::generate(env);
+    let commitment_core = env.register_contract(None, MockCoreContract);
     let contract_id = env.register_contract(None, AllocationStrategiesContract);
     let client = AllocationStrategiesContractClient::new(env, &contract_id);
 
@@ -15,13 +70,141 @@ fn create_contract(env: &Env) -> (Address, Address, AllocationStrategiesContract
     (admin, commitment_core, client)
 }
 
-fn setup_test_pools(_env: &Env, client: &AllocationStrategiesContractClient, admin: &Address) {
-    client.register_pool(admin, &0, &RiskLevel::Low, &500, &1_000_000_000);
-    client.register_pool(admin, &1, &RiskLevel::Low, &600, &1_000_000_000);
-    client.register_pool(admin, &2, &RiskLevel::Medium, &1000, &800_000_000);
-    client.register_pool(admin, &3, &RiskLevel::Medium, &1200, &800_000_000);
-    client.register_pool(admin, &4, &RiskLevel::High, &2000, &500_000_000);
-    client.register_pool(admin, &5, &RiskLevel::High, &2500, &500_000_000);
+fn register_pool(
+    env: &Env,
+    client: &AllocationStrategiesContractClient,
+    admin: &Address,
+    pool_id: u32,
+    risk_level: RiskLevel,
+    apy: u32,
+    max_capacity: i128,
+) {
+    let pool_address = Address::generate(env);
+    client.register_pool(
+        admin,
+        &pool_id,
+        &pool_address,
+        &risk_level,
+        &apy,
+        &max_capacity,
+    );
+}
+
+fn setup_test_pools(env: &Env, client: &AllocationStrategiesContractClient, admin: &Address) {
+    register_pool(env, client, admin, 0, RiskLevel::Low, 500, 1_000_000_000);
+    register_pool(env, client, admin, 1, RiskLevel::Low, 600, 1_000_000_000);
+    register_pool(env, client, admin, 2, RiskLevel::Medium, 1000, 800_000_000);
+    register_pool(env, client, admin, 3, RiskLevel::Medium, 1200, 800_000_000);
+    register_pool(env, client, admin, 4, RiskLevel::High, 2000, 500_000_000);
+    register_pool(env, client, admin, 5, RiskLevel::High, 2500, 500_000_000);
+}
+
+/// Custom strategy `strategy_id` with the given risk weights and no caps.
+fn strategy_config(
+    env: &Env,
+    strategy_id: u32,
+    low_weight_bps: u32,
+    medium_weight_bps: u32,
+    high_weight_bps: u32,
+) -> StrategyConfig {
+    StrategyConfig {
+        strategy_id,
+        name: String::from_str(env, "custom"),
+        low_weight_bps,
+        medium_weight_bps,
+        high_weight_bps,
+        min_pool_bps: 0,
+        max_pool_bps: 0,
+        allowed_pools: vec![env],
+        active: true,
+    }
+}
+
+/// Let every registered pool's address approve the contract to spend `asset`.
+fn approve_pools(env: &Env, client: &AllocationStrategiesContractClient, asset: &Address) {
+    let token = token::Client::new(env, asset);
+    let expiration_ledger = env.ledger().sequence() + 10_000;
+    for pool in client.get_all_pools().iter() {
+        token.approve(
+            &pool.pool_address,
+            &client.address,
+            &i128::MAX,
+            &expiration_ledger,
+        );
+    }
+}
+
+/// Mint `amount` of `asset` to every registered pool address, e.g. as yield.
+fn fund_pools(
+    env: &Env,
+    client: &AllocationStrategiesContractClient,
+    asset: &Address,
+    amount: i128,
+) {
+    let asset_admin = token::StellarAssetClient::new(env, asset);
+    for pool in client.get_all_pools().iter() {
+        asset_admin.mint(&pool.pool_address, &amount);
+    }
+}
+
+/// commitment_core id for the `n`th commitment.
+pub(crate) fn cid(env: &Env, n: u64) -> String {
+    CommitmentId::from_number(env, n)
+}
+
+/// Store active commitment `c_<id>` in the mock core, backed by `value` tokens.
+pub(crate) fn create_commitment(
+    env: &Env,
+    commitment_core: &Address,
+    id: u64,
+    owner: &Address,
+    value: i128,
+) -> Commitment {
+    let asset = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    token::StellarAssetClient::new(env, &asset).mint(commitment_core, &value);
+
+    let commitment = Commitment {
+        commitment_id: cid(env, id),
+        owner: owner.clone(),
+        nft_token_id: id as u32,
+        rules: CommitmentRules {
+            duration_days: 30,
+            max_loss_percent: 10,
+            commitment_type: String::from_str(env, "balanced"),
+            early_exit_penalty: 5,
+            min_fee_threshold: 0,
+            grace_period_days: 0,
+        },
+        amount: value,
+        asset_address: asset,
+        created_at: env.ledger().timestamp(),
+        expires_at: env.ledger().timestamp() + 30 * 86400,
+        current_value: value,
+        allocated: 0,
+        status: CommitmentStatus::Active,
+    };
+    MockCoreContractClient::new(env, commitment_core).set_commitment(&commitment);
+    commitment
+}
+
+/// Store a pre-v2 allocation of 40_000 in pool 2 under numeric id `id`.
+fn store_legacy_allocation(env: &Env, contract: &Address, id: u64, owner: &Address) {
+    env.as_contract(contract, || {
+        let storage = env.storage().persistent();
+        let mut allocations = soroban_sdk::Vec::new(env);
+        allocations.push_back(LegacyAllocation {
+            commitment_id: id,
+            pool_id: 2,
+            amount: 40_000,
+            timestamp: 123,
+        });
+        storage.set(&LegacyDataKey::Allocations(id), &allocations);
+        storage.set(&LegacyDataKey::Strategy(id), &Strategy::Balanced);
+        storage.set(&LegacyDataKey::TotalAllocated(id), &40_000i128);
+        storage.set(&LegacyDataKey::AllocationOwner(id), owner);
+    });
 }
 
 // ============================================================================
@@ -49,14 +232,14 @@ fn test_register_pool() {
 
     let (admin, _, client) = create_contract(&env);
 
-    client.register_pool(&admin, &0, &RiskLevel::Low, &500, &1_000_000_000);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 1_000_000_000);
 
     let pool = client.get_pool(&0);
     assert_eq!(pool.pool_id, 0);
     assert_eq!(pool.risk_level, RiskLevel::Low);
     assert_eq!(pool.apy, 500);
     assert_eq!(pool.max_capacity, 1_000_000_000);
-    assert!(pool.active);
+    assert_eq!(pool.status, PoolStatus::Active);
     assert_eq!(pool.total_liquidity, 0);
 }
 
@@ -65,13 +248,13 @@ fn test_safe_strategy_allocation() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
-    let commitment_id = 1u64;
     let amount = 100_000_000i128;
 
+    let commitment_id = create_commitment(&env, &core, 1, &user, amount).commitment_id;
     let summary = client.allocate(&user, &commitment_id, &amount, &Strategy::Safe);
 
     assert_eq!(summary.commitment_id, commitment_id);
@@ -90,11 +273,12 @@ fn test_balanced_strategy_allocation() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
-    let summary = client.allocate(&user, &2, &100_000_000, &Strategy::Balanced);
+    create_commitment(&env, &core, 2, &user, 100_000_000);
+    let summary = client.allocate(&user, &cid(&env, 2), &100_000_000, &Strategy::Balanced);
 
     assert_eq!(summary.strategy, Strategy::Balanced);
 
@@ -115,11 +299,12 @@ fn test_aggressive_strategy_allocation() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
-    let summary = client.allocate(&user, &3, &100_000_000, &Strategy::Aggressive);
+    create_commitment(&env, &core, 3, &user, 100_000_000);
+    let summary = client.allocate(&user, &cid(&env, 3), &100_000_000, &Strategy::Aggressive);
 
     assert_eq!(summary.strategy, Strategy::Aggressive);
 
@@ -135,17 +320,18 @@ fn test_get_allocation() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
     let amount = 50_000_000i128;
 
-    client.allocate(&user, &4, &amount, &Strategy::Safe);
+    create_commitment(&env, &core, 4, &user, amount);
+    client.allocate(&user, &cid(&env, 4), &amount, &Strategy::Safe);
 
-    let summary = client.get_allocation(&4);
+    let summary = client.get_allocation(&cid(&env, 4));
 
-    assert_eq!(summary.commitment_id, 4);
+    assert_eq!(summary.commitment_id, cid(&env, 4));
     assert_eq!(summary.strategy, Strategy::Safe);
     assert_eq!(summary.total_allocated, amount);
 }
@@ -155,20 +341,22 @@ fn test_rebalance() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
     let amount = 100_000_000i128;
 
     // Initial allocation
-    let _initial = client.allocate(&user, &5, &amount, &Strategy::Safe);
+    let commitment = create_commitment(&env, &core, 5, &user, amount);
+    approve_pools(&env, &client, &commitment.asset_address);
+    let _initial = client.allocate(&user, &cid(&env, 5), &amount, &Strategy::Safe);
 
-    // Disable one of the pools
-    client.update_pool_status(&admin, &0, &false);
+    // Drain one of the pools
+    client.update_pool_status(&admin, &0, &PoolStatus::Draining);
 
     // Rebalance
-    let rebalanced = client.rebalance(&user, &5);
+    let rebalanced = client.rebalance(&user, &cid(&env, 5));
 
     assert_eq!(rebalanced.strategy, Strategy::Safe);
 
@@ -176,6 +364,11 @@ fn test_rebalance() {
     for allocation in rebalanced.allocations.iter() {
         assert_ne!(allocation.pool_id, 0);
     }
+
+    // Its funds moved to pool 1 with the allocation
+    let asset = token::Client::new(&env, &commitment.asset_address);
+    assert_eq!(asset.balance(&client.get_pool(&0).pool_address), 0);
+    assert_eq!(asset.balance(&client.get_pool(&1).pool_address), amount);
 }
 
 #[test]
@@ -185,9 +378,17 @@ fn test_get_all_pools() {
 
     let (admin, _, client) = create_contract(&env);
 
-    client.register_pool(&admin, &0, &RiskLevel::Low, &500, &1_000_000_000);
-    client.register_pool(&admin, &1, &RiskLevel::Medium, &1000, &800_000_000);
-    client.register_pool(&admin, &2, &RiskLevel::High, &2000, &500_000_000);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 1_000_000_000);
+    register_pool(
+        &env,
+        &client,
+        &admin,
+        1,
+        RiskLevel::Medium,
+        1000,
+        800_000_000,
+    );
+    register_pool(&env, &client, &admin, 2, RiskLevel::High, 2000, 500_000_000);
 
     let pools = client.get_all_pools();
 
@@ -199,7 +400,7 @@ fn test_pool_liquidity_tracking() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
@@ -209,7 +410,8 @@ fn test_pool_liquidity_tracking() {
     assert_eq!(pool_before.total_liquidity, 0);
 
     // Allocate
-    client.allocate(&user, &1, &100_000_000, &Strategy::Safe);
+    create_commitment(&env, &core, 1, &user, 100_000_000);
+    client.allocate(&user, &cid(&env, 1), &100_000_000, &Strategy::Safe);
 
     // Check updated liquidity
     let pool_after = client.get_pool(&0);
@@ -224,12 +426,13 @@ fn test_allocation_timestamps() {
     // Set ledger timestamp
     env.ledger().set_timestamp(1000);
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
 
-    let summary = client.allocate(&user, &7, &100_000_000, &Strategy::Safe);
+    create_commitment(&env, &core, 7, &user, 100_000_000);
+    let summary = client.allocate(&user, &cid(&env, 7), &100_000_000, &Strategy::Safe);
 
     // All allocations should have timestamps
     for allocation in summary.allocations.iter() {
@@ -242,13 +445,14 @@ fn test_total_allocation_accuracy() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
     let amount = 100_000_000i128;
 
-    let summary = client.allocate(&user, &8, &amount, &Strategy::Balanced);
+    create_commitment(&env, &core, 8, &user, amount);
+    let summary = client.allocate(&user, &cid(&env, 8), &amount, &Strategy::Balanced);
 
     // Sum all allocations
     let mut total = 0i128;
@@ -265,18 +469,19 @@ fn test_multiple_users_allocations() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     // Create multiple users and allocate
     for i in 0..5 {
         let user = Address::generate(&env);
-        client.allocate(&user, &(i + 10), &10_000_000, &Strategy::Balanced);
+        create_commitment(&env, &core, i + 10, &user, 10_000_000);
+        client.allocate(&user, &cid(&env, i + 10), &10_000_000, &Strategy::Balanced);
     }
 
     // Verify all allocations exist
     for i in 0..5 {
-        let summary = client.get_allocation(&(i + 10));
+        let summary = client.get_allocation(&cid(&env, i + 10));
         assert_eq!(summary.total_allocated, 10_000_000);
     }
 }
@@ -287,7 +492,7 @@ fn test_allocation_rate_limit_enforced() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
 
     // Configure rate limit: 1 allocation call per 60 seconds
     let fn_symbol = soroban_sdk::Symbol::new(&env, "alloc");
@@ -297,10 +502,12 @@ fn test_allocation_rate_limit_enforced() {
 
     // First allocation should succeed
     setup_test_pools(&env, &client, &admin);
-    client.allocate(&user, &100, &10_000_000, &Strategy::Balanced);
+    create_commitment(&env, &core, 100, &user, 10_000_000);
+    client.allocate(&user, &cid(&env, 100), &10_000_000, &Strategy::Balanced);
 
     // Second allocation should panic due to rate limit
-    client.allocate(&user, &101, &10_000_000, &Strategy::Balanced);
+    create_commitment(&env, &core, 101, &user, 10_000_000);
+    client.allocate(&user, &cid(&env, 101), &10_000_000, &Strategy::Balanced);
 }
 
 #[test]
@@ -310,7 +517,7 @@ fn test_get_nonexistent_allocation() {
 
     let (_, _, client) = create_contract(&env);
 
-    let summary = client.get_allocation(&999);
+    let summary = client.get_allocation(&cid(&env, 999));
 
     assert_eq!(summary.total_allocated, 0);
     assert_eq!(summary.allocations.len(), 0);
@@ -326,7 +533,7 @@ fn test_pool_timestamps() {
 
     let (admin, _, client) = create_contract(&env);
 
-    client.register_pool(&admin, &0, &RiskLevel::Low, &500, &1_000_000_000);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 1_000_000_000);
 
     let pool = client.get_pool(&0);
 
@@ -358,7 +565,15 @@ fn test_non_admin_cannot_register_pool() {
     let (_, _, client) = create_contract(&env);
     let non_admin = Address::generate(&env);
 
-    client.register_pool(&non_admin, &0, &RiskLevel::Low, &500, &1_000_000_000);
+    register_pool(
+        &env,
+        &client,
+        &non_admin,
+        0,
+        RiskLevel::Low,
+        500,
+        1_000_000_000,
+    );
 }
 
 #[test]
@@ -371,7 +586,7 @@ fn test_zero_amount_rejected() {
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
-    client.allocate(&user, &1, &0, &Strategy::Safe);
+    client.allocate(&user, &cid(&env, 1), &0, &Strategy::Safe);
 }
 
 #[test]
@@ -382,7 +597,7 @@ fn test_zero_capacity_rejected() {
 
     let (admin, _, client) = create_contract(&env);
 
-    client.register_pool(&admin, &0, &RiskLevel::Low, &500, &0);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 0);
 }
 
 #[test]
@@ -393,7 +608,15 @@ fn test_excessive_apy_rejected() {
 
     let (admin, _, client) = create_contract(&env);
 
-    client.register_pool(&admin, &0, &RiskLevel::Low, &100_001, &1_000_000_000);
+    register_pool(
+        &env,
+        &client,
+        &admin,
+        0,
+        RiskLevel::Low,
+        100_001,
+        1_000_000_000,
+    );
 }
 
 #[test]
@@ -404,8 +627,16 @@ fn test_duplicate_pool_id_rejected() {
 
     let (admin, _, client) = create_contract(&env);
 
-    client.register_pool(&admin, &0, &RiskLevel::Low, &500, &1_000_000_000);
-    client.register_pool(&admin, &0, &RiskLevel::Medium, &1000, &800_000_000);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 1_000_000_000);
+    register_pool(
+        &env,
+        &client,
+        &admin,
+        0,
+        RiskLevel::Medium,
+        1000,
+        800_000_000,
+    );
 }
 
 #[test]
@@ -414,12 +645,13 @@ fn test_pool_capacity_exceeded() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
 
-    client.register_pool(&admin, &0, &RiskLevel::Low, &500, &100_000);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 100_000);
 
     let user = Address::generate(&env);
-    client.allocate(&user, &1, &200_000, &Strategy::Safe);
+    create_commitment(&env, &core, 1, &user, 200_000);
+    client.allocate(&user, &cid(&env, 1), &200_000, &Strategy::Safe);
 }
 
 #[test]
@@ -428,13 +660,14 @@ fn test_double_allocation_prevented() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
 
-    client.allocate(&user, &1, &100_000, &Strategy::Safe);
-    client.allocate(&user, &1, &50_000, &Strategy::Balanced);
+    create_commitment(&env, &core, 1, &user, 1_000_000);
+    client.allocate(&user, &cid(&env, 1), &100_000, &Strategy::Safe);
+    client.allocate(&user, &cid(&env, 1), &50_000, &Strategy::Balanced);
 }
 
 #[test]
@@ -443,14 +676,15 @@ fn test_non_owner_cannot_rebalance() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
     let other_user = Address::generate(&env);
 
-    client.allocate(&user, &1, &100_000_000, &Strategy::Safe);
-    client.rebalance(&other_user, &1);
+    create_commitment(&env, &core, 1, &user, 100_000_000);
+    client.allocate(&user, &cid(&env, 1), &100_000_000, &Strategy::Safe);
+    client.rebalance(&other_user, &cid(&env, 1));
 }
 
 #[test]
@@ -459,13 +693,14 @@ fn test_no_active_pools_fails() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
 
-    client.register_pool(&admin, &0, &RiskLevel::Low, &500, &1_000_000_000);
-    client.update_pool_status(&admin, &0, &false);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 1_000_000_000);
+    client.update_pool_status(&admin, &0, &PoolStatus::Draining);
 
     let user = Address::generate(&env);
-    client.allocate(&user, &1, &100_000, &Strategy::Safe);
+    create_commitment(&env, &core, 1, &user, 100_000);
+    client.allocate(&user, &cid(&env, 1), &100_000, &Strategy::Safe);
 }
 
 // ============================================================================
@@ -478,15 +713,15 @@ fn test_allocation_exceeds_commitment_balance_fails() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _commitment_core, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
 
     // Test allocation when amount exceeds commitment current_value
     // commitment_id 100 has balance of 50M, but we try to allocate 100M
-    let commitment_id = 100u64;
     let allocation_amount = 100_000_000i128;
+    let commitment_id = create_commitment(&env, &core, 100, &user, 50_000_000).commitment_id;
 
     // This should fail because allocation amount exceeds commitment balance
     client.allocate(&user, &commitment_id, &allocation_amount, &Strategy::Safe);
@@ -497,15 +732,15 @@ fn test_allocation_equals_commitment_balance_succeeds() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _commitment_core, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
 
     // Test allocation when amount equals commitment current_value
     // commitment_id 200 has balance of 50M, we allocate exactly 50M
-    let commitment_id = 200u64;
     let allocation_amount = 50_000_000i128;
+    let commitment_id = create_commitment(&env, &core, 200, &user, 50_000_000).commitment_id;
 
     // This should succeed when amount == current_value
     let summary = client.allocate(&user, &commitment_id, &allocation_amount, &Strategy::Safe);
@@ -520,19 +755,20 @@ fn test_multiple_allocations_exceed_total_balance_fails() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (admin, _commitment_core, client) = create_contract(&env);
+    let (admin, core, client) = create_contract(&env);
     setup_test_pools(&env, &client, &admin);
 
     let user = Address::generate(&env);
 
     // First allocation succeeds (commitment_id 300 has 100M balance)
-    let first_commitment_id = 300u64;
     let first_amount = 30_000_000i128;
+    let first_commitment_id = create_commitment(&env, &core, 300, &user, 100_000_000).commitment_id;
     client.allocate(&user, &first_commitment_id, &first_amount, &Strategy::Safe);
 
     // Second allocation should fail (commitment_id 400 has 100M balance, but we try 110M)
-    let second_commitment_id = 400u64;
     let second_amount = 110_000_000i128;
+    let second_commitment_id =
+        create_commitment(&env, &core, 400, &user, 100_000_000).commitment_id;
 
     // This should fail because allocation amount exceeds commitment balance
     client.allocate(
@@ -542,3 +778,1220 @@ fn test_multiple_allocations_exceed_total_balance_fails() {
         &Strategy::Safe,
     );
 }
+
+#[test]
+fn test_allocation_moves_funds_to_pool_addresses() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 150_000_000);
+    let summary = client.allocate(&user, &cid(&env, 1), &100_000_000, &Strategy::Safe);
+
+    let asset = token::Client::new(&env, &commitment.asset_address);
+    for allocation in summary.allocations.iter() {
+        let pool = client.get_pool(&allocation.pool_id);
+        assert_eq!(asset.balance(&pool.pool_address), allocation.amount);
+    }
+    assert_eq!(asset.balance(&core), 50_000_000);
+
+    let updated =
+        MockCoreContractClient::new(&env, &core).get_commitment(&commitment.commitment_id);
+    assert_eq!(updated.current_value, 50_000_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #19)")]
+fn test_allocation_unknown_commitment_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    client.allocate(&user, &cid(&env, 42), &100_000, &Strategy::Safe);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #20)")]
+fn test_allocation_inactive_commitment_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let mut commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    commitment.status = CommitmentStatus::Settled;
+    MockCoreContractClient::new(&env, &core).set_commitment(&commitment);
+
+    client.allocate(&user, &cid(&env, 1), &100_000, &Strategy::Safe);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_allocation_by_non_owner_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let owner = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    create_commitment(&env, &core, 1, &owner, 100_000_000);
+
+    client.allocate(&attacker, &cid(&env, 1), &100_000, &Strategy::Safe);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #19)")]
+fn test_allocation_malformed_commitment_id_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    client.allocate(
+        &user,
+        &String::from_str(&env, "commitment_1"),
+        &100_000,
+        &Strategy::Safe,
+    );
+}
+
+// ============================================================================
+// DISTRIBUTION TESTS
+// ============================================================================
+
+#[test]
+fn test_allocation_assigns_rounding_remainders() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment_id = create_commitment(&env, &core, 1, &user, 100_000_001).commitment_id;
+    let summary = client.allocate(&user, &commitment_id, &100_000_001, &Strategy::Safe);
+
+    assert_eq!(summary.total_allocated, 100_000_001);
+    assert_eq!(client.get_pool(&0).total_liquidity, 50_000_001);
+    assert_eq!(client.get_pool(&1).total_liquidity, 50_000_000);
+}
+
+#[test]
+fn test_preview_allocation_places_weight_remainder_in_first_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    // 40/40/20 of 7 rounds down to 2/2/1; the 2 left over go to pool 0
+    let preview = client.preview_allocation(&7, &Strategy::Balanced);
+    let expected = [(0u32, 3i128), (1, 1), (2, 1), (3, 1), (4, 1)];
+    assert_eq!(preview.len(), expected.len() as u32);
+    for (planned, (pool_id, amount)) in preview.iter().zip(expected) {
+        assert_eq!(planned.pool_id, pool_id);
+        assert_eq!(planned.amount, amount);
+    }
+}
+
+#[test]
+fn test_allocation_spills_capacity_overflow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 30_000_000);
+    register_pool(&env, &client, &admin, 1, RiskLevel::Low, 600, 1_000_000_000);
+
+    let preview = client.preview_allocation(&100_000_000, &Strategy::Safe);
+    assert_eq!(preview.get(0).unwrap().amount, 30_000_000);
+    assert_eq!(preview.get(1).unwrap().amount, 70_000_000);
+
+    let user = Address::generate(&env);
+    let commitment_id = create_commitment(&env, &core, 1, &user, 100_000_000).commitment_id;
+    let summary = client.allocate(&user, &commitment_id, &100_000_000, &Strategy::Safe);
+
+    assert_eq!(summary.total_allocated, 100_000_000);
+    assert_eq!(client.get_pool(&0).total_liquidity, 30_000_000);
+    assert_eq!(client.get_pool(&1).total_liquidity, 70_000_000);
+}
+
+#[test]
+fn test_balanced_allocation_spills_missing_risk_levels() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 1_000_000_000);
+
+    let preview = client.preview_allocation(&100_000_000, &Strategy::Balanced);
+    assert_eq!(preview.len(), 1);
+    assert_eq!(preview.get(0).unwrap().amount, 100_000_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_allocation_exceeding_total_capacity_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 30_000_000);
+    register_pool(&env, &client, &admin, 1, RiskLevel::Low, 600, 30_000_000);
+
+    client.preview_allocation(&100_000_000, &Strategy::Safe);
+}
+
+// ============================================================================
+// CUSTOM STRATEGY TESTS
+// ============================================================================
+
+#[test]
+fn test_custom_strategy_allocation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+    client.register_strategy(&admin, &strategy_config(&env, 1, 5000, 5000, 0));
+    assert_eq!(client.get_all_strategies().len(), 1);
+
+    let user = Address::generate(&env);
+    let commitment_id = create_commitment(&env, &core, 1, &user, 100_000_000).commitment_id;
+    let summary = client.allocate(&user, &commitment_id, &100_000_000, &Strategy::Custom(1));
+
+    assert_eq!(summary.strategy, Strategy::Custom(1));
+    assert_eq!(summary.total_allocated, 100_000_000);
+    assert_eq!(summary.allocations.len(), 4);
+    for allocation in summary.allocations.iter() {
+        let pool = client.get_pool(&allocation.pool_id);
+        assert_ne!(pool.risk_level, RiskLevel::High);
+        assert_eq!(allocation.amount, 25_000_000);
+    }
+}
+
+#[test]
+fn test_custom_strategy_respects_allowed_pools_and_caps() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+    let mut config = strategy_config(&env, 7, 6000, 0, 4000);
+    config.allowed_pools = vec![&env, 0, 4];
+    config.min_pool_bps = 4000;
+    config.max_pool_bps = 6000;
+    client.register_strategy(&admin, &config);
+
+    let user = Address::generate(&env);
+    let commitment_id = create_commitment(&env, &core, 1, &user, 100_000_000).commitment_id;
+    let summary = client.allocate(&user, &commitment_id, &100_000_000, &Strategy::Custom(7));
+
+    assert_eq!(summary.allocations.len(), 2);
+    assert_eq!(client.get_pool(&0).total_liquidity, 60_000_000);
+    assert_eq!(client.get_pool(&4).total_liquidity, 40_000_000);
+    assert_eq!(client.get_pool(&1).total_liquidity, 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #8)")]
+fn test_custom_strategy_pool_above_cap_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+    // All funds go to the two low-risk pools, 50% each, above the 30% cap
+    let mut config = strategy_config(&env, 1, 10_000, 0, 0);
+    config.max_pool_bps = 3000;
+    client.register_strategy(&admin, &config);
+
+    let user = Address::generate(&env);
+    let commitment_id = create_commitment(&env, &core, 1, &user, 100_000_000).commitment_id;
+    client.allocate(&user, &commitment_id, &100_000_000, &Strategy::Custom(1));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #22)")]
+fn test_register_strategy_invalid_weights_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    client.register_strategy(&admin, &strategy_config(&env, 1, 5000, 4000, 0));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_register_strategy_non_admin_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (_, _, client) = create_contract(&env);
+    let attacker = Address::generate(&env);
+    client.register_strategy(&attacker, &strategy_config(&env, 1, 10_000, 0, 0));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #21)")]
+fn test_allocate_unknown_custom_strategy_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment_id = create_commitment(&env, &core, 1, &user, 100_000_000).commitment_id;
+    client.allocate(&user, &commitment_id, &100_000_000, &Strategy::Custom(9));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #23)")]
+fn test_allocate_inactive_custom_strategy_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+    client.register_strategy(&admin, &strategy_config(&env, 1, 10_000, 0, 0));
+    client.update_strategy_status(&admin, &1, &false);
+    assert!(!client.get_strategy(&1).active);
+
+    let user = Address::generate(&env);
+    let commitment_id = create_commitment(&env, &core, 1, &user, 100_000_000).commitment_id;
+    client.allocate(&user, &commitment_id, &100_000_000, &Strategy::Custom(1));
+}
+
+// ============================================================================
+// YIELD TESTS
+// ============================================================================
+
+#[test]
+fn test_harvest_credits_accrued_yield() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 200_000_000);
+    let commitment_id = commitment.commitment_id;
+    approve_pools(&env, &client, &commitment.asset_address);
+    fund_pools(&env, &client, &commitment.asset_address, 10_000_000);
+    // Safe splits 100M across pools 0 (5% APY) and 1 (6% APY)
+    client.allocate(&user, &commitment_id, &100_000_000, &Strategy::Safe);
+    assert_eq!(client.get_allocation(&commitment_id).pending_yield, 0);
+
+    env.ledger().with_mut(|l| l.timestamp += SECONDS_PER_YEAR);
+
+    let expected = 2_500_000 + 3_000_000;
+    assert_eq!(
+        client.get_allocation(&commitment_id).pending_yield,
+        expected
+    );
+    let pool_yield = client.get_pool_yield(&0);
+    assert_eq!(pool_yield.accrued, 2_500_000);
+    assert_eq!(pool_yield.distributed, 0);
+
+    assert_eq!(client.harvest(&commitment_id), expected);
+
+    let updated = MockCoreContractClient::new(&env, &core).get_commitment(&commitment_id);
+    assert_eq!(updated.current_value, 100_000_000 + expected);
+    // The credited yield is backed by tokens pulled from the pools
+    let asset = token::Client::new(&env, &commitment.asset_address);
+    assert_eq!(asset.balance(&core), updated.current_value);
+    let pool = client.get_pool(&0);
+    assert_eq!(
+        asset.balance(&pool.pool_address),
+        50_000_000 + 10_000_000 - 2_500_000
+    );
+    assert_eq!(client.get_allocation(&commitment_id).pending_yield, 0);
+    let pool_yield = client.get_pool_yield(&1);
+    assert_eq!(pool_yield.accrued, 3_000_000);
+    assert_eq!(pool_yield.distributed, 3_000_000);
+
+    // Nothing left to harvest at the same timestamp
+    assert_eq!(client.harvest(&commitment_id), 0);
+}
+
+#[test]
+fn test_rebalance_keeps_pending_yield() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    let commitment_id = commitment.commitment_id;
+    approve_pools(&env, &client, &commitment.asset_address);
+    fund_pools(&env, &client, &commitment.asset_address, 10_000_000);
+    client.allocate(&user, &commitment_id, &100_000_000, &Strategy::Safe);
+
+    env.ledger().with_mut(|l| l.timestamp += SECONDS_PER_YEAR);
+    let pending = client.get_allocation(&commitment_id).pending_yield;
+    assert_eq!(pending, 5_500_000);
+
+    let rebalanced = client.rebalance(&user, &commitment_id);
+    assert_eq!(rebalanced.pending_yield, pending);
+
+    env.ledger().with_mut(|l| l.timestamp += SECONDS_PER_YEAR);
+    assert_eq!(
+        client.get_allocation(&commitment_id).pending_yield,
+        2 * pending
+    );
+    assert_eq!(client.harvest(&commitment_id), 2 * pending);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #9)")]
+fn test_harvest_without_allocation_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (_, core, client) = create_contract(&env);
+    let user = Address::generate(&env);
+    let commitment_id = create_commitment(&env, &core, 1, &user, 100_000_000).commitment_id;
+
+    client.harvest(&commitment_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #20)")]
+fn test_harvest_inactive_commitment_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    client.allocate(
+        &user,
+        &commitment.commitment_id,
+        &100_000_000,
+        &Strategy::Safe,
+    );
+
+    let mut settled =
+        MockCoreContractClient::new(&env, &core).get_commitment(&commitment.commitment_id);
+    settled.status = CommitmentStatus::Settled;
+    MockCoreContractClient::new(&env, &core).set_commitment(&settled);
+
+    env.ledger().with_mut(|l| l.timestamp += SECONDS_PER_YEAR);
+    client.harvest(&commitment.commitment_id);
+}
+
+// ============================================================================
+// DEALLOCATION TESTS
+// ============================================================================
+
+#[test]
+fn test_deallocate_returns_funds_proportionally() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    // Safe splits 80M as 40M to each of pools 0 and 1
+    client.allocate(
+        &user,
+        &commitment.commitment_id,
+        &80_000_000,
+        &Strategy::Safe,
+    );
+
+    let summary = client.deallocate(&user, &commitment.commitment_id, &30_000_000);
+    assert_eq!(summary.total_allocated, 50_000_000);
+    assert_eq!(summary.allocations.len(), 2);
+    for allocation in summary.allocations.iter() {
+        assert_eq!(allocation.amount, 25_000_000);
+        assert_eq!(
+            client.get_pool(&allocation.pool_id).total_liquidity,
+            25_000_000
+        );
+    }
+
+    let asset = token::Client::new(&env, &commitment.asset_address);
+    assert_eq!(asset.balance(&core), 50_000_000);
+    let updated =
+        MockCoreContractClient::new(&env, &core).get_commitment(&commitment.commitment_id);
+    assert_eq!(updated.current_value, 50_000_000);
+    assert_eq!(
+        client
+            .get_allocation(&commitment.commitment_id)
+            .total_allocated,
+        50_000_000
+    );
+}
+
+#[test]
+fn test_deallocate_takes_rounding_remainder_from_first_pool() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 1_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    client.allocate(&user, &commitment.commitment_id, &1_000, &Strategy::Safe);
+
+    let summary = client.deallocate(&user, &commitment.commitment_id, &1);
+    assert_eq!(summary.allocations.get(0).unwrap().amount, 499);
+    assert_eq!(summary.allocations.get(1).unwrap().amount, 500);
+    assert_eq!(summary.total_allocated, 999);
+}
+
+#[test]
+fn test_deallocate_keeps_pending_yield() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    client.allocate(
+        &user,
+        &commitment.commitment_id,
+        &100_000_000,
+        &Strategy::Safe,
+    );
+
+    fund_pools(&env, &client, &commitment.asset_address, 10_000_000);
+
+    env.ledger().with_mut(|l| l.timestamp += SECONDS_PER_YEAR);
+    let summary = client.deallocate(&user, &commitment.commitment_id, &50_000_000);
+    assert_eq!(summary.pending_yield, 5_500_000);
+    assert_eq!(client.harvest(&commitment.commitment_id), 5_500_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #4)")]
+fn test_deallocate_more_than_allocated_fails() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    client.allocate(&user, &commitment.commitment_id, &50_000, &Strategy::Safe);
+
+    client.deallocate(&user, &commitment.commitment_id, &50_001);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_previous_owner_cannot_rebalance_after_transfer() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let mut commitment = create_commitment(&env, &core, 1, &seller, 100_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    client.allocate(
+        &seller,
+        &commitment.commitment_id,
+        &50_000,
+        &Strategy::Balanced,
+    );
+
+    // commitment_core moves the position on an NFT transfer
+    let core_client = MockCoreContractClient::new(&env, &core);
+    commitment = core_client.get_commitment(&commitment.commitment_id);
+    commitment.owner = buyer.clone();
+    core_client.set_commitment(&commitment);
+
+    client.rebalance(&buyer, &commitment.commitment_id);
+    client.rebalance(&seller, &commitment.commitment_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_deallocate_by_non_owner_fails() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000);
+    client.allocate(&user, &commitment.commitment_id, &50_000, &Strategy::Safe);
+
+    let attacker = Address::generate(&env);
+    client.deallocate(&attacker, &commitment.commitment_id, &10_000);
+}
+
+#[test]
+fn test_close_allocation_returns_everything_to_core() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    client.allocate(
+        &user,
+        &commitment.commitment_id,
+        &60_000_000,
+        &Strategy::Balanced,
+    );
+
+    let returned = client.close_allocation(&commitment.commitment_id, &commitment.asset_address);
+    assert_eq!(returned, (60_000_000, 0));
+
+    let asset = token::Client::new(&env, &commitment.asset_address);
+    assert_eq!(asset.balance(&core), 100_000_000);
+    for pool in client.get_all_pools().iter() {
+        assert_eq!(pool.total_liquidity, 0);
+    }
+    let summary = client.get_allocation(&commitment.commitment_id);
+    assert_eq!(summary.total_allocated, 0);
+    assert!(summary.allocations.is_empty());
+
+    // Nothing left to close
+    assert_eq!(
+        client.close_allocation(&commitment.commitment_id, &commitment.asset_address),
+        (0, 0)
+    );
+}
+
+#[test]
+fn test_close_allocation_pays_accrued_yield() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    fund_pools(&env, &client, &commitment.asset_address, 10_000_000);
+    client.allocate(
+        &user,
+        &commitment.commitment_id,
+        &100_000_000,
+        &Strategy::Safe,
+    );
+    client.set_rebalance_config(
+        &admin,
+        &RebalanceConfig {
+            drift_threshold_bps: 1_000,
+            keeper_reward: 1_000,
+            yield_fee_bps: 2_000,
+        },
+    );
+
+    env.ledger().with_mut(|l| l.timestamp += SECONDS_PER_YEAR);
+    // 2.5M + 3M of yield, less the 20% fee
+    assert_eq!(
+        client.close_allocation(&commitment.commitment_id, &commitment.asset_address),
+        (100_000_000, 4_400_000)
+    );
+
+    let asset = token::Client::new(&env, &commitment.asset_address);
+    assert_eq!(asset.balance(&core), 104_400_000);
+    assert_eq!(
+        client.get_keeper_rewards(&commitment.asset_address),
+        1_100_000
+    );
+    assert_eq!(client.get_pool_yield(&1).distributed, 3_000_000);
+    assert_eq!(
+        client
+            .get_allocation(&commitment.commitment_id)
+            .pending_yield,
+        0
+    );
+}
+
+#[test]
+fn test_close_allocation_without_allocation_returns_zero() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (_, core, client) = create_contract(&env);
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000);
+
+    assert_eq!(
+        client.close_allocation(&commitment.commitment_id, &commitment.asset_address),
+        (0, 0)
+    );
+}
+
+// ============================================================================
+// KEEPER REBALANCE TESTS
+// ============================================================================
+
+#[test]
+fn test_rebalance_if_drifted_pays_keeper() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    fund_pools(&env, &client, &commitment.asset_address, 10_000_000);
+    client.allocate(
+        &user,
+        &commitment.commitment_id,
+        &90_000_000,
+        &Strategy::Safe,
+    );
+    assert_eq!(client.get_drift(&commitment.commitment_id), 0);
+
+    client.set_rebalance_config(
+        &admin,
+        &RebalanceConfig {
+            drift_threshold_bps: 1_000,
+            keeper_reward: 1_000,
+            yield_fee_bps: 1_000,
+        },
+    );
+
+    // A year of yield on 45M at 5% and 45M at 6% fills the bucket
+    env.ledger().with_mut(|l| l.timestamp += SECONDS_PER_YEAR);
+    client.harvest(&commitment.commitment_id);
+    assert_eq!(
+        client.get_keeper_rewards(&commitment.asset_address),
+        495_000
+    );
+
+    // A third Low pool moves a third of the Safe split: 45M/45M -> 30M each
+    register_pool(&env, &client, &admin, 6, RiskLevel::Low, 500, 1_000_000_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    assert_eq!(client.get_drift(&commitment.commitment_id), 3_333);
+
+    let keeper = Address::generate(&env);
+    let summary = client.rebalance_if_drifted(&keeper, &commitment.commitment_id);
+    assert_eq!(summary.allocations.len(), 3);
+    for allocation in summary.allocations.iter() {
+        assert_eq!(allocation.amount, 30_000_000);
+    }
+    assert_eq!(client.get_drift(&commitment.commitment_id), 0);
+
+    let asset = token::Client::new(&env, &commitment.asset_address);
+    assert_eq!(asset.balance(&client.get_pool(&6).pool_address), 30_000_000);
+    assert_eq!(asset.balance(&keeper), 1_000);
+    assert_eq!(
+        client.get_keeper_rewards(&commitment.asset_address),
+        494_000
+    );
+}
+
+#[test]
+fn test_harvest_fee_funds_keeper_rewards() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    fund_pools(&env, &client, &commitment.asset_address, 10_000_000);
+    client.allocate(
+        &user,
+        &commitment.commitment_id,
+        &100_000_000,
+        &Strategy::Safe,
+    );
+    client.set_rebalance_config(
+        &admin,
+        &RebalanceConfig {
+            drift_threshold_bps: 1_000,
+            keeper_reward: 1_000,
+            yield_fee_bps: 2_000,
+        },
+    );
+
+    env.ledger().with_mut(|l| l.timestamp += SECONDS_PER_YEAR);
+    // 20% of 2.5M + 3M yield is kept for keepers
+    assert_eq!(client.harvest(&commitment.commitment_id), 4_400_000);
+    assert_eq!(
+        client.get_keeper_rewards(&commitment.asset_address),
+        1_100_000
+    );
+
+    let asset = token::Client::new(&env, &commitment.asset_address);
+    assert_eq!(asset.balance(&client.address), 1_100_000);
+    assert_eq!(asset.balance(&core), 4_400_000);
+    let updated =
+        MockCoreContractClient::new(&env, &core).get_commitment(&commitment.commitment_id);
+    assert_eq!(updated.current_value, 4_400_000);
+}
+
+#[test]
+fn test_rebalance_if_drifted_pays_at_most_bucket_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    client.allocate(
+        &user,
+        &commitment.commitment_id,
+        &100_000_000,
+        &Strategy::Safe,
+    );
+    client.set_rebalance_config(
+        &admin,
+        &RebalanceConfig {
+            drift_threshold_bps: 1_000,
+            keeper_reward: 1_000,
+            yield_fee_bps: 0,
+        },
+    );
+
+    // Without pool 1 everything belongs in pool 0
+    client.update_pool_status(&admin, &1, &PoolStatus::Draining);
+    assert_eq!(client.get_drift(&commitment.commitment_id), 5_000);
+
+    let keeper = Address::generate(&env);
+    let summary = client.rebalance_if_drifted(&keeper, &commitment.commitment_id);
+    assert_eq!(summary.total_allocated, 100_000_000);
+    assert_eq!(
+        token::Client::new(&env, &commitment.asset_address).balance(&keeper),
+        0
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #25)")]
+fn test_rebalance_if_drifted_below_threshold_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    client.allocate(
+        &user,
+        &commitment.commitment_id,
+        &90_000_000,
+        &Strategy::Safe,
+    );
+
+    // The new pool's 3333 bps of drift stays under a 5000 bps threshold
+    client.set_rebalance_config(
+        &admin,
+        &RebalanceConfig {
+            drift_threshold_bps: 5_000,
+            keeper_reward: 0,
+            yield_fee_bps: 0,
+        },
+    );
+    register_pool(&env, &client, &admin, 6, RiskLevel::Low, 500, 1_000_000_000);
+
+    client.rebalance_if_drifted(&Address::generate(&env), &commitment.commitment_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #24)")]
+fn test_set_rebalance_config_invalid_yield_fee_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    client.set_rebalance_config(
+        &admin,
+        &RebalanceConfig {
+            drift_threshold_bps: 1_000,
+            keeper_reward: 0,
+            yield_fee_bps: 10_001,
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #24)")]
+fn test_set_rebalance_config_invalid_threshold_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    client.set_rebalance_config(
+        &admin,
+        &RebalanceConfig {
+            drift_threshold_bps: 10_001,
+            keeper_reward: 0,
+            yield_fee_bps: 0,
+        },
+    );
+}
+
+// ============================================================================
+// POOL LIFECYCLE TESTS
+// ============================================================================
+
+#[test]
+fn test_pool_lifecycle_transitions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 1_000_000_000);
+
+    client.update_pool_status(&admin, &0, &PoolStatus::Draining);
+    client.update_pool_status(&admin, &0, &PoolStatus::Active);
+    client.update_pool_status(&admin, &0, &PoolStatus::Draining);
+    client.update_pool_status(&admin, &0, &PoolStatus::Closed);
+    assert_eq!(client.get_pool(&0).status, PoolStatus::Closed);
+
+    // Closed is final
+    assert!(client
+        .try_update_pool_status(&admin, &0, &PoolStatus::Active)
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #26)")]
+fn test_close_active_pool_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    register_pool(&env, &client, &admin, 0, RiskLevel::Low, 500, 1_000_000_000);
+
+    client.update_pool_status(&admin, &0, &PoolStatus::Closed);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #27)")]
+fn test_close_pool_with_liquidity_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    create_commitment(&env, &core, 1, &user, 100_000);
+    client.allocate(&user, &cid(&env, 1), &100_000, &Strategy::Safe);
+
+    client.update_pool_status(&admin, &0, &PoolStatus::Draining);
+    client.update_pool_status(&admin, &0, &PoolStatus::Closed);
+}
+
+#[test]
+fn test_migrate_pool_moves_allocations_and_funds() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let first = create_commitment(&env, &core, 1, &user, 100_000_000);
+    let second = create_commitment(&env, &core, 2, &user, 60_000_000);
+    approve_pools(&env, &client, &first.asset_address);
+    approve_pools(&env, &client, &second.asset_address);
+    client.allocate(&user, &first.commitment_id, &100_000_000, &Strategy::Safe);
+    client.allocate(&user, &second.commitment_id, &60_000_000, &Strategy::Safe);
+
+    env.ledger().with_mut(|l| l.timestamp += SECONDS_PER_YEAR);
+    let pending = client.get_allocation(&first.commitment_id).pending_yield;
+
+    client.update_pool_status(&admin, &0, &PoolStatus::Draining);
+    register_pool(&env, &client, &admin, 6, RiskLevel::Low, 500, 1_000_000_000);
+
+    // Ids without an allocation in pool 0 are skipped
+    let ids = vec![
+        &env,
+        first.commitment_id.clone(),
+        second.commitment_id.clone(),
+        cid(&env, 99),
+    ];
+    assert_eq!(client.migrate_pool(&admin, &0, &6, &ids), 2);
+
+    let old_pool = client.get_pool(&0);
+    let new_pool = client.get_pool(&6);
+    assert_eq!(old_pool.total_liquidity, 0);
+    assert_eq!(new_pool.total_liquidity, 80_000_000);
+    let asset = token::Client::new(&env, &first.asset_address);
+    assert_eq!(asset.balance(&old_pool.pool_address), 0);
+    assert_eq!(asset.balance(&new_pool.pool_address), 50_000_000);
+
+    let summary = client.get_allocation(&first.commitment_id);
+    assert_eq!(summary.total_allocated, 100_000_000);
+    assert_eq!(summary.pending_yield, pending);
+    for allocation in summary.allocations.iter() {
+        assert_ne!(allocation.pool_id, 0);
+    }
+
+    client.update_pool_status(&admin, &0, &PoolStatus::Closed);
+}
+
+#[test]
+fn test_migrate_pool_merges_into_existing_allocation() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (admin, core, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    let user = Address::generate(&env);
+    let commitment = create_commitment(&env, &core, 1, &user, 100_000_000);
+    approve_pools(&env, &client, &commitment.asset_address);
+    client.allocate(
+        &user,
+        &commitment.commitment_id,
+        &100_000_000,
+        &Strategy::Safe,
+    );
+
+    client.update_pool_status(&admin, &0, &PoolStatus::Draining);
+    client.migrate_pool(
+        &admin,
+        &0,
+        &1,
+        &vec![&env, commitment.commitment_id.clone()],
+    );
+
+    let summary = client.get_allocation(&commitment.commitment_id);
+    assert_eq!(summary.allocations.len(), 1);
+    let allocation = summary.allocations.get(0).unwrap();
+    assert_eq!(allocation.pool_id, 1);
+    assert_eq!(allocation.amount, 100_000_000);
+    assert_eq!(client.get_pool(&1).total_liquidity, 100_000_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #26)")]
+fn test_migrate_pool_from_active_pool_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+
+    client.migrate_pool(&admin, &0, &1, &vec![&env, cid(&env, 1)]);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #28)")]
+fn test_migrate_pool_batch_too_large_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    setup_test_pools(&env, &client, &admin);
+    client.update_pool_status(&admin, &0, &PoolStatus::Draining);
+
+    let mut ids = soroban_sdk::Vec::new(&env);
+    for n in 0..51 {
+        ids.push_back(cid(&env, n));
+    }
+    client.migrate_pool(&admin, &0, &1, &ids);
+}
+
+// ============================================================================
+// MIGRATION TESTS
+// ============================================================================
+
+#[test]
+fn test_migrate_allocations_rekeys_legacy_records() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, commitment_core, client) = create_contract(&env);
+    let owner = Address::generate(&env);
+    create_commitment(&env, &commitment_core, 7, &owner, 100_000);
+    store_legacy_allocation(&env, &client.address, 7, &owner);
+
+    // Ids without a legacy record are skipped.
+    let migrated = client.migrate_allocations(&admin, &vec![&env, 7u64, 8u64]);
+    assert_eq!(migrated, 1);
+
+    let summary = client.get_allocation(&cid(&env, 7));
+    assert_eq!(summary.commitment_id, cid(&env, 7));
+    assert_eq!(summary.strategy, Strategy::Balanced);
+    assert_eq!(summary.total_allocated, 40_000);
+    assert_eq!(summary.allocations.len(), 1);
+    let allocation = summary.allocations.get(0).unwrap();
+    assert_eq!(allocation.commitment_id, cid(&env, 7));
+    assert_eq!(allocation.pool_id, 2);
+    assert_eq!(allocation.timestamp, 123);
+
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&LegacyDataKey::Allocations(7)));
+        assert!(!storage.has(&LegacyDataKey::Strategy(7)));
+        assert!(!storage.has(&LegacyDataKey::TotalAllocated(7)));
+        assert!(!storage.has(&LegacyDataKey::AllocationOwner(7)));
+        let stored_owner: Address = storage
+            .get(&DataKey::AllocationOwner(cid(&env, 7)))
+            .unwrap();
+        assert_eq!(stored_owner, owner);
+    });
+
+    // Replaying the batch is a no-op.
+    assert_eq!(client.migrate_allocations(&admin, &vec![&env, 7u64]), 0);
+}
+
+#[test]
+fn test_migrate_allocations_skips_existing_v2_record() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, commitment_core, client) = create_contract(&env);
+    let owner = Address::generate(&env);
+    create_commitment(&env, &commitment_core, 7, &owner, 100_000);
+    store_legacy_allocation(&env, &client.address, 7, &owner);
+
+    let existing = soroban_sdk::Vec::<Allocation>::new(&env);
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Allocations(cid(&env, 7)), &existing);
+    });
+
+    assert_eq!(client.migrate_allocations(&admin, &vec![&env, 7u64]), 0);
+
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        let stored: soroban_sdk::Vec<Allocation> =
+            storage.get(&DataKey::Allocations(cid(&env, 7))).unwrap();
+        assert_eq!(stored, existing);
+        assert!(storage.has(&LegacyDataKey::Allocations(7)));
+    });
+}
+
+#[test]
+fn test_migrate_allocations_skips_missing_or_foreign_commitment() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, commitment_core, client) = create_contract(&env);
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+    // c_7 belongs to someone else; c_8 does not exist in core.
+    create_commitment(&env, &commitment_core, 7, &other, 100_000);
+    store_legacy_allocation(&env, &client.address, 7, &owner);
+    store_legacy_allocation(&env, &client.address, 8, &owner);
+
+    assert_eq!(
+        client.migrate_allocations(&admin, &vec![&env, 7u64, 8u64]),
+        0
+    );
+
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        for n in [7u64, 8u64] {
+            assert!(!storage.has(&DataKey::Allocations(cid(&env, n))));
+            assert!(storage.has(&LegacyDataKey::Allocations(n)));
+            assert!(storage.has(&LegacyDataKey::AllocationOwner(n)));
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #28)")]
+fn test_migrate_allocations_batch_too_large_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    let mut ids = soroban_sdk::Vec::new(&env);
+    for n in 0..51u64 {
+        ids.push_back(n);
+    }
+    client.migrate_allocations(&admin, &ids);
+}
+
+#[test]
+fn test_migrate_converts_legacy_pools() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (admin, _, client) = create_contract(&env);
+    let pool_address = Address::generate(&env);
+
+    env.as_contract(&client.address, || {
+        for (pool_id, active) in [(0u32, true), (1u32, false)] {
+            env.storage().persistent().set(
+                &DataKey::Pool(pool_id),
+                &LegacyPool {
+                    pool_id,
+                    pool_address: pool_address.clone(),
+                    risk_level: RiskLevel::Low,
+                    apy: 500,
+                    total_liquidity: 1_000,
+                    max_capacity: 1_000_000,
+                    active,
+                    created_at: 0,
+                    updated_at: 0,
+                },
+            );
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::PoolRegistry, &vec![&env, 0u32, 1u32]);
+        env.storage().instance().set(&DataKey::Version, &2u32);
+    });
+
+    client.migrate(&admin, &2);
+
+    assert_eq!(client.get_version(), 3);
+    let pool: Pool = client.get_pool(&0);
+    assert_eq!(pool.status, PoolStatus::Active);
+    assert_eq!(pool.total_liquidity, 1_000);
+    assert_eq!(client.get_pool(&1).status, PoolStatus::Draining);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #3)")]
+fn test_migrate_allocations_non_admin_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (_, _, client) = create_contract(&env);
+    let attacker = Address::generate(&env);
+    client.migrate_allocations(&attacker, &vec![&env, 1u64]);
+}
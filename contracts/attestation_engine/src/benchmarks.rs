@@ -88,7 +88,7 @@ fn store_mock_commitment(
         created_at: 0,
         expires_at: 86_400,
         current_value: 1_000,
-        status: String::from_str(e, "active"),
+        status: CommitmentStatus::Active,
     };
 
     e.as_contract(core_contract_id, || {
@@ -137,14 +137,14 @@ fn benchmark_attest() {
     let e = Env::default();
     let (contract_id, admin, core_contract) = setup_test_env(&e);
 
-    store_mock_commitment(&e, &core_contract, "commitment_1", &Address::generate(&e));
+    store_mock_commitment(&e, &core_contract, "c_1", &Address::generate(&e));
 
     // Add admin as verifier
     e.as_contract(&contract_id, || {
         // Admin is already authorized
     });
 
-    let commitment_id = String::from_str(&e, "commitment_1");
+    let commitment_id = String::from_str(&e, "c_1");
     let mut data = Map::new(&e);
     data.set(
         String::from_str(&e, "health_status"),
@@ -176,9 +176,9 @@ fn benchmark_get_attestations() {
     let e = Env::default();
     let (contract_id, admin, core_contract) = setup_test_env(&e);
 
-    store_mock_commitment(&e, &core_contract, "commitment_1", &Address::generate(&e));
+    store_mock_commitment(&e, &core_contract, "c_1", &Address::generate(&e));
 
-    let commitment_id = String::from_str(&e, "commitment_1");
+    let commitment_id = String::from_str(&e, "c_1");
     let mut data = Map::new(&e);
     data.set(
         String::from_str(&e, "health_status"),
@@ -215,8 +215,8 @@ fn benchmark_calculate_compliance_score() {
     let e = Env::default();
     let (contract_id, _admin, core_contract) = setup_test_env(&e);
 
-    let commitment_id = String::from_str(&e, "commitment_1");
-    store_mock_commitment(&e, &core_contract, "commitment_1", &Address::generate(&e));
+    let commitment_id = String::from_str(&e, "c_1");
+    store_mock_commitment(&e, &core_contract, "c_1", &Address::generate(&e));
 
     let mut metrics = BenchmarkMetrics::new("calculate_compliance_score");
 
@@ -237,7 +237,7 @@ fn benchmark_batch_attest() {
     let (contract_id, admin, core_contract) = setup_test_env(&e);
     let commitment_ids = [
         "commitment_0",
-        "commitment_1",
+        "c_1",
         "commitment_2",
         "commitment_3",
         "commitment_4",
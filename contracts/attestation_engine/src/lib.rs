@@ -1,8 +1,12 @@
 #![no_std]
-use shared_utils::{BatchError, BatchMode, BatchProcessor, BatchResultVoid, Pausable, RateLimiter};
+use commitment_interface::CommitmentCoreClient;
+pub use commitment_interface::{Commitment, CommitmentRules, CommitmentStatus};
+use shared_utils::{
+    BatchError, BatchMode, BatchProcessor, BatchResultVoid, CommitmentId, Pausable, RateLimiter,
+};
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env,
-    IntoVal, Map, String, Symbol, TryIntoVal, Val, Vec,
+    Map, String, Symbol, Vec,
 };
 
 const CURRENT_VERSION: u32 = 1;
@@ -121,33 +125,6 @@ pub struct AttestationsPage {
 /// Maximum number of attestations returned per page (avoids exceeding Soroban limits).
 pub const MAX_PAGE_SIZE: u32 = 100;
 
-// Import Commitment types from commitment_core (define locally for cross-contract calls)
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct CommitmentRules {
-    pub duration_days: u32,
-    pub max_loss_percent: u32,
-    pub commitment_type: String, // "safe", "balanced", "aggressive"
-    pub early_exit_penalty: u32,
-    pub min_fee_threshold: i128,
-    pub grace_period_days: u32,
-}
-
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Commitment {
-    pub commitment_id: String,
-    pub owner: Address,
-    pub nft_token_id: u32,
-    pub rules: CommitmentRules,
-    pub amount: i128,
-    pub asset_address: Address,
-    pub created_at: u64,
-    pub expires_at: u64,
-    pub current_value: i128,
-    pub status: String, // "active", "settled", "violated", "early_exit"
-}
-
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct HealthMetrics {
@@ -475,20 +452,10 @@ impl AttestationEngineContract {
         };
 
         // Try to get commitment from core contract
-        let mut args = Vec::new(e);
-        args.push_back(commitment_id.clone().into_val(e));
-
-        // Use try_invoke_contract to handle potential failures
-        let result = e.try_invoke_contract::<Val, soroban_sdk::Error>(
-            &commitment_core,
-            &Symbol::new(e, "get_commitment"),
-            args,
-        );
+        let result =
+            CommitmentCoreClient::new(e, &commitment_core).try_get_commitment(commitment_id);
 
-        match result {
-            Ok(Ok(_)) => true,
-            _ => false,
-        }
+        matches!(result, Ok(Ok(_)))
     }
 
     // ========================================================================
@@ -667,8 +634,8 @@ impl AttestationEngineContract {
         let fn_symbol = Symbol::new(&e, "attest");
         RateLimiter::check(&e, &caller, &fn_symbol);
 
-        // 4. Validate commitment_id is not empty
-        if commitment_id.len() == 0 {
+        // 4. Validate commitment_id is a commitment_core id ("c_<n>")
+        if !CommitmentId::is_valid(&commitment_id) {
             e.storage().instance().remove(&DataKey::ReentrancyGuard);
             return Err(AttestationError::InvalidCommitmentId);
         }
@@ -869,11 +836,8 @@ impl AttestationEngineContract {
             .get(&DataKey::CoreContract)
             .unwrap_or_else(|| panic!("Contract not initialized"));
 
-        let mut args = Vec::new(&e);
-        args.push_back(commitment_id.clone().into_val(&e));
-        let commitment_val: Val =
-            e.invoke_contract(&commitment_core, &Symbol::new(&e, "get_commitment"), args);
-        let commitment: Commitment = commitment_val.try_into_val(&e).unwrap();
+        let commitment =
+            CommitmentCoreClient::new(&e, &commitment_core).get_commitment(&commitment_id);
 
         let initial_value = commitment.amount;
         let current_value = commitment.current_value;
@@ -926,20 +890,12 @@ impl AttestationEngineContract {
             None => return false,
         };
 
-        let mut args = Vec::new(&e);
-        args.push_back(commitment_id.clone().into_val(&e));
-        let commitment_val: Val = match e.try_invoke_contract::<Val, soroban_sdk::Error>(
-            &commitment_core,
-            &Symbol::new(&e, "get_commitment"),
-            args,
-        ) {
-            Ok(Ok(val)) => val,
+        let commitment = match CommitmentCoreClient::new(&e, &commitment_core)
+            .try_get_commitment(&commitment_id)
+        {
+            Ok(Ok(c)) => c,
             _ => return false,
         };
-        let commitment: Commitment = match commitment_val.try_into_val(&e) {
-            Ok(c) => c,
-            Err(_) => return false,
-        };
 
         let metrics = Self::get_health_metrics(e.clone(), commitment_id);
         let max_loss = commitment.rules.max_loss_percent as i128;
@@ -988,13 +944,12 @@ impl AttestationEngineContract {
             .get(&DataKey::CoreContract)
             .ok_or(AttestationError::NotInitialized)?;
 
-        let mut args = Vec::new(&e);
-        args.push_back(commitment_id.clone().into_val(&e));
-        let commitment_val: Val =
-            e.invoke_contract(&commitment_core, &Symbol::new(&e, "get_commitment"), args);
-        let commitment: Commitment = commitment_val
-            .try_into_val(&e)
-            .map_err(|_| AttestationError::CommitmentNotFound)?;
+        let commitment = match CommitmentCoreClient::new(&e, &commitment_core)
+            .try_get_commitment(&commitment_id)
+        {
+            Ok(Ok(c)) => c,
+            _ => return Err(AttestationError::CommitmentNotFound),
+        };
         let max_loss = commitment.rules.max_loss_percent as i128;
         let is_compliant = drawdown_percent <= max_loss;
 
@@ -1092,14 +1047,8 @@ impl AttestationEngineContract {
         let commitment_core: Address = e.storage().instance().get(&DataKey::CoreContract).unwrap();
 
         // Call get_commitment on commitment_core contract
-        // Using Symbol::new() for function name longer than 9 characters
-        let mut args = Vec::new(&e);
-        args.push_back(commitment_id.clone().into_val(&e));
-        let commitment_val: Val =
-            e.invoke_contract(&commitment_core, &Symbol::new(&e, "get_commitment"), args);
-
-        // Convert Val to Commitment
-        let commitment: Commitment = commitment_val.try_into_val(&e).unwrap();
+        let commitment =
+            CommitmentCoreClient::new(&e, &commitment_core).get_commitment(&commitment_id);
 
         // Get all attestations
         let attestations = Self::get_attestations(e.clone(), commitment_id.clone());
@@ -1223,13 +1172,8 @@ impl AttestationEngineContract {
         let commitment_core: Address = e.storage().instance().get(&DataKey::CoreContract).unwrap();
 
         // get_total_commitments() on core contract
-        let args = Vec::new(&e);
-        let total_commitments_val: Val = e.invoke_contract(
-            &commitment_core,
-            &Symbol::new(&e, "get_total_commitments"),
-            args,
-        );
-        let total_commitments: u64 = total_commitments_val.try_into_val(&e).unwrap();
+        let total_commitments =
+            CommitmentCoreClient::new(&e, &commitment_core).get_total_commitments();
 
         let total_attestations: u64 = e
             .storage()
@@ -1348,20 +1292,20 @@ impl AttestationEngineContract {
             let params = params_list.get(i).unwrap();
 
             // Validate commitment_id
-            if params.commitment_id.len() == 0 {
+            if !CommitmentId::is_valid(&params.commitment_id) {
                 if mode == BatchMode::Atomic {
                     e.storage().instance().remove(&DataKey::ReentrancyGuard);
                     errors.push_back(BatchError {
                         index: i,
                         error_code: AttestationError::InvalidCommitmentId as u32,
-                        context: String::from_str(&e, "empty_commitment_id"),
+                        context: String::from_str(&e, "invalid_commitment_id"),
                     });
                     return BatchResultVoid::failure(&e, errors);
                 } else {
                     errors.push_back(BatchError {
                         index: i,
                         error_code: AttestationError::InvalidCommitmentId as u32,
-                        context: String::from_str(&e, "empty_commitment_id"),
+                        context: String::from_str(&e, "invalid_commitment_id"),
                     });
                     continue;
                 }
@@ -68,6 +68,35 @@ fn test_attest_without_initialize_fails() {
     assert_eq!(result, Err(AttestationError::Unauthorized));
 }
 
+#[test]
+fn test_attest_malformed_commitment_id_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+    let admin = Address::generate(&e);
+    let core = Address::generate(&e);
+    e.as_contract(&contract_id, || {
+        AttestationEngineContract::initialize(e.clone(), admin.clone(), core.clone()).unwrap()
+    });
+
+    let commitment_id = String::from_str(&e, "commitment_1");
+    let attestation_type = String::from_str(&e, "health_check");
+    let data = Map::<String, String>::new(&e);
+
+    let result = e.as_contract(&contract_id, || {
+        AttestationEngineContract::attest(
+            e.clone(),
+            admin.clone(),
+            commitment_id.clone(),
+            attestation_type.clone(),
+            data.clone(),
+            true,
+        )
+    });
+
+    assert_eq!(result, Err(AttestationError::InvalidCommitmentId));
+}
+
 #[test]
 fn test_get_admin_not_initialized_returns_error() {
     let e = Env::default();
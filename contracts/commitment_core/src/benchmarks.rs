@@ -74,7 +74,7 @@ fn build_benchmark_commitment(
         created_at: e.ledger().timestamp(),
         expires_at: e.ledger().timestamp() + (30 * 86400),
         current_value: amount,
-        status: String::from_str(e, "active"),
+        status: CommitmentStatus::Active,
     }
 }
 
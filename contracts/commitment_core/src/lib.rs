@@ -1,9 +1,18 @@
 #![no_std]
 
-use shared_utils::{emit_error_event, Pausable, RateLimiter, SafeMath, TimeUtils, Validation};
+use commitment_interface::{AllocationClient, CommitmentNftClient, PriceData, PriceOracleClient};
+pub use commitment_interface::{
+    Commitment, CommitmentFilter, CommitmentRules, CommitmentStatus, CommitmentsPage,
+    INTERFACE_VERSION,
+};
+use shared_utils::{
+    emit_error_event,
+    fees::{fee_from_bps, net_after_fee_bps, BPS_MAX},
+    CommitmentId, Pausable, RateLimiter, SafeMath, TimeUtils, Validation,
+};
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, log, symbol_short, token, Address, Env,
-    IntoVal, String, Symbol, Vec,
+    Map, String, Symbol, TryFromVal, Val, Vec,
 };
 
 #[contracterror]
@@ -27,6 +36,40 @@ pub enum CommitmentError {
     NotExpired = 15,
     /// Duration would cause expires_at to overflow u64
     ExpirationOverflow = 16,
+    /// Storage is already at the current version
+    AlreadyMigrated = 17,
+    /// Requested migration does not match the stored version
+    InvalidVersion = 18,
+    /// Status change not allowed from the commitment's current status
+    InvalidStatusTransition = 19,
+    /// Oracle price is missing, stale or not positive
+    InvalidOraclePrice = 20,
+    /// No price oracle has been set
+    OracleNotConfigured = 21,
+    /// Early-exit penalty curve is malformed for the commitment's rules
+    InvalidPenaltyCurve = 22,
+    /// No protocol treasury has been set
+    TreasuryNotSet = 23,
+    /// Withdrawal exceeds the penalties collected for the asset
+    InsufficientPenalties = 24,
+    /// Fee rate above 10000 bps
+    InvalidFeeBps = 25,
+    /// No fee recipient has been set
+    FeeRecipientNotSet = 26,
+    /// Withdrawal exceeds the fees collected for the asset
+    InsufficientFees = 27,
+    /// The owner has not opted the commitment into rollover
+    RolloverNotEnabled = 28,
+    /// Commitment has already reached `expires_at`
+    AlreadyExpired = 29,
+    /// Commitment is opted into rollover and cannot be settled
+    RolloverPending = 30,
+    /// Storage is below CURRENT_VERSION; `migrate` must run first
+    MigrationRequired = 31,
+    /// The allocation contract returned a different principal than `allocated`
+    AllocationMismatch = 32,
+    /// The position is escrowed by the marketplace and cannot be settled
+    PositionInEscrow = 33,
 }
 
 impl CommitmentError {
@@ -51,6 +94,25 @@ impl CommitmentError {
             CommitmentError::ExpirationOverflow => {
                 "Duration would cause expiration timestamp overflow"
             }
+            CommitmentError::AlreadyMigrated => "Storage already migrated",
+            CommitmentError::InvalidVersion => "Invalid storage version for migration",
+            CommitmentError::InvalidStatusTransition => "Invalid commitment status transition",
+            CommitmentError::InvalidOraclePrice => "Oracle price is missing, stale or invalid",
+            CommitmentError::OracleNotConfigured => "Price oracle not configured",
+            CommitmentError::InvalidPenaltyCurve => "Invalid early exit penalty curve",
+            CommitmentError::TreasuryNotSet => "Treasury not set",
+            CommitmentError::InsufficientPenalties => "Insufficient collected penalties",
+            CommitmentError::InvalidFeeBps => "Fee must be 0-10000 bps",
+            CommitmentError::FeeRecipientNotSet => "Fee recipient not set",
+            CommitmentError::InsufficientFees => "Insufficient collected fees to withdraw",
+            CommitmentError::RolloverNotEnabled => "Rollover not enabled for this commitment",
+            CommitmentError::AlreadyExpired => "Commitment has already expired",
+            CommitmentError::RolloverPending => "Commitment is set to roll over",
+            CommitmentError::MigrationRequired => "Storage migration required",
+            CommitmentError::AllocationMismatch => {
+                "Reclaimed allocation does not match allocated principal"
+            }
+            CommitmentError::PositionInEscrow => "Position is held in marketplace escrow",
         }
     }
 }
@@ -61,58 +123,162 @@ fn fail(e: &Env, err: CommitmentError, context: &str) -> ! {
     panic!("{}", err.message());
 }
 
+/// Roles the admin can grant to addresses.
 #[contracttype]
-#[derive(Clone)]
-pub struct CommitmentCreatedEvent {
-    pub commitment_id: String,
-    pub owner: Address,
-    pub amount: i128,
-    pub asset_address: Address,
-    pub nft_token_id: u32,
-    pub rules: CommitmentRules,
-    pub timestamp: u64,
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// May call `update_value` (e.g. allocation_logic or a valuation keeper)
+    Valuer,
 }
 
+/// Maximum number of steps in a `PenaltyCurve::Step` schedule.
+pub const MAX_PENALTY_STEPS: u32 = 10;
+
+/// One step of a `PenaltyCurve::Step` schedule.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct CommitmentRules {
-    pub duration_days: u32,
-    pub max_loss_percent: u32,
-    pub commitment_type: String, // "safe", "balanced", "aggressive"
-    pub early_exit_penalty: u32,
-    pub min_fee_threshold: i128,
-    pub grace_period_days: u32,
+pub struct PenaltyStep {
+    /// Whole days since creation from which this step applies
+    pub from_day: u32,
+    pub penalty_percent: u32,
 }
 
+/// How a commitment's early-exit penalty changes over its lifetime.
+/// `rules.early_exit_penalty` is the starting percentage.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Commitment {
+pub enum PenaltyCurve {
+    /// `early_exit_penalty` until expiry (default)
+    Flat,
+    /// Decays linearly from `early_exit_penalty` at creation to 0 at `expires_at`
+    Linear,
+    /// Floor percentage plus linear decay of the rest of `early_exit_penalty`
+    FloorLinear(u32),
+    /// `early_exit_penalty` until the first step, then each step's percentage;
+    /// steps are ordered by strictly increasing `from_day` and percentages
+    /// never rise above the previous one
+    Step(Vec<PenaltyStep>),
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct CommitmentCreatedEvent {
     pub commitment_id: String,
     pub owner: Address,
-    pub nft_token_id: u32,
-    pub rules: CommitmentRules,
     pub amount: i128,
     pub asset_address: Address,
-    pub created_at: u64,
-    pub expires_at: u64,
-    pub current_value: i128,
-    pub status: String, // "active", "settled", "violated", "early_exit"
+    pub nft_token_id: u32,
+    pub rules: CommitmentRules,
+    pub timestamp: u64,
+}
+
+/// Commitment record as stored before v2, with a string status.
+#[contracttype(export = false)]
+#[derive(Clone)]
+struct LegacyCommitment {
+    commitment_id: String,
+    owner: Address,
+    nft_token_id: u32,
+    rules: CommitmentRules,
+    amount: i128,
+    asset_address: Address,
+    created_at: u64,
+    expires_at: u64,
+    current_value: i128,
+    status: String,
 }
 
+/// Maximum number of commitments returned per page (avoids exceeding Soroban limits).
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Maximum number of index entries examined per `list_commitments` call.
+/// Selective filters may therefore return a short (or empty) page with a
+/// non-zero `next_cursor`.
+pub const MAX_PAGE_SCAN: u32 = 10 * MAX_PAGE_SIZE;
+
+/// Maximum number of commitments one `migrate` call processes.
+pub const MAX_MIGRATION_BATCH: u32 = 50;
+
+/// Storage layout version. v0 kept every record in instance storage; v1
+/// moved commitments and owner indexes to persistent storage; v2 stores
+/// `Commitment.status` as `CommitmentStatus` instead of a string and tracks
+/// allocated principal in `Commitment.allocated`.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Approximate ledgers per day (5 second close time).
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// Persistent entries are extended once their TTL drops below this.
+pub const COMMITMENT_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+
+/// TTL persistent entries (and the instance) are extended to.
+pub const COMMITMENT_TTL_EXTEND_TO: u32 = 180 * DAY_IN_LEDGERS;
+
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Admin,
     NftContract,
-    Commitment(String),        // commitment_id -> Commitment
-    OwnerCommitments(Address), // owner -> Vec<commitment_id>
+    Commitment(String),        // commitment_id -> Commitment (persistent)
+    OwnerCommitments(Address), // owner -> Vec<commitment_id> (persistent)
     TotalCommitments,          // counter
     ReentrancyGuard,           // reentrancy protection flag
     TotalValueLocked,          // aggregate value locked across active commitments
-    /// All commitment IDs for time-range queries (analytics). Appended on create.
+    /// Legacy (v0) instance list of all commitment IDs; replaced by `CommitmentIndex`.
     AllCommitmentIds,
+    /// Creation-order index for time-range queries: n -> commitment_id (persistent)
+    CommitmentIndex(u64),
+    /// Storage layout version
+    Version,
+    /// allocation_logic contract allowed to call `allocate`
+    AllocationContract,
+    /// commitment_marketplace contract that escrows transferable positions
+    MarketplaceContract,
+    /// price_oracle contract used by `update_value_from_oracle`
+    PriceOracle,
+    /// Staleness limit (seconds) passed to the oracle; unset uses the oracle default
+    OracleMaxStaleness,
+    /// Last oracle price a commitment was valued at (persistent)
+    ValuationPrice(String),
+    /// Role granted to an address
+    Role(Role, Address),
+    /// When a `Breaching` commitment's loss first exceeded its limit (persistent)
+    BreachStartedAt(String),
+    /// Protocol treasury that `withdraw_penalties` pays
+    Treasury,
+    /// Percent of a violated commitment's value charged as a penalty on resolution
+    ViolationPenalty,
+    /// Early-exit penalty curve of a commitment; absent means `Flat` (persistent)
+    PenaltyCurve(String),
+    /// Penalties held for withdrawal to the treasury, per asset
+    CollectedPenalties(Address),
+    /// Whether penalties are shared out to other open commitments in the asset
+    PenaltyRedistribution,
+    /// Cumulative redistributed penalty per unit of principal, scaled by `LOYALTY_SCALE`
+    LoyaltyIndex(Address),
+    /// Total principal of open commitments earning loyalty rewards, per asset
+    LoyaltyPrincipal(Address),
+    /// `LoyaltyIndex` a commitment's rewards were last settled at; present
+    /// while the commitment earns rewards (persistent)
+    LoyaltyCheckpoint(String),
+    /// Loyalty rewards settled but not yet claimed (persistent)
+    LoyaltyAccrued(String),
+    /// Creation fee rate in bps for an asset
+    CreationFeeBps(Address),
+    /// Address `withdraw_fees` pays
+    FeeRecipient,
+    /// Creation fees collected and not yet withdrawn, per asset
+    CollectedFees(Address),
+    /// Rules a commitment is re-locked under at maturity; present while the
+    /// owner has opted into rollover (persistent)
+    Rollover(String),
+    /// Commitments already processed by an unfinished `migrate`
+    MigrationCursor,
 }
 
+/// Fixed-point scale of `DataKey::LoyaltyIndex`.
+const LOYALTY_SCALE: i128 = 1_000_000_000_000;
+
 /// Transfer assets from owner to contract
 fn transfer_assets(e: &Env, from: &Address, to: &Address, asset_address: &Address, amount: i128) {
     let token_client = token::Client::new(e, asset_address);
@@ -128,54 +294,642 @@ fn transfer_assets(e: &Env, from: &Address, to: &Address, asset_address: &Addres
     token_client.transfer(from, to, &amount);
 }
 
-/// Helper function to call NFT contract mint function
-fn call_nft_mint(
-    e: &Env,
-    nft_contract: &Address,
-    owner: &Address,
-    commitment_id: &String,
-    duration_days: u32,
-    max_loss_percent: u32,
-    commitment_type: &String,
-    initial_amount: i128,
-    asset_address: &Address,
-    early_exit_penalty: u32,
-) -> u32 {
-    let mut args = Vec::new(e);
-    args.push_back(owner.clone().into_val(e));
-    args.push_back(commitment_id.clone().into_val(e));
-    args.push_back(duration_days.into_val(e));
-    args.push_back(max_loss_percent.into_val(e));
-    args.push_back(commitment_type.clone().into_val(e));
-    args.push_back(initial_amount.into_val(e));
-    args.push_back(asset_address.clone().into_val(e));
-    args.push_back(early_exit_penalty.into_val(e));
-
-    // In Soroban, contract calls return the value directly
-    // Failures cause the entire transaction to fail
-    e.invoke_contract::<u32>(nft_contract, &Symbol::new(e, "mint"), args)
+// Storage helpers
+//
+// Commitments, owner indexes and the creation-order index live in persistent
+// storage; every read or write of an entry extends its TTL.
+fn extend_persistent(e: &Env, key: &DataKey) {
+    e.storage()
+        .persistent()
+        .extend_ttl(key, COMMITMENT_TTL_THRESHOLD, COMMITMENT_TTL_EXTEND_TO);
 }
 
-// Storage helpers
-fn read_commitment(e: &Env, commitment_id: &String) -> Option<Commitment> {
+fn extend_instance(e: &Env) {
     e.storage()
         .instance()
-        .get::<_, Commitment>(&DataKey::Commitment(commitment_id.clone()))
+        .extend_ttl(COMMITMENT_TTL_THRESHOLD, COMMITMENT_TTL_EXTEND_TO);
+}
+
+fn read_commitment(e: &Env, commitment_id: &String) -> Option<Commitment> {
+    let key = DataKey::Commitment(commitment_id.clone());
+    let commitment = e.storage().persistent().get::<_, Commitment>(&key);
+    if commitment.is_some() {
+        extend_persistent(e, &key);
+    }
+    commitment
 }
 
 fn set_commitment(e: &Env, commitment: &Commitment) {
-    e.storage().instance().set(
-        &DataKey::Commitment(commitment.commitment_id.clone()),
-        commitment,
+    let key = DataKey::Commitment(commitment.commitment_id.clone());
+    e.storage().persistent().set(&key, commitment);
+    extend_commitment_entries(e, &commitment.commitment_id);
+}
+
+/// Extend a commitment's record and every other persistent entry keyed by
+/// it: its creation-order index entry and whichever optional entries
+/// (penalty curve, rollover, breach timer, valuation price, loyalty) it has.
+/// An archived entry would fail any transaction that reads it.
+fn extend_commitment_entries(e: &Env, commitment_id: &String) {
+    let id = commitment_id.clone();
+    let mut keys = Vec::from_array(
+        e,
+        [
+            DataKey::Commitment(id.clone()),
+            DataKey::PenaltyCurve(id.clone()),
+            DataKey::Rollover(id.clone()),
+            DataKey::BreachStartedAt(id.clone()),
+            DataKey::ValuationPrice(id.clone()),
+            DataKey::LoyaltyCheckpoint(id.clone()),
+            DataKey::LoyaltyAccrued(id),
+        ],
     );
+    if let Some(index) = CommitmentId::to_number(commitment_id) {
+        keys.push_back(DataKey::CommitmentIndex(index));
+    }
+    for key in keys.iter() {
+        if e.storage().persistent().has(&key) {
+            extend_persistent(e, &key);
+        }
+    }
 }
 
 fn has_commitment(e: &Env, commitment_id: &String) -> bool {
     e.storage()
-        .instance()
+        .persistent()
         .has(&DataKey::Commitment(commitment_id.clone()))
 }
 
+fn read_owner_commitments(e: &Env, owner: &Address) -> Vec<String> {
+    let key = DataKey::OwnerCommitments(owner.clone());
+    match e.storage().persistent().get::<_, Vec<String>>(&key) {
+        Some(ids) => {
+            extend_persistent(e, &key);
+            ids
+        }
+        None => Vec::new(e),
+    }
+}
+
+fn write_owner_commitments(e: &Env, owner: &Address, ids: &Vec<String>) {
+    let key = DataKey::OwnerCommitments(owner.clone());
+    e.storage().persistent().set(&key, ids);
+    extend_persistent(e, &key);
+}
+
+fn read_commitment_at(e: &Env, index: u64) -> Option<String> {
+    let key = DataKey::CommitmentIndex(index);
+    let id = e.storage().persistent().get::<_, String>(&key);
+    if id.is_some() {
+        extend_persistent(e, &key);
+    }
+    id
+}
+
+fn write_commitment_at(e: &Env, index: u64, commitment_id: &String) {
+    let key = DataKey::CommitmentIndex(index);
+    e.storage().persistent().set(&key, commitment_id);
+    extend_persistent(e, &key);
+}
+
+fn matches_filter(commitment: &Commitment, filter: &CommitmentFilter) -> bool {
+    if let Some(ref owner) = filter.owner {
+        if commitment.owner != *owner {
+            return false;
+        }
+    }
+    if !filter.statuses.is_empty() && !filter.statuses.contains(commitment.status) {
+        return false;
+    }
+    if let Some(ref asset_address) = filter.asset_address {
+        if commitment.asset_address != *asset_address {
+            return false;
+        }
+    }
+    if let Some(ref commitment_type) = filter.commitment_type {
+        if commitment.rules.commitment_type != *commitment_type {
+            return false;
+        }
+    }
+    let created = commitment.created_at;
+    let expires = commitment.expires_at;
+    in_range(created, filter.created_from, filter.created_to)
+        && in_range(expires, filter.expires_from, filter.expires_to)
+}
+
+/// Inclusive range check where a missing bound is unbounded.
+fn in_range(value: u64, from: Option<u64>, to: Option<u64>) -> bool {
+    from.is_none_or(|f| value >= f) && to.is_none_or(|t| value <= t)
+}
+
+fn read_version(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get::<_, u32>(&DataKey::Version)
+        .unwrap_or(0)
+}
+
+/// Reject writes to an initialized deployment whose storage has not been
+/// migrated to CURRENT_VERSION yet; older records do not decode as `Commitment`.
+fn require_migrated(e: &Env, context: &str) {
+    if read_version(e) < CURRENT_VERSION && e.storage().instance().has(&DataKey::Admin) {
+        fail(e, CommitmentError::MigrationRequired, context);
+    }
+}
+
+/// Move `commitment` to `next`, failing if the lifecycle does not allow it.
+/// Leaving `Breaching` clears the breach timer.
+fn transition_status(e: &Env, commitment: &mut Commitment, next: CommitmentStatus, context: &str) {
+    if !commitment.status.can_transition_to(&next) {
+        set_reentrancy_guard(e, false);
+        fail(e, CommitmentError::InvalidStatusTransition, context);
+    }
+    if commitment.status == CommitmentStatus::Breaching {
+        e.storage()
+            .persistent()
+            .remove(&DataKey::BreachStartedAt(commitment.commitment_id.clone()));
+    }
+    if commitment.status.is_open() && !next.is_open() {
+        leave_loyalty(e, commitment);
+    }
+    commitment.status = next;
+}
+
+fn read_instance_i128(e: &Env, key: &DataKey) -> i128 {
+    e.storage().instance().get::<_, i128>(key).unwrap_or(0)
+}
+
+fn read_persistent_i128(e: &Env, key: &DataKey) -> i128 {
+    let value = e.storage().persistent().get::<_, i128>(key);
+    if value.is_some() {
+        extend_persistent(e, key);
+    }
+    value.unwrap_or(0)
+}
+
+/// Start earning loyalty rewards on a new open commitment.
+fn join_loyalty(e: &Env, commitment: &Commitment) {
+    let asset = &commitment.asset_address;
+    let index = read_instance_i128(e, &DataKey::LoyaltyIndex(asset.clone()));
+    let key = DataKey::LoyaltyCheckpoint(commitment.commitment_id.clone());
+    e.storage().persistent().set(&key, &index);
+    extend_persistent(e, &key);
+
+    let principal_key = DataKey::LoyaltyPrincipal(asset.clone());
+    let principal = read_instance_i128(e, &principal_key);
+    e.storage()
+        .instance()
+        .set(&principal_key, &SafeMath::add(principal, commitment.amount));
+}
+
+/// Move a commitment's pending loyalty reward into `LoyaltyAccrued`.
+/// Returns false if the commitment does not earn rewards.
+fn sync_loyalty(e: &Env, commitment: &Commitment) -> bool {
+    let key = DataKey::LoyaltyCheckpoint(commitment.commitment_id.clone());
+    let Some(checkpoint) = e.storage().persistent().get::<_, i128>(&key) else {
+        return false;
+    };
+    let index = read_instance_i128(e, &DataKey::LoyaltyIndex(commitment.asset_address.clone()));
+    if index > checkpoint {
+        let pending = SafeMath::div(
+            SafeMath::mul(commitment.amount, index - checkpoint),
+            LOYALTY_SCALE,
+        );
+        let accrued_key = DataKey::LoyaltyAccrued(commitment.commitment_id.clone());
+        let accrued = read_persistent_i128(e, &accrued_key);
+        e.storage()
+            .persistent()
+            .set(&accrued_key, &SafeMath::add(accrued, pending));
+        extend_persistent(e, &accrued_key);
+    }
+    e.storage().persistent().set(&key, &index);
+    extend_persistent(e, &key);
+    true
+}
+
+/// Remove `principal` of a rewarded commitment from its asset's loyalty
+/// principal, settling its rewards first.
+fn reduce_loyalty_principal(e: &Env, commitment: &Commitment, principal: i128) {
+    if sync_loyalty(e, commitment) {
+        let key = DataKey::LoyaltyPrincipal(commitment.asset_address.clone());
+        let total = read_instance_i128(e, &key);
+        e.storage()
+            .instance()
+            .set(&key, &SafeMath::sub(total, principal));
+    }
+}
+
+/// Stop a commitment earning loyalty rewards; accrued rewards stay claimable.
+fn leave_loyalty(e: &Env, commitment: &Commitment) {
+    reduce_loyalty_principal(e, commitment, commitment.amount);
+    e.storage().persistent().remove(&DataKey::LoyaltyCheckpoint(
+        commitment.commitment_id.clone(),
+    ));
+}
+
+/// Route a penalty kept by the contract: shared out pro-rata to the other
+/// open commitments in the asset when redistribution is on and there are
+/// any, otherwise added to `CollectedPenalties` for the treasury.
+/// `payer` (if still earning rewards) gets no share of its own penalty.
+fn route_penalty(e: &Env, asset: &Address, penalty: i128, payer: Option<&Commitment>) {
+    if penalty <= 0 {
+        return;
+    }
+    let redistribute = e
+        .storage()
+        .instance()
+        .get::<_, bool>(&DataKey::PenaltyRedistribution)
+        .unwrap_or(false);
+    let payer = payer.filter(|c| sync_loyalty(e, c));
+    let mut principal = read_instance_i128(e, &DataKey::LoyaltyPrincipal(asset.clone()));
+    if let Some(c) = payer {
+        principal = SafeMath::sub(principal, c.amount);
+    }
+
+    let mut collected = penalty;
+    if redistribute && principal > 0 {
+        let index_key = DataKey::LoyaltyIndex(asset.clone());
+        let delta = SafeMath::div(SafeMath::mul(penalty, LOYALTY_SCALE), principal);
+        let index = SafeMath::add(read_instance_i128(e, &index_key), delta);
+        e.storage().instance().set(&index_key, &index);
+        if let Some(c) = payer {
+            let key = DataKey::LoyaltyCheckpoint(c.commitment_id.clone());
+            e.storage().persistent().set(&key, &index);
+        }
+        let distributed = SafeMath::div(SafeMath::mul(delta, principal), LOYALTY_SCALE);
+        collected = SafeMath::sub(penalty, distributed);
+        e.events().publish(
+            (symbol_short!("PenRedist"), asset.clone()),
+            (distributed, e.ledger().timestamp()),
+        );
+    }
+
+    // Rounding dust from redistribution goes to the treasury as well
+    if collected > 0 {
+        let key = DataKey::CollectedPenalties(asset.clone());
+        let total = read_instance_i128(e, &key);
+        e.storage()
+            .instance()
+            .set(&key, &SafeMath::add(total, collected));
+        e.events().publish(
+            (symbol_short!("PenColl"), asset.clone()),
+            (collected, e.ledger().timestamp()),
+        );
+    }
+}
+
+fn read_breach_started_at(e: &Env, commitment_id: &String) -> Option<u64> {
+    let key = DataKey::BreachStartedAt(commitment_id.clone());
+    let started_at = e.storage().persistent().get::<_, u64>(&key);
+    if started_at.is_some() {
+        extend_persistent(e, &key);
+    }
+    started_at
+}
+
+/// Value of the whole position: `current_value` plus principal still
+/// allocated to pools.
+fn position_value(commitment: &Commitment) -> i128 {
+    SafeMath::add(commitment.current_value, commitment.allocated)
+}
+
+/// Loss of the whole position against its principal, in percent. A
+/// zero-amount commitment cannot meaningfully lose, so it reports 0.
+fn position_loss_percent(commitment: &Commitment) -> i128 {
+    if commitment.amount > 0 {
+        SafeMath::loss_percent(commitment.amount, position_value(commitment))
+    } else {
+        0
+    }
+}
+
+/// Whether a loss of `loss_percent` counts as a violation: it must exceed
+/// the commitment's limit and, for a `Breaching` commitment, have lasted
+/// `grace_period_days` since `BreachStartedAt`. Reads storage only.
+fn loss_limit_violated(e: &Env, commitment: &Commitment, loss_percent: i128) -> bool {
+    if loss_percent <= commitment.rules.max_loss_percent as i128 {
+        return false;
+    }
+    let now = e.ledger().timestamp();
+    let breach_started_at = match commitment.status {
+        CommitmentStatus::Breaching => e
+            .storage()
+            .persistent()
+            .get::<_, u64>(&DataKey::BreachStartedAt(commitment.commitment_id.clone()))
+            .unwrap_or(now),
+        CommitmentStatus::Violated => return true,
+        _ => now,
+    };
+    let grace = TimeUtils::days_to_seconds(commitment.rules.grace_period_days);
+    now >= breach_started_at.saturating_add(grace)
+}
+
+fn read_penalty_curve(e: &Env, commitment_id: &String) -> PenaltyCurve {
+    let key = DataKey::PenaltyCurve(commitment_id.clone());
+    match e.storage().persistent().get::<_, PenaltyCurve>(&key) {
+        Some(curve) => {
+            extend_persistent(e, &key);
+            curve
+        }
+        None => PenaltyCurve::Flat,
+    }
+}
+
+/// `percent` of `value`, scaled by the share of the commitment's duration
+/// still remaining.
+fn decayed_penalty(value: i128, percent: u32, remaining: u64, duration: u64) -> i128 {
+    if duration == 0 {
+        return 0;
+    }
+    SafeMath::div(
+        SafeMath::mul(SafeMath::penalty_amount(value, percent), remaining as i128),
+        duration as i128,
+    )
+}
+
+/// Early-exit penalty on `value` at the current ledger time, following the
+/// commitment's penalty curve.
+fn early_exit_penalty_amount(e: &Env, commitment: &Commitment, value: i128) -> i128 {
+    let percent = commitment.rules.early_exit_penalty;
+    let now = e.ledger().timestamp();
+    let remaining = commitment.expires_at.saturating_sub(now);
+    let duration = commitment.expires_at.saturating_sub(commitment.created_at);
+
+    match read_penalty_curve(e, &commitment.commitment_id) {
+        PenaltyCurve::Flat => SafeMath::penalty_amount(value, percent),
+        PenaltyCurve::Linear => decayed_penalty(value, percent, remaining, duration),
+        PenaltyCurve::FloorLinear(floor) => SafeMath::add(
+            SafeMath::penalty_amount(value, floor),
+            decayed_penalty(value, percent - floor, remaining, duration),
+        ),
+        PenaltyCurve::Step(steps) => {
+            let elapsed_days = now.saturating_sub(commitment.created_at) / 86400;
+            let mut step_percent = percent;
+            for step in steps.iter() {
+                if elapsed_days >= step.from_day as u64 {
+                    step_percent = step.penalty_percent;
+                }
+            }
+            SafeMath::penalty_amount(value, step_percent)
+        }
+    }
+}
+
+fn read_rollover(e: &Env, commitment_id: &String) -> Option<CommitmentRules> {
+    let key = DataKey::Rollover(commitment_id.clone());
+    let rules = e.storage().persistent().get::<_, CommitmentRules>(&key);
+    if rules.is_some() {
+        extend_persistent(e, &key);
+    }
+    rules
+}
+
+fn write_rollover(e: &Env, commitment_id: &String, rules: &CommitmentRules) {
+    let key = DataKey::Rollover(commitment_id.clone());
+    e.storage().persistent().set(&key, rules);
+    extend_persistent(e, &key);
+    e.events().publish(
+        (symbol_short!("RollSet"), commitment_id.clone()),
+        (true, e.ledger().timestamp()),
+    );
+}
+
+fn has_role(e: &Env, role: Role, account: &Address) -> bool {
+    e.storage()
+        .instance()
+        .get::<_, bool>(&DataKey::Role(role, account.clone()))
+        .unwrap_or(false)
+}
+
+/// Require auth from `caller` and that it holds `role`.
+fn require_role(e: &Env, role: Role, caller: &Address, context: &str) {
+    caller.require_auth();
+    if !has_role(e, role, caller) {
+        fail(e, CommitmentError::Unauthorized, context);
+    }
+}
+
+/// Require auth from the registered allocation contract.
+fn require_allocation_contract(e: &Env, context: &str) {
+    let allocation_contract = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::AllocationContract)
+        .unwrap_or_else(|| {
+            set_reentrancy_guard(e, false);
+            fail(e, CommitmentError::Unauthorized, context)
+        });
+    allocation_contract.require_auth();
+}
+
+/// Whether `address` is the registered marketplace escrow.
+fn is_marketplace(e: &Env, address: &Address) -> bool {
+    e.storage()
+        .instance()
+        .get::<_, Address>(&DataKey::MarketplaceContract)
+        .is_some_and(|marketplace| marketplace == *address)
+}
+
+/// Pull a commitment's pool allocations back through the allocation
+/// contract, if one is registered, and move them from `allocated` to
+/// `current_value`.
+///
+/// The returned principal must equal `allocated`; unharvested yield paid
+/// out with it is added to `current_value` and TotalValueLocked.
+fn reclaim_allocations(e: &Env, commitment: &mut Commitment) {
+    let (principal, yield_amount) = match e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::AllocationContract)
+    {
+        Some(allocation_contract) => AllocationClient::new(e, &allocation_contract)
+            .close_allocation(&commitment.commitment_id, &commitment.asset_address),
+        None => (0, 0),
+    };
+    if principal != commitment.allocated || yield_amount < 0 {
+        set_reentrancy_guard(e, false);
+        fail(
+            e,
+            CommitmentError::AllocationMismatch,
+            "reclaim_allocations",
+        );
+    }
+    commitment.current_value = SafeMath::add(
+        commitment.current_value,
+        SafeMath::add(principal, yield_amount),
+    );
+    commitment.allocated = 0;
+
+    if yield_amount > 0 {
+        let current_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLocked)
+            .unwrap_or(0);
+        e.storage().instance().set(
+            &DataKey::TotalValueLocked,
+            &SafeMath::add(current_tvl, yield_amount),
+        );
+    }
+}
+
+/// Fetch a valid, positive price for `asset` from `oracle`, honouring the
+/// configured staleness limit.
+fn try_oracle_price(e: &Env, oracle: &Address, asset: &Address) -> Option<PriceData> {
+    let max_staleness = e
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKey::OracleMaxStaleness);
+    match PriceOracleClient::new(e, oracle).try_get_price_valid(asset, &max_staleness) {
+        Ok(Ok(price)) if price.price > 0 => Some(price),
+        _ => None,
+    }
+}
+
+/// Record the oracle price a commitment was last valued at.
+fn write_valuation_price(e: &Env, commitment_id: &String, price: &PriceData) {
+    let key = DataKey::ValuationPrice(commitment_id.clone());
+    e.storage().persistent().set(&key, price);
+    extend_persistent(e, &key);
+}
+
+/// Scale `value` by the move from price `from` to price `to`, normalising
+/// both prices to the larger of their decimal places.
+fn revalue(e: &Env, value: i128, from: &PriceData, to: &PriceData) -> i128 {
+    let decimals = from.decimals.max(to.decimals);
+    let scale = |price: &PriceData| {
+        10i128
+            .checked_pow(decimals - price.decimals)
+            .map(|factor| SafeMath::mul(price.price, factor))
+            .unwrap_or_else(|| fail(e, CommitmentError::InvalidOraclePrice, "revalue"))
+    };
+    SafeMath::div(SafeMath::mul(value, scale(to)), scale(from))
+}
+
+/// Store `new_value` as the commitment's current value, run the breach
+/// timer, and adjust TotalValueLocked. Returns the resulting status.
+///
+/// A loss past the limit moves an `Active` commitment to `Breaching`; it
+/// becomes `Violated` once the loss has lasted `grace_period_days` (at once
+/// with no grace period) and returns to `Active` if the value recovers.
+fn apply_value(
+    e: &Env,
+    mut commitment: Commitment,
+    new_value: i128,
+    context: &str,
+) -> CommitmentStatus {
+    let old_value = commitment.current_value;
+    commitment.current_value = new_value;
+
+    // Check for violations after updating value; allocated principal is
+    // still part of the position
+    let loss_percent = position_loss_percent(&commitment);
+    let max_loss = commitment.rules.max_loss_percent as i128;
+    let loss_violated = loss_percent > max_loss;
+
+    let now = e.ledger().timestamp();
+    let breaching = commitment.status == CommitmentStatus::Breaching;
+    if loss_violated {
+        let grace = TimeUtils::days_to_seconds(commitment.rules.grace_period_days);
+        let breach_started_at = if breaching {
+            read_breach_started_at(e, &commitment.commitment_id).unwrap_or(now)
+        } else {
+            now
+        };
+        let deadline = breach_started_at.saturating_add(grace);
+
+        if now >= deadline {
+            transition_status(e, &mut commitment, CommitmentStatus::Violated, context);
+            set_commitment(e, &commitment);
+            e.events().publish(
+                (symbol_short!("Violated"), commitment.commitment_id.clone()),
+                (symbol_short!("RuleViol"), now),
+            );
+        } else if breaching {
+            set_commitment(e, &commitment);
+            e.events().publish(
+                (symbol_short!("ValUpd"), commitment.commitment_id.clone()),
+                (new_value, now),
+            );
+        } else {
+            transition_status(e, &mut commitment, CommitmentStatus::Breaching, context);
+            set_commitment(e, &commitment);
+            let key = DataKey::BreachStartedAt(commitment.commitment_id.clone());
+            e.storage().persistent().set(&key, &now);
+            extend_persistent(e, &key);
+            e.events().publish(
+                (symbol_short!("Breach"), commitment.commitment_id.clone()),
+                (loss_percent, deadline),
+            );
+        }
+    } else if breaching {
+        transition_status(e, &mut commitment, CommitmentStatus::Active, context);
+        set_commitment(e, &commitment);
+        e.events().publish(
+            (symbol_short!("Recovered"), commitment.commitment_id.clone()),
+            (new_value, now),
+        );
+    } else {
+        set_commitment(e, &commitment);
+        e.events().publish(
+            (symbol_short!("ValUpd"), commitment.commitment_id.clone()),
+            (new_value, now),
+        );
+    }
+
+    // Adjust TotalValueLocked: TVL -= old_value, TVL += new_value
+    let current_tvl = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&DataKey::TotalValueLocked)
+        .unwrap_or(0);
+    let new_tvl = current_tvl - old_value + new_value;
+    e.storage()
+        .instance()
+        .set(&DataKey::TotalValueLocked, &new_tvl);
+    commitment.status
+}
+
+/// Whether a stored commitment record predates v2. Pre-v2 records have no
+/// `allocated` field; decoding them as `Commitment` (or a v2 record as
+/// `LegacyCommitment`) would trap on the field count.
+fn is_legacy_commitment(e: &Env, raw: &Val) -> bool {
+    Map::<Symbol, Val>::try_from_val(e, raw)
+        .is_ok_and(|fields| !fields.contains_key(Symbol::new(e, "allocated")))
+}
+
+/// Convert a pre-v2 record with a string status.
+fn upgrade_legacy_commitment(e: &Env, legacy: LegacyCommitment) -> Commitment {
+    let status = CommitmentStatus::from_legacy(e, &legacy.status)
+        .unwrap_or_else(|| fail(e, CommitmentError::InvalidStatus, "migrate"));
+    Commitment {
+        commitment_id: legacy.commitment_id,
+        owner: legacy.owner,
+        nft_token_id: legacy.nft_token_id,
+        rules: legacy.rules,
+        amount: legacy.amount,
+        asset_address: legacy.asset_address,
+        created_at: legacy.created_at,
+        expires_at: legacy.expires_at,
+        current_value: legacy.current_value,
+        allocated: 0,
+        status,
+    }
+}
+
+/// Record how far an unfinished `migrate` got; returns `false` so `migrate`
+/// can return it directly.
+fn save_migration_cursor(e: &Env, processed: u64) -> bool {
+    e.storage()
+        .instance()
+        .set(&DataKey::MigrationCursor, &processed);
+    extend_instance(e);
+    e.events().publish(
+        (symbol_short!("MigBatch"),),
+        (processed, e.ledger().timestamp()),
+    );
+    false
+}
+
 /// Reentrancy protection helpers
 fn require_no_reentrancy(e: &Env) {
     let guard: bool = e
@@ -275,38 +1029,42 @@ impl CommitmentCoreContract {
         Validation::require_valid_commitment_type(e, &rules.commitment_type, &valid_types);
     }
 
-    /// Generate unique commitment ID
-    /// Optimized: Uses counter to create unique ID efficiently
-    fn generate_commitment_id(e: &Env, counter: u64) -> String {
-        // OPTIMIZATION: Use counter directly as string to minimize allocations
-        // This is more gas-efficient than string concatenation
-        let mut buf = [0u8; 32];
-        let prefix = b"c_";
-        buf[0] = prefix[0];
-        buf[1] = prefix[1];
-
-        // Convert counter to string representation
-        let mut n = counter;
-        let mut i = 2;
-        if n == 0 {
-            buf[i] = b'0';
-            i += 1;
-        } else {
-            let mut digits = [0u8; 20];
-            let mut digit_count = 0;
-            while n > 0 {
-                digits[digit_count] = (n % 10) as u8 + b'0';
-                n /= 10;
-                digit_count += 1;
-            }
-            // Reverse digits
-            for j in 0..digit_count {
-                buf[i] = digits[digit_count - 1 - j];
-                i += 1;
-            }
+    /// Check a penalty curve against the rules it will be stored with.
+    fn validate_penalty_curve(e: &Env, rules: &CommitmentRules, curve: &PenaltyCurve) {
+        let valid = rules.early_exit_penalty <= 100
+            && match curve {
+                PenaltyCurve::Flat | PenaltyCurve::Linear => true,
+                PenaltyCurve::FloorLinear(floor) => *floor <= rules.early_exit_penalty,
+                PenaltyCurve::Step(steps) => {
+                    let mut ordered = !steps.is_empty() && steps.len() <= MAX_PENALTY_STEPS;
+                    let mut previous: Option<u32> = None;
+                    let mut previous_percent = rules.early_exit_penalty;
+                    for step in steps.iter() {
+                        ordered = ordered
+                            && previous.is_none_or(|day| step.from_day > day)
+                            && step.from_day < rules.duration_days
+                            && step.penalty_percent <= previous_percent;
+                        previous = Some(step.from_day);
+                        previous_percent = step.penalty_percent;
+                    }
+                    ordered
+                }
+            };
+        if !valid {
+            fail(e, CommitmentError::InvalidPenaltyCurve, "penalty_curve");
         }
+    }
+
+    /// Rules for a commitment's next term must be valid and fit the penalty
+    /// curve it keeps across terms.
+    fn validate_rollover_rules(e: &Env, rules: &CommitmentRules, curve: &PenaltyCurve) {
+        Self::validate_rules(e, rules);
+        Self::validate_penalty_curve(e, rules, curve);
+    }
 
-        String::from_str(e, core::str::from_utf8(&buf[..i]).unwrap_or("c_0"))
+    /// Generate unique commitment ID (`"c_<counter>"`)
+    fn generate_commitment_id(e: &Env, counter: u64) -> String {
+        CommitmentId::from_number(e, counter)
     }
 
     /// Initialize the core commitment contract
@@ -327,10 +1085,9 @@ impl CommitmentCoreContract {
             .instance()
             .set(&DataKey::TotalCommitments, &0u64);
 
-        // Initialize empty list for time-range queries
         e.storage()
             .instance()
-            .set(&DataKey::AllCommitmentIds, &Vec::<String>::new(&e));
+            .set(&DataKey::Version, &CURRENT_VERSION);
 
         // Initialize total value locked counter
         e.storage()
@@ -362,8 +1119,8 @@ impl CommitmentCoreContract {
     /// **Postconditions:**
     /// - Returns unique `commitment_id`
     /// - `get_commitment(commitment_id).owner == owner`
-    /// - `get_commitment(commitment_id).amount == amount`
-    /// - `get_commitment(commitment_id).status == "active"`
+    /// - `get_commitment(commitment_id).amount == amount - creation_fee`
+    /// - `get_commitment(commitment_id).status == CommitmentStatus::Active`
     /// - `get_total_commitments() == old(get_total_commitments()) + 1`
     /// - `reentrancy_guard == false`
     ///
@@ -385,6 +1142,8 @@ impl CommitmentCoreContract {
         asset_address: Address,
         rules: CommitmentRules,
     ) -> String {
+        require_migrated(&e, "create_commitment");
+
         // Reentrancy protection
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
@@ -444,6 +1203,23 @@ impl CommitmentCoreContract {
         // EFFECTS: Update state before external calls (expires_at already computed above with overflow check)
         let current_timestamp = TimeUtils::now(&e);
 
+        // The creation fee stays in the contract; only the net amount is locked
+        let fee_bps = Self::get_creation_fee_bps(e.clone(), asset_address.clone());
+        let creation_fee = fee_from_bps(amount, fee_bps);
+        let gross_amount = amount;
+        let amount = net_after_fee_bps(amount, fee_bps);
+        if amount <= 0 {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InvalidAmount, "create_commitment");
+        }
+        if creation_fee > 0 {
+            let key = DataKey::CollectedFees(asset_address.clone());
+            let collected = read_instance_i128(&e, &key);
+            e.storage()
+                .instance()
+                .set(&key, &SafeMath::add(collected, creation_fee));
+        }
+
         // Create commitment data
         let commitment = Commitment {
             commitment_id: commitment_id.clone(),
@@ -455,23 +1231,18 @@ impl CommitmentCoreContract {
             created_at: current_timestamp,
             expires_at,
             current_value: amount, // Initially same as amount
-            status: String::from_str(&e, "active"),
+            allocated: 0,
+            status: CommitmentStatus::Active,
         };
 
         // Store commitment data (before external calls)
         set_commitment(&e, &commitment);
+        join_loyalty(&e, &commitment);
 
         // Update owner's commitment list
-        let mut owner_commitments = e
-            .storage()
-            .instance()
-            .get::<_, Vec<String>>(&DataKey::OwnerCommitments(owner.clone()))
-            .unwrap_or(Vec::new(&e));
+        let mut owner_commitments = read_owner_commitments(&e, &owner);
         owner_commitments.push_back(commitment_id.clone());
-        e.storage().instance().set(
-            &DataKey::OwnerCommitments(owner.clone()),
-            &owner_commitments,
-        );
+        write_owner_commitments(&e, &owner, &owner_commitments);
 
         // OPTIMIZATION: Increment both counters using already-read values
         e.storage()
@@ -481,34 +1252,25 @@ impl CommitmentCoreContract {
             .instance()
             .set(&DataKey::TotalValueLocked, &(current_tvl + amount));
 
-        // Append to AllCommitmentIds for time-range queries (#143)
-        let mut all_ids = e
-            .storage()
-            .instance()
-            .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
-            .unwrap_or(Vec::new(&e));
-        all_ids.push_back(commitment_id.clone());
-        e.storage()
-            .instance()
-            .set(&DataKey::AllCommitmentIds, &all_ids);
+        // Record creation order for time-range queries (#143)
+        write_commitment_at(&e, current_total, &commitment_id);
+        extend_instance(&e);
 
         // INTERACTIONS: External calls (token transfer, NFT mint)
         // Transfer assets from owner to contract
         let contract_address = e.current_contract_address();
-        transfer_assets(&e, &owner, &contract_address, &asset_address, amount);
+        transfer_assets(&e, &owner, &contract_address, &asset_address, gross_amount);
 
         // Mint NFT
-        let nft_token_id = call_nft_mint(
-            &e,
-            &nft_contract,
+        let nft_token_id = CommitmentNftClient::new(&e, &nft_contract).mint(
             &owner,
             &commitment_id,
-            rules.duration_days,
-            rules.max_loss_percent,
+            &rules.duration_days,
+            &rules.max_loss_percent,
             &rules.commitment_type,
-            amount,
+            &amount,
             &asset_address,
-            rules.early_exit_penalty,
+            &rules.early_exit_penalty,
         );
 
         // Update commitment with NFT token ID
@@ -516,9 +1278,26 @@ impl CommitmentCoreContract {
         updated_commitment.nft_token_id = nft_token_id;
         set_commitment(&e, &updated_commitment);
 
+        // Record the entry price for oracle valuation; if no valid price is
+        // available, the first update_value_from_oracle call records one.
+        let oracle = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::PriceOracle);
+        if let Some(price) = oracle.and_then(|o| try_oracle_price(&e, &o, &asset_address)) {
+            write_valuation_price(&e, &commitment_id, &price);
+        }
+
         // Clear reentrancy guard
         set_reentrancy_guard(&e, false);
 
+        if creation_fee > 0 {
+            e.events().publish(
+                (symbol_short!("CreateFee"), commitment_id.clone()),
+                (asset_address, creation_fee, e.ledger().timestamp()),
+            );
+        }
+
         // Emit creation event
         e.events().publish(
             (
@@ -531,6 +1310,84 @@ impl CommitmentCoreContract {
         commitment_id
     }
 
+    /// Create a commitment whose early-exit penalty follows `penalty_curve`
+    /// instead of staying at `rules.early_exit_penalty` until expiry.
+    /// The curve is fixed for the commitment's lifetime, so `rollover_rules`,
+    /// if given, opt the commitment into rollover and must fit the curve too.
+    pub fn create_commitment_with_curve(
+        e: Env,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+        penalty_curve: PenaltyCurve,
+        rollover_rules: Option<CommitmentRules>,
+    ) -> String {
+        Self::validate_penalty_curve(&e, &rules, &penalty_curve);
+        if let Some(rollover_rules) = &rollover_rules {
+            Self::validate_rollover_rules(&e, rollover_rules, &penalty_curve);
+        }
+        let commitment_id = Self::create_commitment(e.clone(), owner, amount, asset_address, rules);
+
+        let key = DataKey::PenaltyCurve(commitment_id.clone());
+        e.storage().persistent().set(&key, &penalty_curve);
+        extend_persistent(&e, &key);
+
+        e.events().publish(
+            (symbol_short!("PenCurve"), commitment_id.clone()),
+            penalty_curve,
+        );
+        if let Some(rollover_rules) = rollover_rules {
+            write_rollover(&e, &commitment_id, &rollover_rules);
+        }
+        commitment_id
+    }
+
+    /// Create a commitment that `rollover` re-locks at maturity, under
+    /// `rollover_rules` or, if None, the same `rules`. It keeps the `Flat`
+    /// penalty curve; use `create_commitment_with_curve` for other curves.
+    pub fn create_commitment_with_rollover(
+        e: Env,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+        rollover_rules: Option<CommitmentRules>,
+    ) -> String {
+        let rollover_rules = rollover_rules.unwrap_or(rules.clone());
+        Self::validate_rollover_rules(&e, &rollover_rules, &PenaltyCurve::Flat);
+        let commitment_id = Self::create_commitment(e.clone(), owner, amount, asset_address, rules);
+        write_rollover(&e, &commitment_id, &rollover_rules);
+        commitment_id
+    }
+
+    /// Early-exit penalty curve of a commitment (`Flat` unless one was set at creation).
+    pub fn get_penalty_curve(e: Env, commitment_id: String) -> PenaltyCurve {
+        if !has_commitment(&e, &commitment_id) {
+            fail(&e, CommitmentError::CommitmentNotFound, "get_penalty_curve");
+        }
+        read_penalty_curve(&e, &commitment_id)
+    }
+
+    /// Penalty and payout `early_exit` would apply right now, as
+    /// `(penalty_amount, returned_amount)`. Covers the whole position,
+    /// including principal that is reclaimed from pools at exit time.
+    pub fn quote_early_exit(e: Env, commitment_id: String) -> (i128, i128) {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "quote_early_exit"));
+        if !commitment.status.is_open() {
+            fail(&e, CommitmentError::NotActive, "quote_early_exit");
+        }
+        let value = position_value(&commitment);
+        let penalty_amount = early_exit_penalty_amount(&e, &commitment, value);
+        (penalty_amount, SafeMath::sub(value, penalty_amount))
+    }
+
+    /// Version of `commitment_interface::CommitmentInterface` this contract implements.
+    pub fn interface_version(_e: Env) -> u32 {
+        INTERFACE_VERSION
+    }
+
     /// Get commitment details
     pub fn get_commitment(e: Env, commitment_id: String) -> Commitment {
         read_commitment(&e, &commitment_id)
@@ -547,10 +1404,7 @@ impl CommitmentCoreContract {
 
     /// Get all commitments for an owner
     pub fn get_owner_commitments(e: Env, owner: Address) -> Vec<String> {
-        e.storage()
-            .instance()
-            .get::<_, Vec<String>>(&DataKey::OwnerCommitments(owner))
-            .unwrap_or(Vec::new(&e))
+        read_owner_commitments(&e, &owner)
     }
 
     /// Get total number of commitments
@@ -570,19 +1424,19 @@ impl CommitmentCoreContract {
     }
 
     /// Get commitment IDs created between two timestamps (inclusive).
-    /// For analytics/dashboards. Gas cost is O(n) in total commitments; consider pagination for large n.
+    /// For analytics/dashboards. Gas cost is O(n) in total commitments; use
+    /// `list_commitments` with `created_from`/`created_to` for large n.
     pub fn get_commitments_created_between(
         e: Env,
         from_ts: u64,
         to_ts: u64,
     ) -> Vec<String> {
-        let all_ids = e
-            .storage()
-            .instance()
-            .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
-            .unwrap_or(Vec::new(&e));
+        let total = Self::get_total_commitments(e.clone());
         let mut out = Vec::new(&e);
-        for id in all_ids.iter() {
+        for index in 0..total {
+            let Some(id) = read_commitment_at(&e, index) else {
+                continue;
+            };
             if let Some(c) = read_commitment(&e, &id) {
                 if c.created_at >= from_ts && c.created_at <= to_ts {
                     out.push_back(id.clone());
@@ -592,6 +1446,56 @@ impl CommitmentCoreContract {
         out
     }
 
+    /// Get a page of commitments matching `filter` (ordered by creation, oldest first).
+    ///
+    /// # Arguments
+    /// * `cursor` - Creation index to start from (0 for the first page)
+    /// * `limit` - Max number of commitments to return (capped at MAX_PAGE_SIZE)
+    /// * `filter` - Criteria every returned commitment must match
+    ///
+    /// # Returns
+    /// * `commitments` - Matching commitments for this page
+    /// * `next_cursor` - Cursor for the next page; 0 if no more pages
+    ///
+    /// At most MAX_PAGE_SCAN entries are examined per call, so a page can hold
+    /// fewer than `limit` results while `next_cursor` is still non-zero.
+    pub fn list_commitments(
+        e: Env,
+        cursor: u64,
+        limit: u32,
+        filter: CommitmentFilter,
+    ) -> CommitmentsPage {
+        let total = Self::get_total_commitments(e.clone());
+        let cap = limit.min(MAX_PAGE_SIZE);
+
+        if cursor >= total || cap == 0 {
+            return CommitmentsPage {
+                commitments: Vec::new(&e),
+                next_cursor: 0,
+            };
+        }
+
+        let scan_end = total.min(cursor + MAX_PAGE_SCAN as u64);
+        let mut page = Vec::new(&e);
+        let mut index = cursor;
+        while index < scan_end && page.len() < cap {
+            if let Some(commitment) =
+                read_commitment_at(&e, index).and_then(|id| read_commitment(&e, &id))
+            {
+                if matches_filter(&commitment, &filter) {
+                    page.push_back(commitment);
+                }
+            }
+            index += 1;
+        }
+        let next_cursor = if index < total { index } else { 0 };
+
+        CommitmentsPage {
+            commitments: page,
+            next_cursor,
+        }
+    }
+
     /// Get admin address
     pub fn get_admin(e: Env) -> Address {
         e.storage()
@@ -600,6 +1504,127 @@ impl CommitmentCoreContract {
             .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "get_admin"))
     }
 
+    /// Get current on-chain storage version (0 if legacy/uninitialized).
+    pub fn get_version(e: Env) -> u32 {
+        read_version(&e)
+    }
+
+    /// Extend the TTL of a commitment's persistent entries (record, index
+    /// entry, owner index and optional per-commitment state) and the
+    /// contract instance. Callable by anyone (e.g. keepers) to keep
+    /// long-lived commitments from being archived.
+    pub fn bump_commitment(e: Env, commitment_id: String) {
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "bump_commitment"));
+        extend_commitment_entries(&e, &commitment_id);
+
+        let owner_key = DataKey::OwnerCommitments(commitment.owner);
+        if e.storage().persistent().has(&owner_key) {
+            extend_persistent(&e, &owner_key);
+        }
+        extend_instance(&e);
+    }
+
+    /// Migrate storage from a previous version to CURRENT_VERSION (admin-only).
+    ///
+    /// From v0, moves every `Commitment` and `OwnerCommitments` entry out of
+    /// instance storage into persistent storage, rewriting each record in
+    /// the current format, and replaces the legacy `AllCommitmentIds` vector
+    /// with `CommitmentIndex` entries.
+    /// From v1, rewrites every commitment's string status as `CommitmentStatus`;
+    /// records already in the current format are left as they are.
+    ///
+    /// Either way, each call processes at most `limit` commitments (capped at
+    /// MAX_MIGRATION_BATCH) and stores its progress, so the admin repeats
+    /// the call until it returns `true`. `Version` is written only by the
+    /// last batch, and writes stay rejected with `MigrationRequired` until then.
+    pub fn migrate(e: Env, caller: Address, from_version: u32, limit: u32) -> bool {
+        require_admin(&e, &caller);
+
+        let stored_version = read_version(&e);
+        if stored_version == CURRENT_VERSION {
+            fail(&e, CommitmentError::AlreadyMigrated, "migrate");
+        }
+        if from_version != stored_version || from_version > CURRENT_VERSION {
+            fail(&e, CommitmentError::InvalidVersion, "migrate");
+        }
+
+        if limit == 0 {
+            fail(&e, CommitmentError::InvalidAmount, "migrate");
+        }
+        let start = e
+            .storage()
+            .instance()
+            .get::<_, u64>(&DataKey::MigrationCursor)
+            .unwrap_or(0);
+
+        if from_version < 1 {
+            let legacy_ids = e
+                .storage()
+                .instance()
+                .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
+                .unwrap_or(Vec::new(&e));
+            let total = u64::from(legacy_ids.len());
+            let end = total.min(start + u64::from(limit.min(MAX_MIGRATION_BATCH)));
+
+            for index in start..end {
+                // `index < total <= u32::MAX`, so the cast is lossless
+                let id = legacy_ids.get_unchecked(index as u32);
+                write_commitment_at(&e, index, &id);
+                let key = DataKey::Commitment(id);
+                if let Some(legacy) = e.storage().instance().get::<_, LegacyCommitment>(&key) {
+                    let owner_key = DataKey::OwnerCommitments(legacy.owner.clone());
+                    if let Some(owner_ids) =
+                        e.storage().instance().get::<_, Vec<String>>(&owner_key)
+                    {
+                        write_owner_commitments(&e, &legacy.owner, &owner_ids);
+                        e.storage().instance().remove(&owner_key);
+                    }
+                    e.storage().instance().remove(&key);
+                    set_commitment(&e, &upgrade_legacy_commitment(&e, legacy));
+                }
+            }
+
+            if end < total {
+                return save_migration_cursor(&e, end);
+            }
+            e.storage().instance().remove(&DataKey::AllCommitmentIds);
+        } else if from_version < 2 {
+            let total = Self::get_total_commitments(e.clone());
+            let end = total.min(start + u64::from(limit.min(MAX_MIGRATION_BATCH)));
+            for index in start..end {
+                let Some(id) = read_commitment_at(&e, index) else {
+                    continue;
+                };
+                let key = DataKey::Commitment(id);
+                let Some(raw) = e.storage().persistent().get::<_, Val>(&key) else {
+                    continue;
+                };
+                if is_legacy_commitment(&e, &raw) {
+                    let legacy = LegacyCommitment::try_from_val(&e, &raw)
+                        .unwrap_or_else(|_| fail(&e, CommitmentError::InvalidStatus, "migrate"));
+                    set_commitment(&e, &upgrade_legacy_commitment(&e, legacy));
+                }
+            }
+
+            if end < total {
+                return save_migration_cursor(&e, end);
+            }
+        }
+
+        e.storage().instance().remove(&DataKey::MigrationCursor);
+        e.storage()
+            .instance()
+            .set(&DataKey::Version, &CURRENT_VERSION);
+        extend_instance(&e);
+
+        e.events().publish(
+            (symbol_short!("Migrated"),),
+            (from_version, CURRENT_VERSION, e.ledger().timestamp()),
+        );
+        true
+    }
+
     /// Get NFT contract address
     pub fn get_nft_contract(e: Env) -> Address {
         e.storage()
@@ -610,7 +1635,12 @@ impl CommitmentCoreContract {
 
     /// Update commitment value (called by allocation logic or oracle-fed keeper).
     /// Persists new_value to commitment.current_value and updates TotalValueLocked.
-    pub fn update_value(e: Env, commitment_id: String, new_value: i128) {
+    ///
+    /// `caller` must hold `Role::Valuer`.
+    pub fn update_value(e: Env, caller: Address, commitment_id: String, new_value: i128) {
+        require_role(&e, Role::Valuer, &caller, "update_value");
+        require_migrated(&e, "update_value");
+
         // Global per-function rate limit (per contract instance)
         let fn_symbol = symbol_short!("upd_val");
         let contract_address = e.current_contract_address();
@@ -618,51 +1648,103 @@ impl CommitmentCoreContract {
 
         Validation::require_non_negative(new_value);
 
-        let mut commitment = read_commitment(&e, &commitment_id)
+        let commitment = read_commitment(&e, &commitment_id)
             .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "update_value"));
 
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
+        if !commitment.status.is_open() {
             fail(&e, CommitmentError::NotActive, "update_value");
         }
 
-        let old_value = commitment.current_value;
-        commitment.current_value = new_value;
+        apply_value(&e, commitment, new_value, "update_value");
+    }
 
-        // Check for violations after updating value
-        let loss_percent = if commitment.amount > 0 {
-            SafeMath::loss_percent(commitment.amount, commitment.current_value)
-        } else {
-            0
-        };
-        let max_loss = commitment.rules.max_loss_percent as i128;
-        let loss_violated = loss_percent > max_loss;
+    /// Revalue an `Active` or `Breaching` commitment from the configured
+    /// price oracle.
+    ///
+    /// The whole position (`current_value` plus `allocated`) is scaled by
+    /// the asset's price move since the last oracle valuation (or since
+    /// creation), and the change lands in `current_value`; allocated
+    /// principal is returned in full later. The first valuation of a
+    /// commitment without a recorded price only records it. Missing, stale
+    /// or non-positive prices are rejected. Anyone may call this; it is
+    /// rate limited as `upd_orcl`. Returns the position's new value.
+    pub fn update_value_from_oracle(e: Env, commitment_id: String) -> i128 {
+        require_migrated(&e, "update_value_from_oracle");
+
+        // Separate bucket from update_value so permissionless callers
+        // cannot exhaust the valuers' limit
+        let fn_symbol = symbol_short!("upd_orcl");
+        let contract_address = e.current_contract_address();
+        RateLimiter::check(&e, &contract_address, &fn_symbol);
 
-        if loss_violated {
-            commitment.status = String::from_str(&e, "violated");
-            set_commitment(&e, &commitment);
-            e.events().publish(
-                (symbol_short!("Violated"), commitment_id.clone()),
-                (symbol_short!("RuleViol"), e.ledger().timestamp()),
-            );
-        } else {
-            set_commitment(&e, &commitment);
-            e.events().publish(
-                (symbol_short!("ValUpd"), commitment_id),
-                (new_value, e.ledger().timestamp()),
-            );
+        let oracle = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::PriceOracle)
+            .unwrap_or_else(|| {
+                fail(
+                    &e,
+                    CommitmentError::OracleNotConfigured,
+                    "update_value_from_oracle",
+                )
+            });
+
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "update_value_from_oracle",
+            )
+        });
+
+        if !commitment.status.is_open() {
+            fail(&e, CommitmentError::NotActive, "update_value_from_oracle");
         }
 
-        // Adjust TotalValueLocked: TVL -= old_value, TVL += new_value
-        let current_tvl = e
+        let price = try_oracle_price(&e, &oracle, &commitment.asset_address).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::InvalidOraclePrice,
+                "update_value_from_oracle",
+            )
+        });
+        let position = position_value(&commitment);
+        let new_position = match e
             .storage()
-            .instance()
-            .get::<_, i128>(&DataKey::TotalValueLocked)
-            .unwrap_or(0);
-        let new_tvl = current_tvl - old_value + new_value;
-        e.storage()
-            .instance()
-            .set(&DataKey::TotalValueLocked, &new_tvl);
+            .persistent()
+            .get::<_, PriceData>(&DataKey::ValuationPrice(commitment_id.clone()))
+        {
+            Some(last) => revalue(&e, position, &last, &price),
+            None => position,
+        };
+        write_valuation_price(&e, &commitment_id, &price);
+
+        let new_value = SafeMath::sub(new_position, commitment.allocated);
+        apply_value(&e, commitment, new_value, "update_value_from_oracle");
+        new_position
+    }
+
+    /// Re-run the breach timer of a `Breaching` commitment at its current
+    /// value, so it becomes `Violated` once the grace period has run out
+    /// without waiting for the next valuation. Anyone may call this.
+    /// Returns the resulting status.
+    pub fn check_breach(e: Env, commitment_id: String) -> CommitmentStatus {
+        require_migrated(&e, "check_breach");
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "check_breach"));
+
+        if commitment.status != CommitmentStatus::Breaching {
+            fail(&e, CommitmentError::InvalidStatus, "check_breach");
+        }
+
+        let value = commitment.current_value;
+        apply_value(&e, commitment, value, "check_breach")
+    }
+
+    /// When a `Breaching` commitment's loss first exceeded its limit, if it
+    /// is breaching.
+    pub fn get_breach_started_at(e: Env, commitment_id: String) -> Option<u64> {
+        read_breach_started_at(&e, &commitment_id)
     }
 
     /// Check if commitment rules are violated
@@ -673,9 +1755,11 @@ impl CommitmentCoreContract {
     /// - `commitment_id` exists
     ///
     /// **Postconditions:**
-    /// - Returns `true` if `loss_percent > max_loss_percent OR current_time >= expires_at`
+    /// - Returns `true` if `current_time >= expires_at`, or if
+    ///   `loss_percent > max_loss_percent` and a `Breaching` commitment's
+    ///   `grace_period_days` have passed since `BreachStartedAt`
     /// - Returns `false` otherwise
-    /// - Pure function (no state changes)
+    /// - Pure function (no state changes, no events)
     ///
     /// **Invariants Maintained:**
     /// - INV-2: Commitment balance conservation
@@ -686,47 +1770,31 @@ impl CommitmentCoreContract {
         let commitment = read_commitment(&e, &commitment_id)
             .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "check_violations"));
 
-        // Skip check if already settled or violated
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
+        // Skip check if already settled, exited or violated
+        if !commitment.status.is_open() {
             return false; // Already processed
         }
 
         let current_time = e.ledger().timestamp();
 
-        // Check loss limit violation
-        // Calculate loss percentage using shared utilities, but handle zero-amount
-        // commitments gracefully to avoid panics. A zero-amount commitment cannot
-        // meaningfully violate a loss limit, so we treat its loss percent as 0.
-        let loss_percent = if commitment.amount > 0 {
-            SafeMath::loss_percent(commitment.amount, commitment.current_value)
-        } else {
-            0
-        };
+        // Check loss limit violation on the whole position, including
+        // principal allocated to pools
+        let loss_percent = position_loss_percent(&commitment);
 
-        // Convert max_loss_percent (u32) to i128 for comparison
-        let max_loss = commitment.rules.max_loss_percent as i128;
-        let loss_violated = loss_percent > max_loss;
+        // A loss only counts once its grace period has run out
+        let loss_violated = loss_limit_violated(&e, &commitment, loss_percent);
 
         // Check duration violation (expired)
         let duration_violated = current_time >= commitment.expires_at;
 
-        let violated = loss_violated || duration_violated;
-
-        if violated {
-            // Emit violation event
-            e.events().publish(
-                (symbol_short!("Violated"), commitment_id),
-                (symbol_short!("RuleViol"), e.ledger().timestamp()),
-            );
-        }
-
         // Return true if any violation exists
-        violated
+        loss_violated || duration_violated
     }
 
     /// Get detailed violation information
     /// Returns a tuple: (has_violations, loss_violated, duration_violated, loss_percent, time_remaining)
+    ///
+    /// `loss_violated` follows the same grace period rule as `check_violations`.
     pub fn get_violation_details(e: Env, commitment_id: String) -> (bool, bool, bool, i128, u64) {
         let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
             fail(
@@ -738,27 +1806,17 @@ impl CommitmentCoreContract {
 
         let current_time = e.ledger().timestamp();
 
-        // Calculate loss percentage
-        let loss_amount = commitment.amount - commitment.current_value;
-        let loss_percent = if commitment.amount > 0 {
-            (loss_amount * 100) / commitment.amount
-        } else {
-            0
-        };
+        // Calculate loss percentage, counting allocated principal as held
+        let loss_percent = position_loss_percent(&commitment);
 
-        // Check loss limit violation
-        let max_loss = commitment.rules.max_loss_percent as i128;
-        let loss_violated = loss_percent > max_loss;
+        // Check loss limit violation, allowing for the grace period
+        let loss_violated = loss_limit_violated(&e, &commitment, loss_percent);
 
         // Check duration violation
         let duration_violated = current_time >= commitment.expires_at;
 
         // Calculate time remaining (0 if expired)
-        let time_remaining = if current_time < commitment.expires_at {
-            commitment.expires_at - current_time
-        } else {
-            0
-        };
+        let time_remaining = commitment.expires_at.saturating_sub(current_time);
 
         let has_violations = loss_violated || duration_violated;
 
@@ -776,6 +1834,8 @@ impl CommitmentCoreContract {
     /// # Reentrancy Protection
     /// Uses checks-effects-interactions pattern with reentrancy guard.
     pub fn settle(e: Env, commitment_id: String) {
+        require_migrated(&e, "settle");
+
         // Reentrancy protection
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
@@ -796,16 +1856,30 @@ impl CommitmentCoreContract {
             fail(&e, CommitmentError::NotExpired, "settle");
         }
 
-        // Verify commitment is active
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
+        // Opted-in commitments are rolled over rather than paid out
+        if commitment.status == CommitmentStatus::Active
+            && e.storage()
+                .persistent()
+                .has(&DataKey::Rollover(commitment_id.clone()))
+        {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::RolloverPending, "settle");
+        }
+
+        // An escrowed position would pay the marketplace; it has to be sold
+        // or its listing cancelled first
+        if is_marketplace(&e, &commitment.owner) {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::NotActive, "settle");
+            fail(&e, CommitmentError::PositionInEscrow, "settle");
         }
 
+        // Funds allocated to pools must be back before paying out
+        reclaim_allocations(&e, &mut commitment);
+
         // EFFECTS: Update state before external calls
+        // Active and violated commitments can be settled; terminal ones cannot.
         let settlement_amount = commitment.current_value;
-        commitment.status = String::from_str(&e, "settled");
+        transition_status(&e, &mut commitment, CommitmentStatus::Settled, "settle");
         set_commitment(&e, &commitment);
 
         // Decrease total value locked
@@ -835,9 +1909,7 @@ impl CommitmentCoreContract {
                 fail(&e, CommitmentError::NotInitialized, "settle")
             });
 
-        let mut args = Vec::new(&e);
-        args.push_back(commitment.nft_token_id.into_val(&e));
-        e.invoke_contract::<()>(&nft_contract, &Symbol::new(&e, "settle"), args);
+        CommitmentNftClient::new(&e, &nft_contract).settle(&commitment.nft_token_id);
 
         // Clear reentrancy guard
         set_reentrancy_guard(&e, false);
@@ -850,6 +1922,8 @@ impl CommitmentCoreContract {
     }
 
     pub fn early_exit(e: Env, commitment_id: String, caller: Address) {
+        require_migrated(&e, "early_exit");
+
         // Reentrancy protection
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
@@ -870,25 +1944,30 @@ impl CommitmentCoreContract {
             fail(&e, CommitmentError::Unauthorized, "early_exit");
         }
 
-        // Verify commitment is active
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
+        // Verify commitment is active (or breaching)
+        if !commitment.status.is_open() {
             set_reentrancy_guard(&e, false);
             fail(&e, CommitmentError::NotActive, "early_exit");
         }
 
-        // EFFECTS: Calculate penalty using shared utilities
-        let penalty_amount = SafeMath::penalty_amount(
-            commitment.current_value,
-            commitment.rules.early_exit_penalty,
-        );
+        // Funds allocated to pools must be back before paying out
+        reclaim_allocations(&e, &mut commitment);
+
+        // EFFECTS: Calculate penalty from the commitment's penalty curve
+        let penalty_amount = early_exit_penalty_amount(&e, &commitment, commitment.current_value);
         let returned_amount = SafeMath::sub(commitment.current_value, penalty_amount);
         let original_value = commitment.current_value;
 
         // Update commitment status to early_exit
-        commitment.status = String::from_str(&e, "early_exit");
+        transition_status(
+            &e,
+            &mut commitment,
+            CommitmentStatus::EarlyExit,
+            "early_exit",
+        );
         commitment.current_value = 0; // All value has been distributed
         set_commitment(&e, &commitment);
+        route_penalty(&e, &commitment.asset_address, penalty_amount, None);
 
         // Decrease total value locked by full current value (no longer locked)
         let current_tvl = e
@@ -921,9 +2000,7 @@ impl CommitmentCoreContract {
             });
         
         // Call mark_inactive on NFT instead of settle (since not expired)
-        let mut args = Vec::new(&e);
-        args.push_back(commitment.nft_token_id.into_val(&e));
-        e.invoke_contract::<()>(&nft_contract, &Symbol::new(&e, "mark_inactive"), args);
+        CommitmentNftClient::new(&e, &nft_contract).mark_inactive(&commitment.nft_token_id);
 
         // Clear reentrancy guard
         set_reentrancy_guard(&e, false);
@@ -939,63 +2016,885 @@ impl CommitmentCoreContract {
         );
     }
 
-    /// Allocate liquidity (called by allocation strategy)
+    /// Withdraw part of an open commitment before expiry.
     ///
-    /// # Reentrancy Protection
-    /// Uses checks-effects-interactions pattern with reentrancy guard.
-    pub fn allocate(e: Env, commitment_id: String, target_pool: Address, amount: i128) {
-        // Reentrancy protection
+    /// The penalty is the commitment's current early-exit penalty applied to
+    /// `amount` only. Pool allocations are reclaimed first, so the share is
+    /// taken of the commitment's whole value. The
+    /// commitment stays open: `current_value` drops by `amount` and `amount`
+    /// (principal) drops in the same proportion, so the loss percentage is
+    /// unchanged, and the remainder is re-checked against the loss rules.
+    /// `amount` must be below `current_value`; use `early_exit` to withdraw
+    /// everything.
+    pub fn partial_early_exit(e: Env, commitment_id: String, caller: Address, amount: i128) {
+        require_migrated(&e, "partial_early_exit");
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
 
-        // Check if contract is paused
         Pausable::require_not_paused(&e);
 
-        // Rate limit allocations per target pool address
-        let fn_symbol = symbol_short!("alloc");
-        RateLimiter::check(&e, &target_pool, &fn_symbol);
+        // CHECKS
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "partial_early_exit",
+            )
+        });
 
-        // CHECKS: Validate inputs and commitment
-        if amount <= 0 {
+        caller.require_auth();
+        if commitment.owner != caller {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::InvalidAmount, "allocate");
+            fail(&e, CommitmentError::Unauthorized, "partial_early_exit");
         }
 
-        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+        if !commitment.status.is_open() {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::CommitmentNotFound, "allocate")
-        });
+            fail(&e, CommitmentError::NotActive, "partial_early_exit");
+        }
 
-        // Verify commitment is active
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
+        if amount <= 0 {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::NotActive, "allocate");
+            fail(&e, CommitmentError::InvalidAmount, "partial_early_exit");
         }
 
-        // Verify sufficient balance
-        if commitment.current_value < amount {
+        // Principal is scaled against the whole value, so allocated funds
+        // must be back first
+        reclaim_allocations(&e, &mut commitment);
+
+        if amount >= commitment.current_value {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::InsufficientBalance, "allocate");
+            fail(
+                &e,
+                CommitmentError::InsufficientBalance,
+                "partial_early_exit",
+            );
         }
 
-        // EFFECTS: Update commitment value before external call
-        let mut updated_commitment = commitment;
-        updated_commitment.current_value = updated_commitment.current_value - amount;
-        set_commitment(&e, &updated_commitment);
+        // EFFECTS: pro-rated penalty and proportional principal reduction
+        let penalty_amount = early_exit_penalty_amount(&e, &commitment, amount);
+        let returned_amount = SafeMath::sub(amount, penalty_amount);
+        let principal_withdrawn = SafeMath::div(
+            SafeMath::mul(commitment.amount, amount),
+            commitment.current_value,
+        );
+        reduce_loyalty_principal(&e, &commitment, principal_withdrawn);
+        commitment.amount = SafeMath::sub(commitment.amount, principal_withdrawn);
+        let remaining_value = SafeMath::sub(commitment.current_value, amount);
+        let new_amount = commitment.amount;
+        let nft_token_id = commitment.nft_token_id;
+        let owner = commitment.owner.clone();
+        let asset_address = commitment.asset_address.clone();
+
+        // Stores the commitment, re-checks the loss limit and adjusts TVL
+        let commitment_after = commitment.clone();
+        apply_value(&e, commitment, remaining_value, "partial_early_exit");
+        route_penalty(&e, &asset_address, penalty_amount, Some(&commitment_after));
+
+        // INTERACTIONS
+        if returned_amount > 0 {
+            token::Client::new(&e, &asset_address).transfer(
+                &e.current_contract_address(),
+                &owner,
+                &returned_amount,
+            );
+        }
 
-        // INTERACTIONS: External call (token transfer)
-        // Transfer assets to target pool
-        let contract_address = e.current_contract_address();
-        let token_client = token::Client::new(&e, &updated_commitment.asset_address);
-        token_client.transfer(&contract_address, &target_pool, &amount);
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::NotInitialized, "partial_early_exit")
+            });
+        CommitmentNftClient::new(&e, &nft_contract).update_amount(&nft_token_id, &new_amount);
 
-        // Clear reentrancy guard
         set_reentrancy_guard(&e, false);
 
-        // Emit allocation event
         e.events().publish(
-            (symbol_short!("Alloc"), commitment_id, target_pool),
+            (symbol_short!("PartExit"), commitment_id, caller),
+            (
+                amount,
+                penalty_amount,
+                returned_amount,
+                e.ledger().timestamp(),
+            ),
+        );
+    }
+
+    /// Close a `Violated` commitment and return its remaining value to the
+    /// owner.
+    ///
+    /// Pool allocations are reclaimed first. The violation penalty set with
+    /// `set_violation_penalty` (percent of the value) is routed like
+    /// early-exit penalties: to `CollectedPenalties` for the treasury, or to
+    /// the other holders of the asset when redistribution is on. The
+    /// commitment becomes `Settled` and its NFT is marked inactive.
+    pub fn resolve_violation(e: Env, commitment_id: String, caller: Address) {
+        require_migrated(&e, "resolve_violation");
+
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        // CHECKS: Get and validate commitment
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "resolve_violation")
+        });
+
+        // Verify caller is owner
+        caller.require_auth();
+        if commitment.owner != caller {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::Unauthorized, "resolve_violation");
+        }
+
+        if commitment.status != CommitmentStatus::Violated {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InvalidStatus, "resolve_violation");
+        }
+
+        // Funds allocated to pools must be back before paying out
+        reclaim_allocations(&e, &mut commitment);
+
+        // EFFECTS: The violation penalty is routed like early-exit penalties
+        let penalty_amount = SafeMath::penalty_amount(
+            commitment.current_value,
+            Self::get_violation_penalty(e.clone()),
+        );
+        let returned_amount = SafeMath::sub(commitment.current_value, penalty_amount);
+        let original_value = commitment.current_value;
+
+        transition_status(
+            &e,
+            &mut commitment,
+            CommitmentStatus::Settled,
+            "resolve_violation",
+        );
+        commitment.current_value = 0; // All value has been distributed
+        set_commitment(&e, &commitment);
+        route_penalty(&e, &commitment.asset_address, penalty_amount, None);
+
+        // Decrease total value locked by the full value (no longer locked)
+        let current_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLocked)
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLocked, &(current_tvl - original_value));
+
+        // INTERACTIONS: Pay the owner
+        if returned_amount > 0 {
+            token::Client::new(&e, &commitment.asset_address).transfer(
+                &e.current_contract_address(),
+                &commitment.owner,
+                &returned_amount,
+            );
+        }
+
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::NotInitialized, "resolve_violation")
+            });
+        CommitmentNftClient::new(&e, &nft_contract).mark_inactive(&commitment.nft_token_id);
+
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
+
+        e.events().publish(
+            (symbol_short!("Resolved"), commitment_id, caller),
+            (returned_amount, penalty_amount, e.ledger().timestamp()),
+        );
+    }
+
+    /// Opt a commitment into rollover at maturity (owner only, before
+    /// expiry) or back out (owner only, any time while open, e.g. to
+    /// `settle` after expiry). `new_rules` replaces the commitment's rules
+    /// for the next term; None keeps the current rules. The choice carries
+    /// over to later terms until disabled.
+    pub fn set_rollover(
+        e: Env,
+        commitment_id: String,
+        caller: Address,
+        enabled: bool,
+        new_rules: Option<CommitmentRules>,
+    ) {
+        require_migrated(&e, "set_rollover");
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "set_rollover"));
+
+        caller.require_auth();
+        if commitment.owner != caller {
+            fail(&e, CommitmentError::Unauthorized, "set_rollover");
+        }
+        if !commitment.status.is_open() {
+            fail(&e, CommitmentError::NotActive, "set_rollover");
+        }
+
+        if enabled {
+            if e.ledger().timestamp() >= commitment.expires_at {
+                fail(&e, CommitmentError::AlreadyExpired, "set_rollover");
+            }
+            let rules = new_rules.unwrap_or(commitment.rules);
+            Self::validate_rollover_rules(&e, &rules, &read_penalty_curve(&e, &commitment_id));
+            write_rollover(&e, &commitment_id, &rules);
+        } else {
+            e.storage()
+                .persistent()
+                .remove(&DataKey::Rollover(commitment_id.clone()));
+            e.events().publish(
+                (symbol_short!("RollSet"), commitment_id),
+                (false, e.ledger().timestamp()),
+            );
+        }
+    }
+
+    /// Rules a commitment will be re-locked under at maturity, if the owner
+    /// opted into rollover.
+    pub fn get_rollover(e: Env, commitment_id: String) -> Option<CommitmentRules> {
+        read_rollover(&e, &commitment_id)
+    }
+
+    /// Re-lock a matured commitment that opted into rollover, instead of
+    /// settling it. Callable by anyone (e.g. a keeper) once `expires_at` has
+    /// passed. Pool allocations are reclaimed, `current_value` becomes the
+    /// new `amount`, and a new term starts now under the rollover rules.
+    /// The NFT is renewed in place.
+    pub fn rollover(e: Env, commitment_id: String) {
+        require_migrated(&e, "rollover");
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        Pausable::require_not_paused(&e);
+
+        // CHECKS
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "rollover")
+        });
+        let rules = read_rollover(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::RolloverNotEnabled, "rollover")
+        });
+        if e.ledger().timestamp() < commitment.expires_at {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotExpired, "rollover");
+        }
+        // Breaching and violated commitments are settled instead
+        if commitment.status != CommitmentStatus::Active {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "rollover");
+        }
+        let expires_at = TimeUtils::checked_calculate_expiration(&e, rules.duration_days)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::ExpirationOverflow, "rollover")
+            });
+
+        // Funds allocated to pools come back before re-locking
+        reclaim_allocations(&e, &mut commitment);
+        if commitment.current_value <= 0 {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InvalidAmount, "rollover");
+        }
+
+        // EFFECTS: new term with current_value as principal; TVL is unchanged
+        reduce_loyalty_principal(&e, &commitment, commitment.amount);
+        commitment.amount = commitment.current_value;
+        commitment.rules = rules;
+        commitment.created_at = TimeUtils::now(&e);
+        commitment.expires_at = expires_at;
+        set_commitment(&e, &commitment);
+        join_loyalty(&e, &commitment);
+
+        // INTERACTIONS
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::NotInitialized, "rollover")
+            });
+        CommitmentNftClient::new(&e, &nft_contract).renew(
+            &commitment.nft_token_id,
+            &commitment.rules.duration_days,
+            &commitment.rules.max_loss_percent,
+            &commitment.rules.commitment_type,
+            &commitment.amount,
+            &commitment.rules.early_exit_penalty,
+        );
+
+        set_reentrancy_guard(&e, false);
+
+        e.events().publish(
+            (symbol_short!("Rolled"), commitment_id),
+            (commitment.amount, expires_at, e.ledger().timestamp()),
+        );
+    }
+
+    /// Reassign a commitment to a new owner (called by the NFT contract).
+    ///
+    /// Invoked from `CommitmentNFTContract::transfer` for active tokens in
+    /// transferable position mode so that `settle` and `early_exit` pay out
+    /// to the current NFT holder. Only `Active` commitments can move, except
+    /// that a position not yet settled or exited can always leave marketplace
+    /// escrow.
+    pub fn transfer_position(e: Env, commitment_id: String, from: Address, to: Address) {
+        require_migrated(&e, "transfer_position");
+
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        // CHECKS: Only the registered NFT contract may move positions
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .unwrap_or_else(|| {
+                set_reentrancy_guard(&e, false);
+                fail(&e, CommitmentError::NotInitialized, "transfer_position")
+            });
+        nft_contract.require_auth();
+
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "transfer_position")
+        });
+
+        if commitment.owner != from {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::Unauthorized, "transfer_position");
+        }
+
+        let leaving_escrow = is_marketplace(&e, &from) && !commitment.status.is_terminal();
+        if commitment.status != CommitmentStatus::Active && !leaving_escrow {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "transfer_position");
+        }
+
+        // EFFECTS: Update owner and move the id between owner indexes
+        commitment.owner = to.clone();
+        set_commitment(&e, &commitment);
+
+        let mut from_commitments = read_owner_commitments(&e, &from);
+        if let Some(index) = from_commitments.iter().position(|id| id == commitment_id) {
+            from_commitments.remove(index as u32);
+        }
+        write_owner_commitments(&e, &from, &from_commitments);
+
+        let mut to_commitments = read_owner_commitments(&e, &to);
+        to_commitments.push_back(commitment_id.clone());
+        write_owner_commitments(&e, &to, &to_commitments);
+
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
+
+        e.events().publish(
+            (symbol_short!("PosXfer"), commitment_id, from),
+            (to, e.ledger().timestamp()),
+        );
+    }
+
+    /// Grant `role` to `account` (admin only).
+    pub fn grant_role(e: Env, caller: Address, role: Role, account: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::Role(role, account.clone()), &true);
+        e.events().publish(
+            (symbol_short!("RoleGrant"), role),
+            (account, e.ledger().timestamp()),
+        );
+    }
+
+    /// Revoke `role` from `account` (admin only).
+    pub fn revoke_role(e: Env, caller: Address, role: Role, account: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .remove(&DataKey::Role(role, account.clone()));
+        e.events().publish(
+            (symbol_short!("RoleRevok"), role),
+            (account, e.ledger().timestamp()),
+        );
+    }
+
+    /// Whether `account` holds `role`.
+    pub fn has_role(e: Env, role: Role, account: Address) -> bool {
+        has_role(&e, role, &account)
+    }
+
+    /// Set the allocation_logic contract allowed to call `allocate` (admin only).
+    pub fn set_allocation_contract(e: Env, caller: Address, allocation_contract: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::AllocationContract, &allocation_contract);
+    }
+
+    /// Get the allocation_logic contract allowed to call `allocate`, if set.
+    pub fn get_allocation_contract(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::AllocationContract)
+    }
+
+    /// Set the commitment_marketplace contract whose escrowed positions
+    /// cannot be settled (admin only).
+    pub fn set_marketplace_contract(e: Env, caller: Address, marketplace: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::MarketplaceContract, &marketplace);
+    }
+
+    /// Get the commitment_marketplace contract, if set.
+    pub fn get_marketplace_contract(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::MarketplaceContract)
+    }
+
+    /// Set the price_oracle contract used by `update_value_from_oracle`
+    /// (admin only). `max_staleness` overrides the oracle's default price
+    /// age limit in seconds.
+    pub fn set_price_oracle(e: Env, caller: Address, oracle: Address, max_staleness: Option<u64>) {
+        require_admin(&e, &caller);
+        e.storage().instance().set(&DataKey::PriceOracle, &oracle);
+        match max_staleness {
+            Some(seconds) => e
+                .storage()
+                .instance()
+                .set(&DataKey::OracleMaxStaleness, &seconds),
+            None => e.storage().instance().remove(&DataKey::OracleMaxStaleness),
+        }
+    }
+
+    /// Get the price_oracle contract used for valuation, if set.
+    pub fn get_price_oracle(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::PriceOracle)
+    }
+
+    /// Set the protocol treasury that `withdraw_penalties` pays (admin only).
+    pub fn set_treasury(e: Env, caller: Address, treasury: Address) {
+        require_admin(&e, &caller);
+        e.storage().instance().set(&DataKey::Treasury, &treasury);
+        e.events().publish(
+            (symbol_short!("Treasury"), caller),
+            (treasury, e.ledger().timestamp()),
+        );
+    }
+
+    /// Get the protocol treasury, if set.
+    pub fn get_treasury(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::Treasury)
+    }
+
+    /// Set the percent (0-100) of a violated commitment's value that
+    /// `resolve_violation` charges as a penalty (admin only). It is routed
+    /// like early-exit penalties; 0 disables it.
+    pub fn set_violation_penalty(e: Env, caller: Address, penalty_percent: u32) {
+        require_admin(&e, &caller);
+        Validation::require_valid_percent(penalty_percent);
+        e.storage()
+            .instance()
+            .set(&DataKey::ViolationPenalty, &penalty_percent);
+    }
+
+    /// Get the violation penalty percent (0 if unset).
+    pub fn get_violation_penalty(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::ViolationPenalty)
+            .unwrap_or(0)
+    }
+
+    /// Set the creation fee charged on new commitments in an asset, in bps
+    /// of the deposited amount (admin only).
+    pub fn set_creation_fee_bps(e: Env, caller: Address, asset_address: Address, fee_bps: u32) {
+        require_admin(&e, &caller);
+        if fee_bps > BPS_MAX {
+            fail(&e, CommitmentError::InvalidFeeBps, "set_creation_fee_bps");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::CreationFeeBps(asset_address.clone()), &fee_bps);
+        e.events().publish(
+            (symbol_short!("FeeSet"), caller),
+            (asset_address, fee_bps, e.ledger().timestamp()),
+        );
+    }
+
+    /// Creation fee in bps for an asset (0 if unset).
+    pub fn get_creation_fee_bps(e: Env, asset_address: Address) -> u32 {
+        e.storage()
+            .instance()
+            .get::<_, u32>(&DataKey::CreationFeeBps(asset_address))
+            .unwrap_or(0)
+    }
+
+    /// Set fee recipient (protocol treasury). Admin only.
+    pub fn set_fee_recipient(e: Env, caller: Address, recipient: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::FeeRecipient, &recipient);
+        e.events().publish(
+            (symbol_short!("FeeRecip"), caller),
+            (recipient, e.ledger().timestamp()),
+        );
+    }
+
+    /// Get fee recipient, if set.
+    pub fn get_fee_recipient(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::FeeRecipient)
+    }
+
+    /// Creation fees collected in an asset and not yet withdrawn.
+    pub fn get_collected_fees(e: Env, asset_address: Address) -> i128 {
+        read_instance_i128(&e, &DataKey::CollectedFees(asset_address))
+    }
+
+    /// Withdraw collected creation fees to the fee recipient. Admin only.
+    pub fn withdraw_fees(e: Env, caller: Address, asset_address: Address, amount: i128) {
+        require_admin(&e, &caller);
+        if amount <= 0 {
+            fail(&e, CommitmentError::InvalidAmount, "withdraw_fees");
+        }
+        let recipient = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::FeeRecipient)
+            .unwrap_or_else(|| fail(&e, CommitmentError::FeeRecipientNotSet, "withdraw_fees"));
+        let key = DataKey::CollectedFees(asset_address.clone());
+        let collected = read_instance_i128(&e, &key);
+        if amount > collected {
+            fail(&e, CommitmentError::InsufficientFees, "withdraw_fees");
+        }
+        e.storage().instance().set(&key, &(collected - amount));
+        token::Client::new(&e, &asset_address).transfer(
+            &e.current_contract_address(),
+            &recipient,
+            &amount,
+        );
+        e.events().publish(
+            (symbol_short!("FeesWith"), caller, recipient),
+            (asset_address, amount, e.ledger().timestamp()),
+        );
+    }
+
+    /// Share future penalties out to other open commitments in the same
+    /// asset instead of collecting them for the treasury (admin only).
+    pub fn set_penalty_redistribution(e: Env, caller: Address, enabled: bool) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::PenaltyRedistribution, &enabled);
+        e.events().publish(
+            (symbol_short!("PenRoute"), caller),
+            (enabled, e.ledger().timestamp()),
+        );
+    }
+
+    /// Whether penalties are redistributed (false if unset).
+    pub fn get_penalty_redistribution(e: Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::PenaltyRedistribution)
+            .unwrap_or(false)
+    }
+
+    /// Penalties collected for the treasury in an asset.
+    pub fn get_collected_penalties(e: Env, asset_address: Address) -> i128 {
+        read_instance_i128(&e, &DataKey::CollectedPenalties(asset_address))
+    }
+
+    /// Send collected penalties to the treasury (admin only).
+    pub fn withdraw_penalties(e: Env, caller: Address, asset_address: Address, amount: i128) {
+        require_admin(&e, &caller);
+        if amount <= 0 {
+            fail(&e, CommitmentError::InvalidAmount, "withdraw_penalties");
+        }
+        let treasury = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::Treasury)
+            .unwrap_or_else(|| fail(&e, CommitmentError::TreasuryNotSet, "withdraw_penalties"));
+        let key = DataKey::CollectedPenalties(asset_address.clone());
+        let collected = read_instance_i128(&e, &key);
+        if amount > collected {
+            fail(
+                &e,
+                CommitmentError::InsufficientPenalties,
+                "withdraw_penalties",
+            );
+        }
+        e.storage().instance().set(&key, &(collected - amount));
+        token::Client::new(&e, &asset_address).transfer(
+            &e.current_contract_address(),
+            &treasury,
+            &amount,
+        );
+        e.events().publish(
+            (symbol_short!("PenWith"), caller, treasury),
+            (asset_address, amount, e.ledger().timestamp()),
+        );
+    }
+
+    /// Unclaimed loyalty rewards of a commitment.
+    pub fn get_loyalty_reward(e: Env, commitment_id: String) -> i128 {
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "get_loyalty_reward",
+            )
+        });
+        let accrued = read_persistent_i128(&e, &DataKey::LoyaltyAccrued(commitment_id.clone()));
+        let checkpoint = e
+            .storage()
+            .persistent()
+            .get::<_, i128>(&DataKey::LoyaltyCheckpoint(commitment_id));
+        match checkpoint {
+            Some(checkpoint) => {
+                let index =
+                    read_instance_i128(&e, &DataKey::LoyaltyIndex(commitment.asset_address));
+                let pending = SafeMath::div(
+                    SafeMath::mul(commitment.amount, index - checkpoint),
+                    LOYALTY_SCALE,
+                );
+                SafeMath::add(accrued, pending)
+            }
+            None => accrued,
+        }
+    }
+
+    /// Pay a commitment's loyalty rewards (redistributed penalties) to its
+    /// owner. Works in any status. Returns the amount paid.
+    pub fn claim_loyalty_reward(e: Env, commitment_id: String, caller: Address) -> i128 {
+        require_migrated(&e, "claim_loyalty_reward");
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        Pausable::require_not_paused(&e);
+
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "claim_loyalty_reward",
+            )
+        });
+
+        caller.require_auth();
+        if commitment.owner != caller {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::Unauthorized, "claim_loyalty_reward");
+        }
+
+        sync_loyalty(&e, &commitment);
+        let key = DataKey::LoyaltyAccrued(commitment_id.clone());
+        let reward = read_persistent_i128(&e, &key);
+        if reward > 0 {
+            e.storage().persistent().remove(&key);
+            token::Client::new(&e, &commitment.asset_address).transfer(
+                &e.current_contract_address(),
+                &caller,
+                &reward,
+            );
+            e.events().publish(
+                (symbol_short!("Loyalty"), commitment_id, caller),
+                (reward, e.ledger().timestamp()),
+            );
+        }
+
+        set_reentrancy_guard(&e, false);
+        reward
+    }
+
+    /// Allocate liquidity (called by allocation strategy)
+    ///
+    /// Only the contract registered via `set_allocation_contract` may call this.
+    ///
+    /// # Reentrancy Protection
+    /// Uses checks-effects-interactions pattern with reentrancy guard.
+    pub fn allocate(e: Env, commitment_id: String, target_pool: Address, amount: i128) {
+        require_migrated(&e, "allocate");
+
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        // Only the registered allocation contract may move commitment funds
+        require_allocation_contract(&e, "allocate");
+
+        // Rate limit allocations per target pool address
+        let fn_symbol = symbol_short!("alloc");
+        RateLimiter::check(&e, &target_pool, &fn_symbol);
+
+        // CHECKS: Validate inputs and commitment
+        if amount <= 0 {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InvalidAmount, "allocate");
+        }
+
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "allocate")
+        });
+
+        // Verify commitment is active
+        if commitment.status != CommitmentStatus::Active {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "allocate");
+        }
+
+        // Verify sufficient balance
+        if commitment.current_value < amount {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InsufficientBalance, "allocate");
+        }
+
+        // EFFECTS: Move the amount from current_value to allocated before
+        // the external call
+        let mut updated_commitment = commitment;
+        updated_commitment.current_value -= amount;
+        updated_commitment.allocated = SafeMath::add(updated_commitment.allocated, amount);
+        set_commitment(&e, &updated_commitment);
+
+        // INTERACTIONS: External call (token transfer)
+        // Transfer assets to target pool
+        let contract_address = e.current_contract_address();
+        let token_client = token::Client::new(&e, &updated_commitment.asset_address);
+        token_client.transfer(&contract_address, &target_pool, &amount);
+
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
+
+        // Emit allocation event
+        e.events().publish(
+            (symbol_short!("Alloc"), commitment_id, target_pool),
+            (amount, e.ledger().timestamp()),
+        );
+    }
+
+    /// Credit `amount` of principal returned from pools back to an `Active`
+    /// or `Breaching` commitment, moving it from `allocated` to
+    /// `current_value`.
+    ///
+    /// Only the registered allocation contract may call this, after it has
+    /// transferred the tokens back to this contract.
+    pub fn deallocate(e: Env, commitment_id: String, amount: i128) {
+        require_migrated(&e, "deallocate");
+
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        require_allocation_contract(&e, "deallocate");
+
+        if amount <= 0 {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InvalidAmount, "deallocate");
+        }
+
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "deallocate")
+        });
+
+        if !commitment.status.is_open() {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "deallocate");
+        }
+
+        if amount > commitment.allocated {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InsufficientBalance, "deallocate");
+        }
+
+        commitment.allocated -= amount;
+        commitment.current_value = SafeMath::add(commitment.current_value, amount);
+        set_commitment(&e, &commitment);
+
+        set_reentrancy_guard(&e, false);
+
+        e.events().publish(
+            (symbol_short!("Dealloc"), commitment_id),
+            (amount, e.ledger().timestamp()),
+        );
+    }
+
+    /// Credit `amount` of pool yield harvested for an `Active` or
+    /// `Breaching` commitment to its `current_value`.
+    ///
+    /// Only the registered allocation contract may call this, after it has
+    /// transferred the tokens to this contract.
+    pub fn credit_yield(e: Env, commitment_id: String, amount: i128) {
+        require_migrated(&e, "credit_yield");
+
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // Check if contract is paused
+        Pausable::require_not_paused(&e);
+
+        require_allocation_contract(&e, "credit_yield");
+
+        if amount <= 0 {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InvalidAmount, "credit_yield");
+        }
+
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "credit_yield")
+        });
+
+        if !commitment.status.is_open() {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::NotActive, "credit_yield");
+        }
+
+        commitment.current_value = SafeMath::add(commitment.current_value, amount);
+        set_commitment(&e, &commitment);
+
+        // Yield is new value locked; settle and early_exit remove it with the payout
+        let current_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLocked)
+            .unwrap_or(0);
+        e.storage().instance().set(
+            &DataKey::TotalValueLocked,
+            &SafeMath::add(current_tvl, amount),
+        );
+
+        set_reentrancy_guard(&e, false);
+
+        e.events().publish(
+            (symbol_short!("Yield"), commitment_id),
             (amount, e.ledger().timestamp()),
         );
     }
@@ -1,12 +1,18 @@
 #![cfg(test)]
 
 use super::*;
+use commitment_interface::{INTERFACE_SPEC, INTERNAL_SPEC};
 use shared_utils::TimeUtils;
 use soroban_sdk::{
     contract, contractimpl, symbol_short,
-    testutils::{Address as _, Events, Ledger},
+    testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, Events, Ledger,
+    },
     token::StellarAssetClient,
-    vec, Address, Env, IntoVal, String,
+    vec,
+    xdr::{Limits, ReadXdr, ScSpecEntry, ScSpecFunctionV0},
+    Address, Env, IntoVal, String,
 };
 
 #[contract]
@@ -27,6 +33,90 @@ impl MockNftContract {
     ) -> u32 {
         1
     }
+
+    pub fn settle(_e: Env, _token_id: u32) {}
+
+    pub fn mark_inactive(_e: Env, _token_id: u32) {}
+
+    pub fn update_amount(_e: Env, _token_id: u32, _new_amount: i128) {}
+
+    pub fn renew(
+        _e: Env,
+        _token_id: u32,
+        _duration_days: u32,
+        _max_loss_percent: u32,
+        _commitment_type: String,
+        _initial_amount: i128,
+        _early_exit_penalty: u32,
+    ) {
+    }
+}
+
+/// Stands in for allocation_logic: `close_allocation` mints the reclaimed
+/// principal and yield to core as if they had been pulled back from the
+/// pools.
+#[contract]
+struct MockAllocationContract;
+
+#[contractimpl]
+impl MockAllocationContract {
+    pub fn set_reclaim(e: Env, core: Address, principal: i128, yield_amount: i128) {
+        e.storage().instance().set(&symbol_short!("core"), &core);
+        e.storage()
+            .instance()
+            .set(&symbol_short!("amount"), &(principal, yield_amount));
+    }
+
+    pub fn close_allocation(e: Env, _commitment_id: String, asset: Address) -> (i128, i128) {
+        let core: Address = e.storage().instance().get(&symbol_short!("core")).unwrap();
+        let (principal, yield_amount): (i128, i128) = e
+            .storage()
+            .instance()
+            .get(&symbol_short!("amount"))
+            .unwrap();
+        if principal + yield_amount > 0 {
+            StellarAssetClient::new(&e, &asset).mint(&core, &(principal + yield_amount));
+        }
+        (principal, yield_amount)
+    }
+}
+
+/// Stands in for price_oracle: `get_price_valid` fails for missing prices
+/// and prices older than the override (or one hour).
+#[contract]
+struct MockPriceOracle;
+
+#[contractimpl]
+impl MockPriceOracle {
+    pub fn set_price(e: Env, asset: Address, price: i128, decimals: u32, updated_at: u64) {
+        let data = PriceData {
+            price,
+            updated_at,
+            decimals,
+        };
+        e.storage().instance().set(&asset, &data);
+    }
+
+    pub fn get_price_valid(
+        e: Env,
+        asset: Address,
+        max_staleness_override: Option<u64>,
+    ) -> PriceData {
+        let data: PriceData = e.storage().instance().get(&asset).unwrap();
+        let max_staleness = max_staleness_override.unwrap_or(3600);
+        if e.ledger().timestamp() - data.updated_at > max_staleness {
+            panic!("stale price");
+        }
+        data
+    }
+}
+
+/// Grant `Role::Valuer` to a fresh address and return it.
+fn grant_valuer(e: &Env, contract_id: &Address) -> Address {
+    let client = CommitmentCoreContractClient::new(e, contract_id);
+    let valuer = Address::generate(e);
+    client.grant_role(&client.get_admin(), &Role::Valuer, &valuer);
+    valuer
 }
 
 fn test_rules(e: &Env) -> CommitmentRules {
@@ -144,7 +234,8 @@ fn test_update_value_zero_behaves() {
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.update_value(&commitment_id, &0);
+    let valuer = grant_valuer(&e, &contract_id);
+    client.update_value(&valuer, &commitment_id, &0);
     let updated = client.get_commitment(&commitment_id);
     assert_eq!(updated.current_value, 0);
 }
@@ -171,7 +262,8 @@ fn test_update_value_negative_panics() {
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.update_value(&commitment_id, &-100);
+    let valuer = grant_valuer(&e, &contract_id);
+    client.update_value(&valuer, &commitment_id, &-100);
 }
 
 #[test]
@@ -238,7 +330,8 @@ fn create_test_commitment(
         created_at,
         expires_at,
         current_value,
-        status: String::from_str(e, "active"),
+        allocated: 0,
+        status: CommitmentStatus::Active,
     }
 }
 
@@ -685,9 +778,7 @@ fn test_list_commitments_by_owner_matches_get_owner_commitments() {
             String::from_str(&e, "c_1"),
             String::from_str(&e, "c_2"),
         ];
-        e.storage()
-            .instance()
-            .set(&DataKey::OwnerCommitments(owner.clone()), &ids);
+        write_owner_commitments(&e, &owner, &ids);
     });
 
     let via_get = e.as_contract(&contract_id, || {
@@ -891,7 +982,7 @@ fn test_get_commitment_returns_created_commitment_data() {
     assert_eq!(fetched.amount, amount);
     assert_eq!(fetched.current_value, amount);
     assert_eq!(fetched.asset_address, asset_address);
-    assert_eq!(fetched.status, String::from_str(&e, "active"));
+    assert_eq!(fetched.status, CommitmentStatus::Active);
 }
 
 #[test]
@@ -1324,7 +1415,8 @@ fn test_update_value_event() {
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.update_value(&commitment_id, &1100);
+    client.grant_role(&admin, &Role::Valuer, &updater);
+    client.update_value(&updater, &commitment_id, &1100);
 
     let updated = client.get_commitment(&commitment_id);
     assert_eq!(updated.current_value, 1100);
@@ -1369,10 +1461,11 @@ fn test_update_value_rate_limit_enforced() {
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.grant_role(&admin, &Role::Valuer, &updater);
     // First call — allowed
-    client.update_value(&commitment_id, &100);
+    client.update_value(&updater, &commitment_id, &100);
     // Second call — should hit rate limit and panic
-    client.update_value(&commitment_id, &200);
+    client.update_value(&updater, &commitment_id, &200);
 }
 
 #[test]
@@ -1474,9 +1567,14 @@ fn test_early_exit_event() {
 #[should_panic(expected = "Commitment not found")]
 fn test_allocate_event() {
     let e = Env::default();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let allocation_contract = Address::generate(&e);
     let target_pool = Address::generate(&e);
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &Address::generate(&e));
+    client.set_allocation_contract(&admin, &allocation_contract);
 
     let commitment_id = String::from_str(&e, "test_id");
     // This will panic because commitment doesn't exist
@@ -1499,11 +1597,16 @@ fn test_allocate_when_settled_fails() {
 
     e.as_contract(&contract_id, || {
         CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::set_allocation_contract(
+            e.clone(),
+            admin.clone(),
+            Address::generate(&e),
+        );
     });
 
     let mut commitment =
         create_test_commitment(&e, commitment_id, &owner, 1000, 1000, 10, 30, 1000);
-    commitment.status = String::from_str(&e, "settled");
+    commitment.status = CommitmentStatus::Settled;
     store_commitment(&e, &contract_id, &commitment);
 
     e.as_contract(&contract_id, || {
@@ -1531,11 +1634,16 @@ fn test_allocate_when_violated_fails() {
 
     e.as_contract(&contract_id, || {
         CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::set_allocation_contract(
+            e.clone(),
+            admin.clone(),
+            Address::generate(&e),
+        );
     });
 
     let mut commitment =
         create_test_commitment(&e, commitment_id, &owner, 1000, 1000, 10, 30, 1000);
-    commitment.status = String::from_str(&e, "violated");
+    commitment.status = CommitmentStatus::Violated;
     store_commitment(&e, &contract_id, &commitment);
 
     e.as_contract(&contract_id, || {
@@ -1563,11 +1671,16 @@ fn test_allocate_when_early_exit_fails() {
 
     e.as_contract(&contract_id, || {
         CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::set_allocation_contract(
+            e.clone(),
+            admin.clone(),
+            Address::generate(&e),
+        );
     });
 
     let mut commitment =
         create_test_commitment(&e, commitment_id, &owner, 1000, 1000, 10, 30, 1000);
-    commitment.status = String::from_str(&e, "early_exit");
+    commitment.status = CommitmentStatus::EarlyExit;
     store_commitment(&e, &contract_id, &commitment);
 
     e.as_contract(&contract_id, || {
@@ -1603,6 +1716,11 @@ fn test_allocate_when_active_succeeds() {
 
     e.as_contract(&contract_id, || {
         CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::set_allocation_contract(
+            e.clone(),
+            admin.clone(),
+            Address::generate(&e),
+        );
     });
 
     let mut commitment =
@@ -1618,7 +1736,291 @@ fn test_allocate_when_active_succeeds() {
 
     let updated = client.get_commitment(&String::from_str(&e, commitment_id));
     assert_eq!(updated.current_value, 750);
-    assert_eq!(updated.status, String::from_str(&e, "active"));
+    assert_eq!(updated.allocated, 250);
+    assert_eq!(updated.status, CommitmentStatus::Active);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: caller not allowed")]
+fn test_allocate_without_allocation_contract_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let owner = Address::generate(&e);
+    client.initialize(&Address::generate(&e), &Address::generate(&e));
+
+    let commitment = create_test_commitment(&e, "no_allocator", &owner, 1000, 1000, 10, 30, 1000);
+    store_commitment(&e, &contract_id, &commitment);
+
+    client.allocate(
+        &String::from_str(&e, "no_allocator"),
+        &Address::generate(&e),
+        &100,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: caller not allowed")]
+fn test_set_allocation_contract_non_admin_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&Address::generate(&e), &Address::generate(&e));
+
+    let attacker = Address::generate(&e);
+    client.set_allocation_contract(&attacker, &attacker);
+}
+
+#[test]
+fn test_deallocate_credits_current_value() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+    client.set_allocation_contract(&admin, &Address::generate(&e));
+
+    let mut commitment = create_test_commitment(&e, "dealloc", &owner, 1000, 600, 50, 30, 1000);
+    commitment.allocated = 400;
+    store_commitment(&e, &contract_id, &commitment);
+
+    client.deallocate(&commitment.commitment_id, &400);
+
+    let updated = client.get_commitment(&commitment.commitment_id);
+    assert_eq!(updated.current_value, 1000);
+    assert_eq!(updated.allocated, 0);
+    assert_eq!(updated.status, CommitmentStatus::Active);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient balance")]
+fn test_deallocate_more_than_allocated_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+    client.set_allocation_contract(&admin, &Address::generate(&e));
+
+    let mut commitment = create_test_commitment(&e, "dealloc", &owner, 1000, 600, 50, 30, 1000);
+    commitment.allocated = 400;
+    store_commitment(&e, &contract_id, &commitment);
+
+    client.deallocate(&commitment.commitment_id, &401);
+}
+
+#[test]
+fn test_credit_yield_adds_to_current_value() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+    client.set_allocation_contract(&admin, &Address::generate(&e));
+
+    let mut commitment = create_test_commitment(&e, "yield", &owner, 1000, 600, 10, 30, 1000);
+    commitment.allocated = 400;
+    store_commitment(&e, &contract_id, &commitment);
+
+    client.credit_yield(&commitment.commitment_id, &50);
+
+    let updated = client.get_commitment(&commitment.commitment_id);
+    assert_eq!(updated.current_value, 650);
+    assert_eq!(updated.allocated, 400);
+}
+
+#[test]
+fn test_settle_after_credit_yield_clears_tvl() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    let allocation_contract = e.register_contract(None, MockAllocationContract);
+    MockAllocationContractClient::new(&e, &allocation_contract).set_reclaim(&contract_id, &400, &0);
+    client.set_allocation_contract(&client.get_admin(), &allocation_contract);
+
+    client.allocate(&commitment_id, &Address::generate(&e), &400);
+    assert_eq!(client.get_total_value_locked(), 1000);
+
+    // A harvest pays the yield to core, then credits it
+    let asset = client.get_commitment(&commitment_id).asset_address;
+    StellarAssetClient::new(&e, &asset).mint(&contract_id, &50);
+    client.credit_yield(&commitment_id, &50);
+    assert_eq!(client.get_total_value_locked(), 1050);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    assert_eq!(client.get_total_value_locked(), 0);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 10_050);
+}
+
+#[test]
+fn test_allocation_beyond_loss_limit_is_not_a_loss() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.set_allocation_contract(&client.get_admin(), &Address::generate(&e));
+
+    // 40% of the value goes to pools; the loss limit is 10%
+    client.allocate(&commitment_id, &Address::generate(&e), &400);
+
+    assert!(!client.check_violations(&commitment_id));
+    let (has_violations, loss_violated, _, loss_percent, _) =
+        client.get_violation_details(&commitment_id);
+    assert!(!has_violations);
+    assert!(!loss_violated);
+    assert_eq!(loss_percent, 0);
+
+    // The next valuation at an unchanged value keeps the commitment Active
+    let valuer = grant_valuer(&e, &contract_id);
+    client.update_value(&valuer, &commitment_id, &600);
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        CommitmentStatus::Active
+    );
+    assert!(!client.check_violations(&commitment_id));
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: caller not allowed")]
+fn test_deallocate_without_allocation_contract_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let owner = Address::generate(&e);
+    client.initialize(&Address::generate(&e), &Address::generate(&e));
+
+    let commitment = create_test_commitment(&e, "no_allocator", &owner, 1000, 600, 50, 30, 1000);
+    store_commitment(&e, &contract_id, &commitment);
+
+    client.deallocate(&commitment.commitment_id, &400);
+}
+
+#[test]
+#[should_panic(expected = "Commitment is not active")]
+fn test_deallocate_when_settled_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+    client.set_allocation_contract(&admin, &Address::generate(&e));
+
+    let mut commitment =
+        create_test_commitment(&e, "dealloc_settled", &owner, 1000, 0, 10, 30, 1000);
+    commitment.status = CommitmentStatus::Settled;
+    store_commitment(&e, &contract_id, &commitment);
+
+    client.deallocate(&commitment.commitment_id, &400);
+}
+
+#[test]
+fn test_settle_reclaims_allocated_funds() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    let allocation_contract = e.register_contract(None, MockAllocationContract);
+    MockAllocationContractClient::new(&e, &allocation_contract).set_reclaim(&contract_id, &400, &0);
+    client.set_allocation_contract(&client.get_admin(), &allocation_contract);
+
+    client.allocate(&commitment_id, &Address::generate(&e), &400);
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 600);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, CommitmentStatus::Settled);
+    assert_eq!(commitment.allocated, 0);
+    let asset = token::Client::new(&e, &commitment.asset_address);
+    assert_eq!(asset.balance(&owner), 10_000);
+}
+
+#[test]
+fn test_settle_pays_unharvested_yield() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    let allocation_contract = e.register_contract(None, MockAllocationContract);
+    MockAllocationContractClient::new(&e, &allocation_contract).set_reclaim(
+        &contract_id,
+        &400,
+        &30,
+    );
+    client.set_allocation_contract(&client.get_admin(), &allocation_contract);
+
+    client.allocate(&commitment_id, &Address::generate(&e), &400);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    assert_eq!(client.get_total_value_locked(), 0);
+    let asset = token::Client::new(&e, &client.get_commitment(&commitment_id).asset_address);
+    assert_eq!(asset.balance(&owner), 10_030);
+    assert_eq!(asset.balance(&contract_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Reclaimed allocation does not match allocated principal")]
+fn test_settle_with_mismatched_reclaim_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    // The allocation contract hands back more than was allocated
+    let allocation_contract = e.register_contract(None, MockAllocationContract);
+    MockAllocationContractClient::new(&e, &allocation_contract).set_reclaim(&contract_id, &900, &0);
+    client.set_allocation_contract(&client.get_admin(), &allocation_contract);
+
+    client.allocate(&commitment_id, &Address::generate(&e), &400);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+}
+
+#[test]
+fn test_early_exit_reclaims_allocated_funds() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    let allocation_contract = e.register_contract(None, MockAllocationContract);
+    MockAllocationContractClient::new(&e, &allocation_contract).set_reclaim(&contract_id, &400, &0);
+    client.set_allocation_contract(&client.get_admin(), &allocation_contract);
+
+    client.allocate(&commitment_id, &Address::generate(&e), &400);
+    client.early_exit(&commitment_id, &owner);
+
+    // The 5% penalty applies to the full 1000, including the reclaimed 400
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, CommitmentStatus::EarlyExit);
+    let asset = token::Client::new(&e, &commitment.asset_address);
+    assert_eq!(asset.balance(&owner), 9_000 + 950);
 }
 
 /// Helper function to create a test commitment with custom penalty
@@ -1652,7 +2054,8 @@ fn create_test_commitment_with_penalty(
         created_at,
         expires_at,
         current_value,
-        status: String::from_str(e, "active"),
+        allocated: 0,
+        status: CommitmentStatus::Active,
     }
 }
 
@@ -1735,7 +2138,7 @@ fn test_early_exit_already_settled() {
         create_test_commitment(&e, commitment_id, &owner, 1000, 1000, 10, 30, 1000);
 
     // Mark as settled
-    commitment.status = String::from_str(&e, "settled");
+    commitment.status = CommitmentStatus::Settled;
     store_commitment(&e, &contract_id, &commitment);
 
     // Try to exit already settled commitment
@@ -1768,7 +2171,7 @@ fn test_early_exit_already_violated() {
         create_test_commitment(&e, commitment_id, &owner, 1000, 1000, 10, 30, 1000);
 
     // Mark as violated
-    commitment.status = String::from_str(&e, "violated");
+    commitment.status = CommitmentStatus::Violated;
     store_commitment(&e, &contract_id, &commitment);
 
     // Try to exit violated commitment
@@ -1801,7 +2204,7 @@ fn test_early_exit_already_exited() {
         create_test_commitment(&e, commitment_id, &owner, 1000, 1000, 10, 30, 1000);
 
     // Mark as early_exit
-    commitment.status = String::from_str(&e, "early_exit");
+    commitment.status = CommitmentStatus::EarlyExit;
     store_commitment(&e, &contract_id, &commitment);
 
     // Try to exit again
@@ -1843,7 +2246,7 @@ fn test_early_exit_state_update() {
         CommitmentCoreContract::get_commitment(e.clone(), String::from_str(&e, commitment_id))
     });
 
-    assert_eq!(initial_commitment.status, String::from_str(&e, "active"));
+    assert_eq!(initial_commitment.status, CommitmentStatus::Active);
     assert_eq!(initial_commitment.current_value, 1000);
 }
 
@@ -2091,10 +2494,11 @@ fn test_early_exit_status_transition() {
         CommitmentCoreContract::get_commitment(e.clone(), String::from_str(&e, commitment_id))
     });
 
-    assert_eq!(before.status, String::from_str(&e, "active"));
+    assert_eq!(before.status, CommitmentStatus::Active);
 }
 #[test]
-fn test_update_value_updates_without_updater_param() {
+#[should_panic(expected = "Unauthorized")]
+fn test_update_value_by_non_valuer_fails() {
     let e = Env::default();
     e.mock_all_auths();
     let contract_id = e.register_contract(None, CommitmentCoreContract);
@@ -2105,8 +2509,66 @@ fn test_update_value_updates_without_updater_param() {
         CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
         let commitment = create_test_commitment(&e, "test_id", &owner, 1000, 1000, 10, 30, 1000);
         set_commitment(&e, &commitment);
-        CommitmentCoreContract::update_value(e.clone(), String::from_str(&e, "test_id"), 900);
     });
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let stranger = Address::generate(&e);
+    client.update_value(&stranger, &String::from_str(&e, "test_id"), &900);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_update_value_after_role_revoked_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let valuer = grant_valuer(&e, &contract_id);
+
+    client.update_value(&valuer, &commitment_id, &950);
+    client.revoke_role(&client.get_admin(), &Role::Valuer, &valuer);
+    assert!(!client.has_role(&Role::Valuer, &valuer));
+    client.update_value(&valuer, &commitment_id, &900);
+}
+
+#[test]
+fn test_grant_role_emits_event() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+
+    let keeper = Address::generate(&e);
+    assert!(!client.has_role(&Role::Valuer, &keeper));
+    client.grant_role(&admin, &Role::Valuer, &keeper);
+    assert!(client.has_role(&Role::Valuer, &keeper));
+
+    let events = e.events().all();
+    let (_, topics, data) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        vec![
+            &e,
+            symbol_short!("RoleGrant").into_val(&e),
+            Role::Valuer.into_val(&e)
+        ]
+    );
+    let (account, _timestamp): (Address, u64) = data.into_val(&e);
+    assert_eq!(account, keeper);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_grant_role_non_admin_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&Address::generate(&e), &Address::generate(&e));
+
+    let attacker = Address::generate(&e);
+    client.grant_role(&attacker, &Role::Valuer, &attacker);
 }
 
 #[test]
@@ -2126,11 +2588,12 @@ fn test_update_value_no_violation() {
             .set(&DataKey::TotalValueLocked, &1000i128);
     });
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.update_value(&String::from_str(&e, "test_id"), &950);
+    let valuer = grant_valuer(&e, &contract_id);
+    client.update_value(&valuer, &String::from_str(&e, "test_id"), &950);
 
     let updated = client.get_commitment(&String::from_str(&e, "test_id"));
     assert_eq!(updated.current_value, 950);
-    assert_eq!(updated.status, String::from_str(&e, "active"));
+    assert_eq!(updated.status, CommitmentStatus::Active);
     assert_eq!(client.get_total_value_locked(), 950);
     
     // Verify ValueUpdated event was emitted
@@ -2160,12 +2623,13 @@ fn test_update_value_triggers_violation() {
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let valuer = grant_valuer(&e, &contract_id);
     // Update to 850: loss = (1000-850)/1000 = 15% > 10% max_loss_percent
-    client.update_value(&String::from_str(&e, "test_id"), &850);
+    client.update_value(&valuer, &String::from_str(&e, "test_id"), &850);
 
     let updated = client.get_commitment(&String::from_str(&e, "test_id"));
     assert_eq!(updated.current_value, 850);
-    assert_eq!(updated.status, String::from_str(&e, "violated"));
+    assert_eq!(updated.status, CommitmentStatus::Violated);
     
     // Verify ViolationDetected event was emitted
     let events = e.events().all();
@@ -2176,25 +2640,300 @@ fn test_update_value_triggers_violation() {
     assert!(has_violation, "ViolationDetected event should be emitted");
 }
 
+/// Register a mock oracle with core and return it with the commitment's asset.
+fn setup_price_oracle(
+    e: &Env,
+    contract_id: &Address,
+    commitment_id: &String,
+    max_staleness: Option<u64>,
+) -> (MockPriceOracleClient<'static>, Address) {
+    let client = CommitmentCoreContractClient::new(e, contract_id);
+    let oracle = MockPriceOracleClient::new(e, &e.register_contract(None, MockPriceOracle));
+    client.set_price_oracle(&client.get_admin(), &oracle.address, &max_staleness);
+    let asset = client.get_commitment(commitment_id).asset_address;
+    (oracle, asset)
+}
+
 #[test]
-fn test_check_violations_after_update_value() {
+fn test_update_value_from_oracle_tracks_price() {
     let e = Env::default();
-    e.mock_all_auths();
-    let contract_id = e.register_contract(None, CommitmentCoreContract);
-    let admin = Address::generate(&e);
-    let nft_contract = Address::generate(&e);
-    let owner = Address::generate(&e);
-    e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
-        let commitment = create_test_commitment(&e, "test_id", &owner, 1000, 1000, 10, 30, 1000);
-        set_commitment(&e, &commitment);
-        e.storage()
-            .instance()
-            .set(&DataKey::TotalValueLocked, &1000i128);
-    });
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let (oracle, asset) = setup_price_oracle(&e, &contract_id, &commitment_id, None);
+    let now = e.ledger().timestamp();
+
+    // The first valuation only records the price
+    oracle.set_price(&asset, &100, &2, &now);
+    assert_eq!(client.update_value_from_oracle(&commitment_id), 1_000);
+
+    // 1.00 -> 0.9500 (different decimals): 5% loss, within the 10% limit
+    oracle.set_price(&asset, &9_500, &4, &now);
+    assert_eq!(client.update_value_from_oracle(&commitment_id), 950);
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, CommitmentStatus::Active);
+    assert_eq!(client.get_total_value_locked(), 950);
+
+    // 0.95 -> 0.85: value 850, a 15% loss
+    oracle.set_price(&asset, &85, &2, &now);
+    assert_eq!(client.update_value_from_oracle(&commitment_id), 850);
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.current_value, 850);
+    assert_eq!(commitment.status, CommitmentStatus::Violated);
+    assert_eq!(client.get_total_value_locked(), 850);
+}
 
+#[test]
+fn test_update_value_from_oracle_values_allocated_principal() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    
+    client.set_allocation_contract(&client.get_admin(), &Address::generate(&e));
+    let (oracle, asset) = setup_price_oracle(&e, &contract_id, &commitment_id, None);
+    let now = e.ledger().timestamp();
+    oracle.set_price(&asset, &100, &2, &now);
+    client.update_value_from_oracle(&commitment_id);
+
+    client.allocate(&commitment_id, &Address::generate(&e), &400);
+
+    // An unchanged price leaves the whole position intact
+    assert_eq!(client.update_value_from_oracle(&commitment_id), 1_000);
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.current_value, 600);
+    assert_eq!(commitment.status, CommitmentStatus::Active);
+
+    // A 5% drop applies to all 1000, not just the 600 held in core
+    oracle.set_price(&asset, &95, &2, &now);
+    assert_eq!(client.update_value_from_oracle(&commitment_id), 950);
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.current_value, 550);
+    assert_eq!(commitment.allocated, 400);
+    assert_eq!(commitment.status, CommitmentStatus::Active);
+    assert_eq!(client.quote_early_exit(&commitment_id), (47, 903));
+}
+
+#[test]
+fn test_update_value_from_oracle_has_own_rate_limit() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let (oracle, asset) = setup_price_oracle(&e, &contract_id, &commitment_id, None);
+    let admin = client.get_admin();
+    let now = e.ledger().timestamp();
+    oracle.set_price(&asset, &100, &2, &now);
+
+    // Exhausting the oracle path leaves update_value's limit untouched
+    client.set_rate_limit(&admin, &symbol_short!("upd_val"), &60, &1);
+    client.set_rate_limit(&admin, &symbol_short!("upd_orcl"), &60, &2);
+    client.update_value_from_oracle(&commitment_id);
+    client.update_value_from_oracle(&commitment_id);
+    let valuer = grant_valuer(&e, &contract_id);
+    client.update_value(&valuer, &commitment_id, &990);
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 990);
+
+    assert!(client.try_update_value_from_oracle(&commitment_id).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Oracle price is missing, stale or invalid")]
+fn test_update_value_from_oracle_rejects_stale_price() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    e.ledger().with_mut(|l| l.timestamp = 10_000);
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let (oracle, asset) = setup_price_oracle(&e, &contract_id, &commitment_id, Some(60));
+
+    // Within the oracle's one-hour default, but older than core's override
+    oracle.set_price(&asset, &100, &2, &(10_000 - 120));
+    client.update_value_from_oracle(&commitment_id);
+}
+
+#[test]
+#[should_panic(expected = "Oracle price is missing, stale or invalid")]
+fn test_update_value_from_oracle_rejects_zero_price() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let (oracle, asset) = setup_price_oracle(&e, &contract_id, &commitment_id, None);
+
+    oracle.set_price(&asset, &0, &2, &e.ledger().timestamp());
+    client.update_value_from_oracle(&commitment_id);
+}
+
+#[test]
+#[should_panic(expected = "Price oracle not configured")]
+fn test_update_value_from_oracle_without_oracle_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.update_value_from_oracle(&commitment_id);
+}
+
+/// Store a 1000-unit commitment with a 10% loss limit and `grace_period_days`
+/// grace, and return a client, a valuer and the commitment id.
+fn setup_grace_period(
+    e: &Env,
+    grace_period_days: u32,
+) -> (CommitmentCoreContractClient<'static>, Address, String) {
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(e, &contract_id);
+    client.initialize(&Address::generate(e), &Address::generate(e));
+
+    let owner = Address::generate(e);
+    let mut commitment = create_test_commitment(e, "c_0", &owner, 1000, 1000, 10, 30, 0);
+    commitment.rules.grace_period_days = grace_period_days;
+    store_commitment(e, &contract_id, &commitment);
+
+    let valuer = grant_valuer(e, &contract_id);
+    (client, valuer, commitment.commitment_id)
+}
+
+#[test]
+fn test_update_value_breach_becomes_violated_after_grace_period() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (client, valuer, commitment_id) = setup_grace_period(&e, 2);
+
+    client.update_value(&valuer, &commitment_id, &850);
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        CommitmentStatus::Breaching
+    );
+    assert_eq!(client.get_breach_started_at(&commitment_id), Some(0));
+
+    // Still losing one day later: the timer keeps running from the first breach
+    e.ledger().with_mut(|l| l.timestamp = 86400);
+    client.update_value(&valuer, &commitment_id, &800);
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        CommitmentStatus::Breaching
+    );
+    assert_eq!(client.get_breach_started_at(&commitment_id), Some(0));
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    client.update_value(&valuer, &commitment_id, &800);
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, CommitmentStatus::Violated);
+    assert_eq!(commitment.current_value, 800);
+    assert_eq!(client.get_breach_started_at(&commitment_id), None);
+}
+
+#[test]
+fn test_update_value_breach_recovers_to_active() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (client, valuer, commitment_id) = setup_grace_period(&e, 2);
+
+    client.update_value(&valuer, &commitment_id, &850);
+    e.ledger().with_mut(|l| l.timestamp = 86400);
+    client.update_value(&valuer, &commitment_id, &950);
+
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        CommitmentStatus::Active
+    );
+    assert_eq!(client.get_breach_started_at(&commitment_id), None);
+
+    let events = e.events().all();
+    let (_, topics, _) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        vec![
+            &e,
+            symbol_short!("Recovered").into_val(&e),
+            commitment_id.into_val(&e)
+        ]
+    );
+}
+
+#[test]
+fn test_check_breach_violates_after_grace_period() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (client, valuer, commitment_id) = setup_grace_period(&e, 1);
+
+    client.update_value(&valuer, &commitment_id, &850);
+    assert_eq!(
+        client.check_breach(&commitment_id),
+        CommitmentStatus::Breaching
+    );
+
+    e.ledger().with_mut(|l| l.timestamp = 86400);
+    assert_eq!(
+        client.check_breach(&commitment_id),
+        CommitmentStatus::Violated
+    );
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        CommitmentStatus::Violated
+    );
+}
+
+#[test]
+fn test_check_violations_respects_grace_period() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (client, valuer, commitment_id) = setup_grace_period(&e, 2);
+
+    client.update_value(&valuer, &commitment_id, &850);
+    assert!(!client.check_violations(&commitment_id));
+    let (has_violations, loss_violated, _, loss_percent, _) =
+        client.get_violation_details(&commitment_id);
+    assert!(!has_violations);
+    assert!(!loss_violated);
+    assert_eq!(loss_percent, 15);
+
+    // Grace runs out two days after the breach started
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    let events_before = e.events().all().len();
+    assert!(client.check_violations(&commitment_id));
+    let (has_violations, loss_violated, duration_violated, _, _) =
+        client.get_violation_details(&commitment_id);
+    assert!(has_violations);
+    assert!(loss_violated);
+    assert!(!duration_violated);
+
+    // Views leave status and events alone
+    assert_eq!(e.events().all().len(), events_before);
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        CommitmentStatus::Breaching
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid commitment status for this operation")]
+fn test_check_breach_on_active_commitment_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (client, _valuer, commitment_id) = setup_grace_period(&e, 1);
+    client.check_breach(&commitment_id);
+}
+
+#[test]
+fn test_check_violations_after_update_value() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let admin = Address::generate(&e);
+    let nft_contract = Address::generate(&e);
+    let owner = Address::generate(&e);
+    e.as_contract(&contract_id, || {
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        let commitment = create_test_commitment(&e, "test_id", &owner, 1000, 1000, 10, 30, 1000);
+        set_commitment(&e, &commitment);
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLocked, &1000i128);
+    });
+
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    
     // Before update, no violations
     assert!(!client.check_violations(&String::from_str(&e, "test_id")));
     
@@ -2239,7 +2978,7 @@ fn test_owner_multiple_commitments_creation() {
         owner_commitments.push_back(c1.commitment_id.clone());
         owner_commitments.push_back(c2.commitment_id.clone());
         owner_commitments.push_back(c3.commitment_id.clone());
-        e.storage().instance().set(&DataKey::OwnerCommitments(owner.clone()), &owner_commitments);
+        write_owner_commitments(&e, &owner, &owner_commitments);
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
@@ -2275,7 +3014,7 @@ fn test_owner_multiple_commitments_get_each() {
         owner_commitments.push_back(c1.commitment_id.clone());
         owner_commitments.push_back(c2.commitment_id.clone());
         owner_commitments.push_back(c3.commitment_id.clone());
-        e.storage().instance().set(&DataKey::OwnerCommitments(owner.clone()), &owner_commitments);
+        write_owner_commitments(&e, &owner, &owner_commitments);
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
@@ -2320,10 +3059,10 @@ fn test_owner_multiple_commitments_settle_one() {
         owner_commitments.push_back(c1.commitment_id.clone());
         owner_commitments.push_back(c2.commitment_id.clone());
         owner_commitments.push_back(c3.commitment_id.clone());
-        e.storage().instance().set(&DataKey::OwnerCommitments(owner.clone()), &owner_commitments);
+        write_owner_commitments(&e, &owner, &owner_commitments);
         
         // Manually settle one commitment (simulating what settle() would do)
-        c2.status = String::from_str(&e, "settled");
+        c2.status = CommitmentStatus::Settled;
         set_commitment(&e, &c2);
     });
 
@@ -2335,12 +3074,1719 @@ fn test_owner_multiple_commitments_settle_one() {
 
     // Verify settled commitment status changed
     let c2 = client.get_commitment(&String::from_str(&e, "commit_002"));
-    assert_eq!(c2.status, String::from_str(&e, "settled"));
+    assert_eq!(c2.status, CommitmentStatus::Settled);
 
     // Verify other commitments remain active
     let c1 = client.get_commitment(&String::from_str(&e, "commit_001"));
-    assert_eq!(c1.status, String::from_str(&e, "active"));
+    assert_eq!(c1.status, CommitmentStatus::Active);
 
     let c3 = client.get_commitment(&String::from_str(&e, "commit_003"));
-    assert_eq!(c3.status, String::from_str(&e, "active"));
+    assert_eq!(c3.status, CommitmentStatus::Active);
+}
+
+// ============================================
+// Transferable Position Tests
+// ============================================
+
+fn setup_position(e: &Env) -> (CommitmentCoreContractClient<'_>, Address, Address, String) {
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let admin = Address::generate(e);
+    let nft_contract = Address::generate(e);
+    let owner = Address::generate(e);
+    let commitment_id = String::from_str(e, "position_1");
+
+    e.as_contract(&contract_id, || {
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        let commitment = create_test_commitment(e, "position_1", &owner, 1000, 1000, 10, 30, 0);
+        set_commitment(e, &commitment);
+        write_owner_commitments(e, &owner, &vec![e, commitment_id.clone()]);
+    });
+
+    (
+        CommitmentCoreContractClient::new(e, &contract_id),
+        nft_contract,
+        owner,
+        commitment_id,
+    )
+}
+
+#[test]
+fn test_transfer_position_reassigns_owner() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (client, nft_contract, owner, commitment_id) = setup_position(&e);
+    let new_owner = Address::generate(&e);
+
+    client.transfer_position(&commitment_id, &owner, &new_owner);
+
+    // Authorized by the NFT contract, not the previous owner
+    assert_eq!(e.auths()[0].0, nft_contract);
+
+    assert_eq!(client.get_commitment(&commitment_id).owner, new_owner);
+    assert_eq!(client.get_owner_commitments(&owner).len(), 0);
+    assert_eq!(
+        client.get_owner_commitments(&new_owner),
+        vec![&e, commitment_id.clone()]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_transfer_position_from_not_owner_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (client, _nft_contract, _owner, commitment_id) = setup_position(&e);
+    let stranger = Address::generate(&e);
+    let new_owner = Address::generate(&e);
+
+    client.transfer_position(&commitment_id, &stranger, &new_owner);
+}
+
+#[test]
+#[should_panic(expected = "Commitment is not active")]
+fn test_transfer_position_settled_commitment_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (client, _nft_contract, owner, commitment_id) = setup_position(&e);
+
+    e.as_contract(&client.address, || {
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap();
+        commitment.status = CommitmentStatus::Settled;
+        set_commitment(&e, &commitment);
+    });
+
+    client.transfer_position(&commitment_id, &owner, &Address::generate(&e));
+}
+
+#[test]
+fn test_violated_position_can_leave_marketplace_escrow() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (client, _nft_contract, owner, commitment_id) = setup_position(&e);
+    let marketplace = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    client.set_marketplace_contract(&client.get_admin(), &marketplace);
+
+    client.transfer_position(&commitment_id, &owner, &marketplace);
+    e.as_contract(&client.address, || {
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap();
+        commitment.status = CommitmentStatus::Violated;
+        set_commitment(&e, &commitment);
+    });
+
+    // A sale or cancellation must not strand the position in escrow
+    client.transfer_position(&commitment_id, &marketplace, &buyer);
+    assert_eq!(client.get_commitment(&commitment_id).owner, buyer);
+
+    // Outside escrow the position stays put
+    let result = client.try_transfer_position(&commitment_id, &buyer, &owner);
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "Position is held in marketplace escrow")]
+fn test_settle_escrowed_position_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let marketplace = Address::generate(&e);
+    client.set_marketplace_contract(&client.get_admin(), &marketplace);
+
+    client.transfer_position(&commitment_id, &owner, &marketplace);
+    assert_eq!(client.get_marketplace_contract(), Some(marketplace));
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+}
+
+#[test]
+#[should_panic]
+fn test_transfer_position_requires_nft_contract_auth() {
+    let e = Env::default();
+    let (client, _nft_contract, owner, commitment_id) = setup_position(&e);
+    let new_owner = Address::generate(&e);
+
+    client.transfer_position(&commitment_id, &owner, &new_owner);
+}
+
+#[test]
+fn test_early_exit_after_transfer_rejects_previous_owner() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (client, _nft_contract, owner, commitment_id) = setup_position(&e);
+    let new_owner = Address::generate(&e);
+
+    client.transfer_position(&commitment_id, &owner, &new_owner);
+
+    // The previous owner can no longer exit the position
+    let result = client.try_early_exit(&commitment_id, &owner);
+    assert!(result.is_err());
+}
+
+// ============================================
+// Persistent Storage / TTL Tests
+// ============================================
+
+/// Create a 1000-unit commitment over 30 days with a mock NFT contract.
+/// With a penalty curve it starts at a 20% penalty so the curve is visible.
+fn create_with_mock_nft(e: &Env, curve: Option<PenaltyCurve>) -> (Address, Address, String) {
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let nft_contract = e.register_contract(None, MockNftContract);
+    let admin = Address::generate(e);
+    let owner = Address::generate(e);
+    let token_admin = Address::generate(e);
+    let asset_address = e.register_stellar_asset_contract_v2(token_admin).address();
+    StellarAssetClient::new(e, &asset_address).mint(&owner, &10_000);
+
+    e.as_contract(&contract_id, || {
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract);
+    });
+    let commitment_id = e.as_contract(&contract_id, || match curve {
+        Some(curve) => {
+            let mut rules = test_rules(e);
+            rules.early_exit_penalty = 20;
+            CommitmentCoreContract::create_commitment_with_curve(
+                e.clone(),
+                owner.clone(),
+                1_000,
+                asset_address,
+                rules,
+                curve,
+                None,
+            )
+        }
+        None => CommitmentCoreContract::create_commitment(
+            e.clone(),
+            owner.clone(),
+            1_000,
+            asset_address,
+            test_rules(e),
+        ),
+    });
+    (contract_id, owner, commitment_id)
+}
+
+#[test]
+fn test_initialize_sets_current_version() {
+    let e = Env::default();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    assert_eq!(client.get_version(), 0);
+    client.initialize(&Address::generate(&e), &Address::generate(&e));
+    assert_eq!(client.get_version(), CURRENT_VERSION);
+}
+
+#[test]
+fn test_commitment_records_use_persistent_storage() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+
+    e.as_contract(&contract_id, || {
+        let key = DataKey::Commitment(commitment_id.clone());
+        assert!(e.storage().persistent().has(&key));
+        assert!(!e.storage().instance().has(&key));
+        assert!(e
+            .storage()
+            .persistent()
+            .has(&DataKey::OwnerCommitments(owner.clone())));
+        assert!(e.storage().persistent().has(&DataKey::CommitmentIndex(0)));
+        assert!(!e.storage().instance().has(&DataKey::AllCommitmentIds));
+        assert_eq!(
+            e.storage().persistent().get_ttl(&key),
+            COMMITMENT_TTL_EXTEND_TO
+        );
+    });
+}
+
+#[test]
+fn test_bump_commitment_extends_ttl() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    // Age the entries past the extension threshold
+    let elapsed = COMMITMENT_TTL_EXTEND_TO - COMMITMENT_TTL_THRESHOLD + 1;
+    e.ledger().with_mut(|li| li.sequence_number += elapsed);
+
+    let key = DataKey::Commitment(commitment_id.clone());
+    let owner_key = DataKey::OwnerCommitments(owner);
+    e.as_contract(&contract_id, || {
+        assert!(e.storage().persistent().get_ttl(&key) < COMMITMENT_TTL_THRESHOLD);
+    });
+
+    client.bump_commitment(&commitment_id);
+
+    e.as_contract(&contract_id, || {
+        assert_eq!(
+            e.storage().persistent().get_ttl(&key),
+            COMMITMENT_TTL_EXTEND_TO
+        );
+        assert_eq!(
+            e.storage().persistent().get_ttl(&owner_key),
+            COMMITMENT_TTL_EXTEND_TO
+        );
+        assert_eq!(e.storage().instance().get_ttl(), COMMITMENT_TTL_EXTEND_TO);
+    });
+}
+
+#[test]
+fn test_bump_commitment_extends_index_and_optional_entries() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, Some(PenaltyCurve::Linear));
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.set_rollover(&commitment_id, &owner, &true, &None);
+
+    let keys = [
+        DataKey::CommitmentIndex(0),
+        DataKey::PenaltyCurve(commitment_id.clone()),
+        DataKey::Rollover(commitment_id.clone()),
+        DataKey::LoyaltyCheckpoint(commitment_id.clone()),
+    ];
+    let elapsed = COMMITMENT_TTL_EXTEND_TO - COMMITMENT_TTL_THRESHOLD + 1;
+    e.ledger().with_mut(|li| li.sequence_number += elapsed);
+
+    client.bump_commitment(&commitment_id);
+
+    e.as_contract(&contract_id, || {
+        for key in keys.iter() {
+            assert_eq!(
+                e.storage().persistent().get_ttl(key),
+                COMMITMENT_TTL_EXTEND_TO
+            );
+        }
+    });
+}
+
+#[test]
+fn test_commitment_write_extends_index_entry() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    let elapsed = COMMITMENT_TTL_EXTEND_TO - COMMITMENT_TTL_THRESHOLD + 1;
+    e.ledger().with_mut(|li| li.sequence_number += elapsed);
+
+    let valuer = grant_valuer(&e, &contract_id);
+    client.update_value(&valuer, &commitment_id, &990);
+
+    e.as_contract(&contract_id, || {
+        assert_eq!(
+            e.storage()
+                .persistent()
+                .get_ttl(&DataKey::CommitmentIndex(0)),
+            COMMITMENT_TTL_EXTEND_TO
+        );
+    });
+}
+
+#[test]
+#[should_panic(expected = "Commitment not found")]
+fn test_bump_commitment_nonexistent_fails() {
+    let e = Env::default();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&Address::generate(&e), &Address::generate(&e));
+
+    client.bump_commitment(&String::from_str(&e, "missing"));
+}
+
+/// Encode `commitment` the way pre-v2 storage did (string status).
+fn to_legacy(e: &Env, commitment: &Commitment) -> LegacyCommitment {
+    LegacyCommitment {
+        commitment_id: commitment.commitment_id.clone(),
+        owner: commitment.owner.clone(),
+        nft_token_id: commitment.nft_token_id,
+        rules: commitment.rules.clone(),
+        amount: commitment.amount,
+        asset_address: commitment.asset_address.clone(),
+        created_at: commitment.created_at,
+        expires_at: commitment.expires_at,
+        current_value: commitment.current_value,
+        status: String::from_str(e, commitment.status.as_str()),
+    }
+}
+
+#[test]
+fn test_migrate_moves_legacy_instance_records() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let commitment = create_test_commitment(&e, "c_0", &owner, 1000, 1000, 10, 30, 1000);
+    let commitment_id = commitment.commitment_id.clone();
+
+    // v0 layout: everything in instance storage, no version key
+    e.as_contract(&contract_id, || {
+        let instance = e.storage().instance();
+        instance.set(&DataKey::Admin, &admin);
+        instance.set(&DataKey::NftContract, &Address::generate(&e));
+        instance.set(&DataKey::TotalCommitments, &1u64);
+        instance.set(
+            &DataKey::Commitment(commitment_id.clone()),
+            &to_legacy(&e, &commitment),
+        );
+        instance.set(
+            &DataKey::OwnerCommitments(owner.clone()),
+            &vec![&e, commitment_id.clone()],
+        );
+        instance.set(&DataKey::AllCommitmentIds, &vec![&e, commitment_id.clone()]);
+    });
+    assert_eq!(client.get_version(), 0);
+
+    assert!(client.migrate(&admin, &0, &MAX_MIGRATION_BATCH));
+
+    assert_eq!(client.get_version(), CURRENT_VERSION);
+    assert_eq!(client.get_commitment(&commitment_id), commitment);
+    assert_eq!(
+        client.get_owner_commitments(&owner),
+        vec![&e, commitment_id.clone()]
+    );
+    assert_eq!(
+        client.get_commitments_created_between(&0, &u64::MAX),
+        vec![&e, commitment_id.clone()]
+    );
+    e.as_contract(&contract_id, || {
+        let instance = e.storage().instance();
+        assert!(!instance.has(&DataKey::Commitment(commitment_id.clone())));
+        assert!(!instance.has(&DataKey::OwnerCommitments(owner.clone())));
+        assert!(!instance.has(&DataKey::AllCommitmentIds));
+    });
+}
+
+#[test]
+fn test_migrate_v0_in_batches() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let commitments = vec![
+        &e,
+        create_test_commitment(&e, "c_0", &owner, 1000, 1000, 10, 30, 1000),
+        create_test_commitment(&e, "c_1", &owner, 2000, 1900, 10, 30, 1000),
+        create_test_commitment(&e, "c_2", &owner, 3000, 3000, 10, 30, 1000),
+    ];
+    let mut ids = Vec::new(&e);
+    for commitment in commitments.iter() {
+        ids.push_back(commitment.commitment_id.clone());
+    }
+
+    e.as_contract(&contract_id, || {
+        let instance = e.storage().instance();
+        instance.set(&DataKey::Admin, &admin);
+        instance.set(&DataKey::NftContract, &Address::generate(&e));
+        instance.set(&DataKey::TotalCommitments, &3u64);
+        for commitment in commitments.iter() {
+            instance.set(
+                &DataKey::Commitment(commitment.commitment_id.clone()),
+                &to_legacy(&e, &commitment),
+            );
+        }
+        instance.set(&DataKey::OwnerCommitments(owner.clone()), &ids);
+        instance.set(&DataKey::AllCommitmentIds, &ids);
+    });
+
+    assert!(!client.migrate(&admin, &0, &2));
+    assert_eq!(client.get_version(), 0);
+    e.as_contract(&contract_id, || {
+        let instance = e.storage().instance();
+        assert_eq!(instance.get::<_, u64>(&DataKey::MigrationCursor), Some(2));
+        assert!(instance.has(&DataKey::AllCommitmentIds));
+        assert!(instance.has(&DataKey::Commitment(ids.get(2).unwrap())));
+    });
+
+    assert!(client.migrate(&admin, &0, &2));
+    assert_eq!(client.get_version(), CURRENT_VERSION);
+    for commitment in commitments.iter() {
+        assert_eq!(client.get_commitment(&commitment.commitment_id), commitment);
+    }
+    assert_eq!(client.get_owner_commitments(&owner), ids);
+    assert_eq!(client.get_commitments_created_between(&0, &u64::MAX), ids);
+    e.as_contract(&contract_id, || {
+        let instance = e.storage().instance();
+        assert!(!instance.has(&DataKey::MigrationCursor));
+        assert!(!instance.has(&DataKey::AllCommitmentIds));
+    });
+}
+
+#[test]
+#[should_panic(expected = "Invalid amount")]
+fn test_migrate_zero_limit_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+    e.as_contract(&contract_id, || {
+        e.storage().instance().set(&DataKey::Version, &1u32);
+    });
+
+    client.migrate(&admin, &1, &0);
+}
+
+#[test]
+fn test_migrate_v1_converts_string_statuses() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+
+    let active = create_test_commitment(&e, "c_0", &owner, 1000, 1000, 10, 30, 1000);
+    let mut violated = create_test_commitment(&e, "c_1", &owner, 1000, 800, 10, 30, 1000);
+    violated.status = CommitmentStatus::Violated;
+    let mut exited = create_test_commitment(&e, "c_2", &owner, 1000, 0, 10, 30, 1000);
+    exited.status = CommitmentStatus::EarlyExit;
+    let commitments = vec![&e, active, violated, exited];
+
+    // v1 layout: persistent records with string statuses
+    e.as_contract(&contract_id, || {
+        let mut index: u64 = 0;
+        for commitment in commitments.iter() {
+            let key = DataKey::Commitment(commitment.commitment_id.clone());
+            e.storage()
+                .persistent()
+                .set(&key, &to_legacy(&e, &commitment));
+            write_commitment_at(&e, index, &commitment.commitment_id);
+            index += 1;
+        }
+        let instance = e.storage().instance();
+        instance.set(&DataKey::TotalCommitments, &index);
+        instance.set(&DataKey::Version, &1u32);
+    });
+
+    client.migrate(&admin, &1, &MAX_MIGRATION_BATCH);
+
+    assert_eq!(client.get_version(), CURRENT_VERSION);
+    for commitment in commitments.iter() {
+        assert_eq!(client.get_commitment(&commitment.commitment_id), commitment);
+    }
+}
+
+#[test]
+fn test_migrate_v1_in_batches() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+
+    let mut commitments = Vec::new(&e);
+    e.as_contract(&contract_id, || {
+        for index in 0..5u64 {
+            let id = CommitmentId::from_number(&e, index);
+            let mut commitment =
+                create_test_commitment(&e, "c_0", &owner, 1000, 1000, 10, 30, 1000);
+            commitment.commitment_id = id.clone();
+            e.storage().persistent().set(
+                &DataKey::Commitment(id.clone()),
+                &to_legacy(&e, &commitment),
+            );
+            write_commitment_at(&e, index, &id);
+            commitments.push_back(commitment);
+        }
+        let instance = e.storage().instance();
+        instance.set(&DataKey::TotalCommitments, &5u64);
+        instance.set(&DataKey::Version, &1u32);
+    });
+
+    assert!(!client.migrate(&admin, &1, &2));
+    assert!(!client.migrate(&admin, &1, &2));
+    assert_eq!(client.get_version(), 1);
+    e.as_contract(&contract_id, || {
+        assert_eq!(
+            e.storage()
+                .instance()
+                .get::<_, u64>(&DataKey::MigrationCursor),
+            Some(4)
+        );
+    });
+
+    assert!(client.migrate(&admin, &1, &2));
+    assert_eq!(client.get_version(), CURRENT_VERSION);
+    for commitment in commitments.iter() {
+        assert_eq!(client.get_commitment(&commitment.commitment_id), commitment);
+    }
+    e.as_contract(&contract_id, || {
+        assert!(!e.storage().instance().has(&DataKey::MigrationCursor));
+    });
+}
+
+#[test]
+fn test_migrate_v1_keeps_records_already_in_current_format() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+
+    let legacy = create_test_commitment(&e, "c_0", &owner, 1000, 1000, 10, 30, 1000);
+    let mut current = create_test_commitment(&e, "c_1", &owner, 1000, 900, 10, 30, 1000);
+    current.status = CommitmentStatus::Breaching;
+
+    // c_1 was written by the upgraded code before `migrate` ran
+    e.as_contract(&contract_id, || {
+        e.storage().persistent().set(
+            &DataKey::Commitment(legacy.commitment_id.clone()),
+            &to_legacy(&e, &legacy),
+        );
+        set_commitment(&e, &current);
+        write_commitment_at(&e, 0, &legacy.commitment_id);
+        write_commitment_at(&e, 1, &current.commitment_id);
+        let instance = e.storage().instance();
+        instance.set(&DataKey::TotalCommitments, &2u64);
+        instance.set(&DataKey::Version, &1u32);
+    });
+
+    client.migrate(&admin, &1, &MAX_MIGRATION_BATCH);
+
+    assert_eq!(client.get_commitment(&legacy.commitment_id), legacy);
+    assert_eq!(client.get_commitment(&current.commitment_id), current);
+}
+
+#[test]
+#[should_panic(expected = "Storage migration required")]
+fn test_writes_rejected_before_migration() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&Address::generate(&e), &Address::generate(&e));
+    e.as_contract(&contract_id, || {
+        e.storage().instance().set(&DataKey::Version, &1u32);
+    });
+
+    client.settle(&String::from_str(&e, "c_0"));
+}
+
+#[test]
+#[should_panic(expected = "Invalid storage version for migration")]
+fn test_migrate_wrong_from_version_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+    e.as_contract(&contract_id, || {
+        e.storage().instance().set(&DataKey::Version, &1u32);
+    });
+
+    client.migrate(&admin, &0, &MAX_MIGRATION_BATCH);
+}
+
+#[test]
+#[should_panic(expected = "Storage already migrated")]
+fn test_migrate_when_current_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e));
+
+    client.migrate(&admin, &0, &MAX_MIGRATION_BATCH);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_migrate_non_admin_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&Address::generate(&e), &Address::generate(&e));
+
+    client.migrate(&Address::generate(&e), &0, &MAX_MIGRATION_BATCH);
+}
+
+// ============================================
+// Status Transition Tests
+// ============================================
+
+#[test]
+fn test_settle_violated_commitment() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    // 15% loss against a 10% limit
+    let valuer = grant_valuer(&e, &contract_id);
+    client.update_value(&valuer, &commitment_id, &850);
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        CommitmentStatus::Violated
+    );
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, CommitmentStatus::Settled);
+    assert_eq!(client.get_total_value_locked(), 0);
+    let asset = token::Client::new(&e, &commitment.asset_address);
+    assert_eq!(asset.balance(&owner), 10_000 - 1_000 + 850);
+}
+
+/// Mark the commitment from `create_with_mock_nft` violated (15% loss).
+fn violate(e: &Env, contract_id: &Address, commitment_id: &String) {
+    let client = CommitmentCoreContractClient::new(e, contract_id);
+    let valuer = grant_valuer(e, contract_id);
+    client.update_value(&valuer, commitment_id, &850);
+}
+
+#[test]
+fn test_resolve_violation_returns_value_to_owner() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    violate(&e, &contract_id, &commitment_id);
+
+    // No violation penalty set; no need to wait for expiry
+    client.resolve_violation(&commitment_id, &owner);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, CommitmentStatus::Settled);
+    assert_eq!(commitment.current_value, 0);
+    assert_eq!(client.get_total_value_locked(), 0);
+    let asset = token::Client::new(&e, &commitment.asset_address);
+    assert_eq!(asset.balance(&owner), 9_000 + 850);
+
+    let events = e.events().all();
+    let (_, topics, data) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        vec![
+            &e,
+            symbol_short!("Resolved").into_val(&e),
+            commitment_id.into_val(&e),
+            owner.into_val(&e)
+        ]
+    );
+    let (returned, penalty, _timestamp): (i128, i128, u64) = data.into_val(&e);
+    assert_eq!((returned, penalty), (850, 0));
+}
+
+#[test]
+fn test_resolve_violation_collects_penalty() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    violate(&e, &contract_id, &commitment_id);
+
+    client.set_violation_penalty(&client.get_admin(), &10);
+    client.resolve_violation(&commitment_id, &owner);
+
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    let asset = token::Client::new(&e, &asset_address);
+    assert_eq!(asset.balance(&owner), 9_000 + 765);
+    assert_eq!(client.get_collected_penalties(&asset_address), 85);
+
+    let treasury = Address::generate(&e);
+    client.set_treasury(&client.get_admin(), &treasury);
+    client.withdraw_penalties(&client.get_admin(), &asset_address, &85);
+    assert_eq!(asset.balance(&treasury), 85);
+    assert_eq!(asset.balance(&contract_id), 150);
+}
+
+#[test]
+#[should_panic(expected = "Invalid commitment status for this operation")]
+fn test_resolve_violation_active_commitment_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.resolve_violation(&commitment_id, &owner);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_resolve_violation_by_non_owner_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    violate(&e, &contract_id, &commitment_id);
+    client.resolve_violation(&commitment_id, &Address::generate(&e));
+}
+
+#[test]
+fn test_partial_early_exit_keeps_commitment_open() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    // 5% penalty on the 400 withdrawn only
+    client.partial_early_exit(&commitment_id, &owner, &400);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, CommitmentStatus::Active);
+    assert_eq!(commitment.amount, 600);
+    assert_eq!(commitment.current_value, 600);
+    assert_eq!(client.get_total_value_locked(), 600);
+    let asset = token::Client::new(&e, &commitment.asset_address);
+    assert_eq!(asset.balance(&owner), 9_000 + 380);
+    assert_eq!(asset.balance(&contract_id), 620);
+
+    let events = e.events().all();
+    let (_, topics, data) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        vec![
+            &e,
+            symbol_short!("PartExit").into_val(&e),
+            commitment_id.into_val(&e),
+            owner.into_val(&e)
+        ]
+    );
+    let (amount, penalty, returned, _timestamp): (i128, i128, i128, u64) = data.into_val(&e);
+    assert_eq!((amount, penalty, returned), (400, 20, 380));
+}
+
+#[test]
+fn test_partial_early_exit_scales_principal_with_value() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let valuer = grant_valuer(&e, &contract_id);
+    client.update_value(&valuer, &commitment_id, &2_000);
+
+    client.partial_early_exit(&commitment_id, &owner, &500);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.amount, 750);
+    assert_eq!(commitment.current_value, 1_500);
+    assert_eq!(client.get_total_value_locked(), 1_500);
+}
+
+#[test]
+fn test_partial_early_exit_reclaims_allocated_funds() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    let allocation_contract = e.register_contract(None, MockAllocationContract);
+    MockAllocationContractClient::new(&e, &allocation_contract).set_reclaim(&contract_id, &400, &0);
+    client.set_allocation_contract(&client.get_admin(), &allocation_contract);
+
+    client.allocate(&commitment_id, &Address::generate(&e), &400);
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 600);
+
+    // 400 of the whole 1000 is 40%, so 40% of the principal goes
+    client.partial_early_exit(&commitment_id, &owner, &400);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, CommitmentStatus::Active);
+    assert_eq!(commitment.amount, 600);
+    assert_eq!(commitment.current_value, 600);
+    let asset = token::Client::new(&e, &commitment.asset_address);
+    assert_eq!(asset.balance(&owner), 9_000 + 380);
+    assert_eq!(asset.balance(&contract_id), 620);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient balance")]
+fn test_partial_early_exit_whole_value_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.partial_early_exit(&commitment_id, &owner, &1_000);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_partial_early_exit_by_non_owner_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.partial_early_exit(&commitment_id, &Address::generate(&e), &100);
+}
+
+#[test]
+#[should_panic(expected = "Commitment is not active")]
+fn test_partial_early_exit_violated_commitment_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    violate(&e, &contract_id, &commitment_id);
+    client.partial_early_exit(&commitment_id, &owner, &100);
+}
+
+// ============================================
+// Penalty Routing Tests
+// ============================================
+
+/// Adds a commitment in `asset_address` for a new owner funded with `amount`.
+fn create_in_asset(
+    e: &Env,
+    contract_id: &Address,
+    asset_address: &Address,
+    amount: i128,
+) -> (Address, String) {
+    let owner = Address::generate(e);
+    StellarAssetClient::new(e, asset_address).mint(&owner, &amount);
+    let client = CommitmentCoreContractClient::new(e, contract_id);
+    let commitment_id = client.create_commitment(&owner, &amount, asset_address, &test_rules(e));
+    (owner, commitment_id)
+}
+
+#[test]
+fn test_early_exit_penalty_is_collected_and_withdrawn() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+
+    client.early_exit(&commitment_id, &owner);
+    assert_eq!(client.get_collected_penalties(&asset_address), 50);
+
+    let treasury = Address::generate(&e);
+    client.set_treasury(&client.get_admin(), &treasury);
+    let events = e.events().all();
+    let (_, topics, _) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        vec![
+            &e,
+            symbol_short!("Treasury").into_val(&e),
+            client.get_admin().into_val(&e)
+        ]
+    );
+    assert_eq!(client.get_treasury(), Some(treasury.clone()));
+
+    client.withdraw_penalties(&client.get_admin(), &asset_address, &30);
+    assert_eq!(client.get_collected_penalties(&asset_address), 20);
+    assert_eq!(
+        token::Client::new(&e, &asset_address).balance(&treasury),
+        30
+    );
+}
+
+#[test]
+#[should_panic(expected = "Treasury not set")]
+fn test_withdraw_penalties_without_treasury_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+
+    client.early_exit(&commitment_id, &owner);
+    client.withdraw_penalties(&client.get_admin(), &asset_address, &50);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient collected penalties")]
+fn test_withdraw_more_than_collected_penalties_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+
+    client.early_exit(&commitment_id, &owner);
+    client.set_treasury(&client.get_admin(), &Address::generate(&e));
+    client.withdraw_penalties(&client.get_admin(), &asset_address, &51);
+}
+
+#[test]
+fn test_penalties_redistributed_pro_rata_by_principal() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    let (owner_b, id_b) = create_in_asset(&e, &contract_id, &asset_address, 3_000);
+    let (_owner_c, id_c) = create_in_asset(&e, &contract_id, &asset_address, 2_000);
+    client.set_penalty_redistribution(&client.get_admin(), &true);
+
+    // 50 penalty over 5_000 of other principal
+    client.early_exit(&commitment_id, &owner);
+    assert_eq!(client.get_collected_penalties(&asset_address), 0);
+    assert_eq!(client.get_loyalty_reward(&commitment_id), 0);
+    assert_eq!(client.get_loyalty_reward(&id_b), 30);
+    assert_eq!(client.get_loyalty_reward(&id_c), 20);
+
+    assert_eq!(client.claim_loyalty_reward(&id_b, &owner_b), 30);
+    let asset = token::Client::new(&e, &asset_address);
+    assert_eq!(asset.balance(&owner_b), 30);
+    assert_eq!(client.get_loyalty_reward(&id_b), 0);
+
+    // A partial exit's penalty goes to the others only
+    client.partial_early_exit(&id_b, &owner_b, &1_000);
+    assert_eq!(client.get_loyalty_reward(&id_b), 0);
+    assert_eq!(client.get_loyalty_reward(&id_c), 70);
+}
+
+#[test]
+fn test_penalty_collected_when_no_one_to_redistribute_to() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    client.set_penalty_redistribution(&client.get_admin(), &true);
+
+    client.early_exit(&commitment_id, &owner);
+    assert_eq!(client.get_collected_penalties(&asset_address), 50);
+}
+
+// ============================================
+// Creation Fee Tests
+// ============================================
+
+#[test]
+fn test_creation_fee_is_deducted_and_withdrawn() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    let admin = client.get_admin();
+
+    client.set_creation_fee_bps(&admin, &asset_address, &100);
+    assert_eq!(client.get_creation_fee_bps(&asset_address), 100);
+    let (owner, fee_id) = create_in_asset(&e, &contract_id, &asset_address, 1_000);
+
+    let commitment = client.get_commitment(&fee_id);
+    assert_eq!(commitment.amount, 990);
+    assert_eq!(commitment.current_value, 990);
+    assert_eq!(client.get_total_value_locked(), 1_000 + 990);
+    assert_eq!(client.get_collected_fees(&asset_address), 10);
+    let asset = token::Client::new(&e, &asset_address);
+    assert_eq!(asset.balance(&owner), 0);
+
+    let recipient = Address::generate(&e);
+    client.set_fee_recipient(&admin, &recipient);
+    assert_eq!(client.get_fee_recipient(), Some(recipient.clone()));
+    client.withdraw_fees(&admin, &asset_address, &10);
+    assert_eq!(asset.balance(&recipient), 10);
+    assert_eq!(client.get_collected_fees(&asset_address), 0);
+}
+
+#[test]
+fn test_creation_fee_is_per_asset() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    let other_asset = e
+        .register_stellar_asset_contract_v2(Address::generate(&e))
+        .address();
+
+    client.set_creation_fee_bps(&client.get_admin(), &other_asset, &100);
+    let (_owner, id) = create_in_asset(&e, &contract_id, &asset_address, 1_000);
+    assert_eq!(client.get_commitment(&id).amount, 1_000);
+    assert_eq!(client.get_collected_fees(&asset_address), 0);
+}
+
+#[test]
+#[should_panic(expected = "Fee must be 0-10000 bps")]
+fn test_set_creation_fee_above_max_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    client.set_creation_fee_bps(&client.get_admin(), &asset_address, &10_001);
+}
+
+#[test]
+#[should_panic(expected = "Invalid amount: must be greater than zero")]
+fn test_creation_fee_leaving_nothing_to_lock_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    client.set_creation_fee_bps(&client.get_admin(), &asset_address, &10_000);
+    create_in_asset(&e, &contract_id, &asset_address, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Fee recipient not set")]
+fn test_withdraw_fees_without_recipient_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    client.set_creation_fee_bps(&client.get_admin(), &asset_address, &100);
+    create_in_asset(&e, &contract_id, &asset_address, 1_000);
+    client.withdraw_fees(&client.get_admin(), &asset_address, &10);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient collected fees to withdraw")]
+fn test_withdraw_more_than_collected_fees_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    client.set_fee_recipient(&client.get_admin(), &Address::generate(&e));
+    client.withdraw_fees(&client.get_admin(), &asset_address, &1);
+}
+
+// ============================================
+// Rollover Tests
+// ============================================
+
+#[test]
+fn test_rollover_relocks_current_value() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.set_rollover(&commitment_id, &owner, &true, &None);
+    let valuer = grant_valuer(&e, &contract_id);
+    client.update_value(&valuer, &commitment_id, &1_050);
+
+    advance_days(&e, 30);
+    client.rollover(&commitment_id);
+
+    let now = e.ledger().timestamp();
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.status, CommitmentStatus::Active);
+    assert_eq!(commitment.amount, 1_050);
+    assert_eq!(commitment.current_value, 1_050);
+    assert_eq!(commitment.created_at, now);
+    assert_eq!(commitment.expires_at, now + 30 * 86400);
+    assert_eq!(client.get_total_value_locked(), 1_050);
+    let asset = token::Client::new(&e, &commitment.asset_address);
+    assert_eq!(asset.balance(&owner), 9_000);
+
+    let events = e.events().all();
+    let (_, topics, data) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        vec![
+            &e,
+            symbol_short!("Rolled").into_val(&e),
+            commitment_id.into_val(&e)
+        ]
+    );
+    let (amount, expires_at, _timestamp): (i128, u64, u64) = data.into_val(&e);
+    assert_eq!((amount, expires_at), (1_050, now + 30 * 86400));
+
+    // Still opted in for the next term
+    assert!(client.get_rollover(&commitment_id).is_some());
+}
+
+#[test]
+fn test_rollover_applies_updated_rules() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let mut rules = test_rules(&e);
+    rules.duration_days = 60;
+    rules.commitment_type = String::from_str(&e, "safe");
+    client.set_rollover(&commitment_id, &owner, &true, &Some(rules.clone()));
+    assert_eq!(client.get_rollover(&commitment_id), Some(rules.clone()));
+
+    advance_days(&e, 30);
+    client.rollover(&commitment_id);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.rules, rules);
+    assert_eq!(commitment.expires_at, e.ledger().timestamp() + 60 * 86400);
+}
+
+#[test]
+fn test_create_commitment_with_rollover_opts_in() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset_address).mint(&owner, &1_000);
+
+    let id = client.create_commitment_with_rollover(
+        &owner,
+        &1_000,
+        &asset_address,
+        &test_rules(&e),
+        &None,
+    );
+    assert_eq!(client.get_rollover(&id), Some(test_rules(&e)));
+    assert_eq!(client.get_rollover(&commitment_id), None);
+}
+
+#[test]
+fn test_create_commitment_with_curve_and_rollover() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset_address).mint(&owner, &1_000);
+
+    let id = client.create_commitment_with_curve(
+        &owner,
+        &1_000,
+        &asset_address,
+        &test_rules(&e),
+        &PenaltyCurve::Linear,
+        &Some(test_rules(&e)),
+    );
+    assert_eq!(client.get_penalty_curve(&id), PenaltyCurve::Linear);
+    assert_eq!(client.get_rollover(&id), Some(test_rules(&e)));
+}
+
+#[test]
+#[should_panic(expected = "Invalid early exit penalty curve")]
+fn test_rollover_rules_must_fit_penalty_curve() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let asset_address = client.get_commitment(&commitment_id).asset_address;
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &asset_address).mint(&owner, &1_000);
+
+    // A 4% step fits the 5% starting penalty but not a 3% one next term
+    let steps = vec![
+        &e,
+        PenaltyStep {
+            from_day: 10,
+            penalty_percent: 4,
+        },
+    ];
+    let mut rollover_rules = test_rules(&e);
+    rollover_rules.early_exit_penalty = 3;
+    client.create_commitment_with_curve(
+        &owner,
+        &1_000,
+        &asset_address,
+        &test_rules(&e),
+        &PenaltyCurve::Step(steps),
+        &Some(rollover_rules),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Commitment has not expired yet")]
+fn test_rollover_before_expiry_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.set_rollover(&commitment_id, &owner, &true, &None);
+    advance_days(&e, 29);
+    client.rollover(&commitment_id);
+}
+
+#[test]
+#[should_panic(expected = "Rollover not enabled for this commitment")]
+fn test_rollover_without_opt_in_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    advance_days(&e, 30);
+    client.rollover(&commitment_id);
+}
+
+#[test]
+#[should_panic(expected = "Commitment has already expired")]
+fn test_set_rollover_after_expiry_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    advance_days(&e, 30);
+    client.set_rollover(&commitment_id, &owner, &true, &None);
+}
+
+#[test]
+#[should_panic(expected = "Commitment is set to roll over")]
+fn test_settle_opted_in_commitment_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.set_rollover(&commitment_id, &owner, &true, &None);
+    advance_days(&e, 30);
+    client.settle(&commitment_id);
+}
+
+#[test]
+fn test_settle_after_disabling_rollover() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.set_rollover(&commitment_id, &owner, &true, &None);
+    advance_days(&e, 30);
+
+    client.set_rollover(&commitment_id, &owner, &false, &None);
+    client.settle(&commitment_id);
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        CommitmentStatus::Settled
+    );
+}
+
+// ============================================
+// Early Exit Penalty Curve Tests
+// ============================================
+
+fn advance_days(e: &Env, days: u64) {
+    e.ledger().with_mut(|l| l.timestamp += days * 86400);
+}
+
+#[test]
+fn test_default_penalty_curve_is_flat() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    assert_eq!(client.get_penalty_curve(&commitment_id), PenaltyCurve::Flat);
+    assert_eq!(client.quote_early_exit(&commitment_id), (50, 950));
+    advance_days(&e, 29);
+    assert_eq!(client.quote_early_exit(&commitment_id), (50, 950));
+}
+
+#[test]
+fn test_linear_penalty_decays_to_zero_at_expiry() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, Some(PenaltyCurve::Linear));
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    assert_eq!(
+        client.get_penalty_curve(&commitment_id),
+        PenaltyCurve::Linear
+    );
+    assert_eq!(client.quote_early_exit(&commitment_id), (200, 800));
+    advance_days(&e, 15);
+    assert_eq!(client.quote_early_exit(&commitment_id), (100, 900));
+    advance_days(&e, 15);
+    assert_eq!(client.quote_early_exit(&commitment_id), (0, 1_000));
+}
+
+#[test]
+fn test_floor_linear_penalty_keeps_floor() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) =
+        create_with_mock_nft(&e, Some(PenaltyCurve::FloorLinear(5)));
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    assert_eq!(client.quote_early_exit(&commitment_id), (200, 800));
+    advance_days(&e, 15);
+    assert_eq!(client.quote_early_exit(&commitment_id), (125, 875));
+    advance_days(&e, 15);
+    assert_eq!(client.quote_early_exit(&commitment_id), (50, 950));
+}
+
+#[test]
+fn test_step_penalty_schedule() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let steps = vec![
+        &e,
+        PenaltyStep {
+            from_day: 10,
+            penalty_percent: 10,
+        },
+        PenaltyStep {
+            from_day: 20,
+            penalty_percent: 2,
+        },
+    ];
+    let (contract_id, _owner, commitment_id) =
+        create_with_mock_nft(&e, Some(PenaltyCurve::Step(steps)));
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    assert_eq!(client.quote_early_exit(&commitment_id), (200, 800));
+    advance_days(&e, 10);
+    assert_eq!(client.quote_early_exit(&commitment_id), (100, 900));
+    advance_days(&e, 15);
+    assert_eq!(client.quote_early_exit(&commitment_id), (20, 980));
+}
+
+#[test]
+fn test_early_exit_applies_penalty_curve() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, owner, commitment_id) = create_with_mock_nft(&e, Some(PenaltyCurve::Linear));
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    advance_days(&e, 15);
+
+    // 10% halfway through: 20 on the 200 withdrawn, then 80 on the other 800
+    client.partial_early_exit(&commitment_id, &owner, &200);
+    client.early_exit(&commitment_id, &owner);
+
+    let asset = token::Client::new(&e, &client.get_commitment(&commitment_id).asset_address);
+    assert_eq!(asset.balance(&owner), 9_000 + 180 + 720);
+    assert_eq!(asset.balance(&contract_id), 100);
+}
+
+#[test]
+#[should_panic(expected = "Invalid early exit penalty curve")]
+fn test_unordered_penalty_steps_fail() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let steps = vec![
+        &e,
+        PenaltyStep {
+            from_day: 20,
+            penalty_percent: 10,
+        },
+        PenaltyStep {
+            from_day: 10,
+            penalty_percent: 2,
+        },
+    ];
+    create_with_mock_nft(&e, Some(PenaltyCurve::Step(steps)));
+}
+
+#[test]
+#[should_panic(expected = "Invalid early exit penalty curve")]
+fn test_rising_penalty_steps_fail() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let steps = vec![
+        &e,
+        PenaltyStep {
+            from_day: 10,
+            penalty_percent: 5,
+        },
+        PenaltyStep {
+            from_day: 20,
+            penalty_percent: 10,
+        },
+    ];
+    create_with_mock_nft(&e, Some(PenaltyCurve::Step(steps)));
+}
+
+#[test]
+#[should_panic(expected = "Invalid early exit penalty curve")]
+fn test_penalty_step_above_starting_penalty_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let steps = vec![
+        &e,
+        PenaltyStep {
+            from_day: 10,
+            penalty_percent: 25,
+        },
+    ];
+    create_with_mock_nft(&e, Some(PenaltyCurve::Step(steps)));
+}
+
+#[test]
+#[should_panic(expected = "Invalid early exit penalty curve")]
+fn test_penalty_floor_above_penalty_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    create_with_mock_nft(&e, Some(PenaltyCurve::FloorLinear(25)));
+}
+
+#[test]
+#[should_panic(expected = "Invalid commitment status transition")]
+fn test_settle_settled_commitment_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+    let (contract_id, _owner, commitment_id) = create_with_mock_nft(&e, None);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+    client.settle(&commitment_id);
+}
+
+#[test]
+#[should_panic(expected = "Invalid commitment status transition")]
+fn test_settle_early_exited_commitment_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let owner = Address::generate(&e);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&Address::generate(&e), &Address::generate(&e));
+
+    let mut commitment = create_test_commitment(&e, "exited", &owner, 1000, 0, 10, 1, 0);
+    commitment.status = CommitmentStatus::EarlyExit;
+    store_commitment(&e, &contract_id, &commitment);
+
+    e.ledger().with_mut(|l| l.timestamp = 2 * 86400);
+    client.settle(&commitment.commitment_id);
+}
+
+// ============================================
+// Paginated Listing Tests
+// ============================================
+
+fn empty_filter(e: &Env) -> CommitmentFilter {
+    CommitmentFilter {
+        owner: None,
+        statuses: Vec::new(e),
+        asset_address: None,
+        commitment_type: None,
+        created_from: None,
+        created_to: None,
+        expires_from: None,
+        expires_to: None,
+    }
+}
+
+/// Store commitments in creation order as `create_commitment` would.
+fn seed_commitments(e: &Env, contract_id: &Address, commitments: &Vec<Commitment>) {
+    e.as_contract(contract_id, || {
+        for (index, commitment) in commitments.iter().enumerate() {
+            set_commitment(e, &commitment);
+            write_commitment_at(e, index as u64, &commitment.commitment_id);
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalCommitments, &(commitments.len() as u64));
+    });
+}
+
+fn setup_listing(e: &Env) -> (CommitmentCoreContractClient<'_>, Address, Address) {
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(e, &contract_id);
+    client.initialize(&Address::generate(e), &Address::generate(e));
+    let owner_a = Address::generate(e);
+    let owner_b = Address::generate(e);
+
+    let mut commitments = Vec::new(e);
+    for (i, id) in ["c_0", "c_1", "c_2", "c_3", "c_4"].iter().enumerate() {
+        let owner = if i % 2 == 0 { &owner_a } else { &owner_b };
+        let n = i as u64 + 1;
+        let mut commitment =
+            create_test_commitment(e, id, owner, 1000, 1000, 10, n as u32, 1000 * n);
+        if i == 1 {
+            commitment.status = CommitmentStatus::Settled;
+        }
+        if i == 3 {
+            commitment.rules.commitment_type = String::from_str(e, "safe");
+        }
+        commitments.push_back(commitment);
+    }
+    seed_commitments(e, &contract_id, &commitments);
+
+    (client, owner_a, owner_b)
+}
+
+#[test]
+fn test_list_commitments_paginates_in_creation_order() {
+    let e = Env::default();
+    let (client, _, _) = setup_listing(&e);
+
+    let page = client.list_commitments(&0, &2, &empty_filter(&e));
+    assert_eq!(page.commitments.len(), 2);
+    assert_eq!(
+        page.commitments.get(0).unwrap().commitment_id,
+        String::from_str(&e, "c_0")
+    );
+    assert_eq!(page.next_cursor, 2);
+
+    let page = client.list_commitments(&page.next_cursor, &2, &empty_filter(&e));
+    assert_eq!(
+        page.commitments.get(0).unwrap().commitment_id,
+        String::from_str(&e, "c_2")
+    );
+    assert_eq!(page.next_cursor, 4);
+
+    let page = client.list_commitments(&page.next_cursor, &2, &empty_filter(&e));
+    assert_eq!(page.commitments.len(), 1);
+    assert_eq!(
+        page.commitments.get(0).unwrap().commitment_id,
+        String::from_str(&e, "c_4")
+    );
+    assert_eq!(page.next_cursor, 0);
+}
+
+#[test]
+fn test_list_commitments_empty_pages() {
+    let e = Env::default();
+    let (client, _, _) = setup_listing(&e);
+
+    let past_end = client.list_commitments(&5, &10, &empty_filter(&e));
+    assert_eq!(past_end.commitments.len(), 0);
+    assert_eq!(past_end.next_cursor, 0);
+
+    let zero_limit = client.list_commitments(&0, &0, &empty_filter(&e));
+    assert_eq!(zero_limit.commitments.len(), 0);
+    assert_eq!(zero_limit.next_cursor, 0);
+}
+
+#[test]
+fn test_list_commitments_filters() {
+    let e = Env::default();
+    let (client, owner_a, _) = setup_listing(&e);
+    let ids = |page: CommitmentsPage| -> Vec<String> {
+        let mut out = Vec::new(&e);
+        for c in page.commitments.iter() {
+            out.push_back(c.commitment_id);
+        }
+        out
+    };
+    let id = |s: &str| String::from_str(&e, s);
+
+    let mut by_owner = empty_filter(&e);
+    by_owner.owner = Some(owner_a.clone());
+    assert_eq!(
+        ids(client.list_commitments(&0, &10, &by_owner)),
+        vec![&e, id("c_0"), id("c_2"), id("c_4")]
+    );
+
+    let mut by_status = empty_filter(&e);
+    by_status.statuses = vec![&e, CommitmentStatus::Settled];
+    assert_eq!(
+        ids(client.list_commitments(&0, &10, &by_status)),
+        vec![&e, id("c_1")]
+    );
+
+    let mut by_type = empty_filter(&e);
+    by_type.commitment_type = Some(String::from_str(&e, "safe"));
+    assert_eq!(
+        ids(client.list_commitments(&0, &10, &by_type)),
+        vec![&e, id("c_3")]
+    );
+
+    let asset = client.get_commitment(&id("c_2")).asset_address;
+    let mut by_asset = empty_filter(&e);
+    by_asset.asset_address = Some(asset);
+    assert_eq!(
+        ids(client.list_commitments(&0, &10, &by_asset)),
+        vec![&e, id("c_2")]
+    );
+
+    // created_at = 1000 * (i + 1); expires_at = created_at + (i + 1) days
+    let mut by_created = empty_filter(&e);
+    by_created.created_from = Some(2000);
+    by_created.created_to = Some(3000);
+    assert_eq!(
+        ids(client.list_commitments(&0, &10, &by_created)),
+        vec![&e, id("c_1"), id("c_2")]
+    );
+
+    let mut by_expiry = empty_filter(&e);
+    by_expiry.expires_from = Some(4 * 86400);
+    assert_eq!(
+        ids(client.list_commitments(&0, &10, &by_expiry)),
+        vec![&e, id("c_3"), id("c_4")]
+    );
+}
+
+#[test]
+fn test_list_commitments_filter_and_limit_returns_next_cursor() {
+    let e = Env::default();
+    let (client, owner_a, _) = setup_listing(&e);
+
+    let mut by_owner = empty_filter(&e);
+    by_owner.owner = Some(owner_a);
+    let page = client.list_commitments(&0, &2, &by_owner);
+    assert_eq!(page.commitments.len(), 2);
+    // c_0 and c_2 matched; scanning resumes after c_2
+    assert_eq!(page.next_cursor, 3);
+
+    let page = client.list_commitments(&page.next_cursor, &2, &by_owner);
+    assert_eq!(page.commitments.len(), 1);
+    assert_eq!(page.next_cursor, 0);
+}
+
+// ============================================
+// Interface Conformance Tests
+// ============================================
+
+fn spec_fn(xdr: &[u8]) -> ScSpecFunctionV0 {
+    match ScSpecEntry::from_xdr(xdr, Limits::none()).unwrap() {
+        ScSpecEntry::FunctionV0(function) => function,
+        _ => panic!("spec entry is not a function"),
+    }
+}
+
+/// Check that every function in `expected` is exported by core with the
+/// same inputs and outputs.
+fn assert_spec_conforms(core_spec: &[&[u8]], expected: &[&[u8]]) {
+    for expected in expected.iter().map(|xdr| spec_fn(xdr)) {
+        let actual = core_spec
+            .iter()
+            .map(|xdr| spec_fn(xdr))
+            .find(|function| function.name == expected.name)
+            .unwrap_or_else(|| panic!("missing interface function {:?}", expected.name));
+
+        assert_eq!(actual.inputs.len(), expected.inputs.len(), "{:?}", expected.name);
+        for (a, b) in actual.inputs.iter().zip(expected.inputs.iter()) {
+            assert_eq!(a.name, b.name, "{:?}", expected.name);
+            assert_eq!(a.type_, b.type_, "{:?}", expected.name);
+        }
+        assert_eq!(actual.outputs, expected.outputs, "{:?}", expected.name);
+    }
+}
+
+#[test]
+fn test_exported_spec_conforms_to_commitment_interface() {
+    let core_spec: [&[u8]; 9] = [
+        &CommitmentCoreContract::spec_xdr_interface_version(),
+        &CommitmentCoreContract::spec_xdr_create_commitment(),
+        &CommitmentCoreContract::spec_xdr_get_commitment(),
+        &CommitmentCoreContract::spec_xdr_get_owner_commitments(),
+        &CommitmentCoreContract::spec_xdr_get_total_commitments(),
+        &CommitmentCoreContract::spec_xdr_list_commitments(),
+        &CommitmentCoreContract::spec_xdr_settle(),
+        &CommitmentCoreContract::spec_xdr_early_exit(),
+        &CommitmentCoreContract::spec_xdr_transfer_position(),
+    ];
+    assert_eq!(core_spec.len(), INTERFACE_SPEC.len());
+    assert_spec_conforms(&core_spec, INTERFACE_SPEC);
+}
+
+#[test]
+fn test_exported_spec_conforms_to_internal_interface() {
+    let core_spec: [&[u8]; 4] = [
+        &CommitmentCoreContract::spec_xdr_allocate(),
+        &CommitmentCoreContract::spec_xdr_deallocate(),
+        &CommitmentCoreContract::spec_xdr_credit_yield(),
+        &CommitmentCoreContract::spec_xdr_update_value(),
+    ];
+    assert_eq!(core_spec.len(), INTERNAL_SPEC.len());
+    assert_spec_conforms(&core_spec, INTERNAL_SPEC);
+}
+
+#[test]
+fn test_interface_version() {
+    let e = Env::default();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+
+    assert_eq!(client.interface_version(), INTERFACE_VERSION);
+    let interface = commitment_interface::CommitmentCoreClient::new(&e, &contract_id);
+    assert_eq!(interface.interface_version(), INTERFACE_VERSION);
 }
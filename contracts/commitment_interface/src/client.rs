@@ -0,0 +1,114 @@
+//! Typed clients for calls between the protocol's own contracts.
+//!
+//! Each trait lists the entry points one contract calls on another;
+//! `contractclient` generates the matching `*Client` so callers don't build
+//! argument vectors by hand. None of these are part of the stable
+//! `CommitmentInterface` ABI, whose client is generated in the crate root.
+
+use soroban_sdk::{contractclient, contractspecfn, Address, Env, String};
+
+use crate::types::PriceData;
+
+/// commitment_nft entry points used by commitment_core and commitment_marketplace.
+#[contractclient(name = "CommitmentNftClient")]
+pub trait CommitmentNftInterface {
+    /// Mint the NFT representing a new commitment and return its token id.
+    #[allow(clippy::too_many_arguments)]
+    fn mint(
+        e: Env,
+        owner: Address,
+        commitment_id: String,
+        duration_days: u32,
+        max_loss_percent: u32,
+        commitment_type: String,
+        initial_amount: i128,
+        asset_address: Address,
+        early_exit_penalty: u32,
+    ) -> u32;
+
+    /// Mark the NFT of a matured commitment as settled.
+    fn settle(e: Env, token_id: u32);
+
+    /// Mark the NFT of an early-exited commitment as inactive.
+    fn mark_inactive(e: Env, token_id: u32);
+
+    /// Record the reduced amount of a partially exited commitment.
+    fn update_amount(e: Env, token_id: u32, new_amount: i128);
+
+    /// Start a new term for a rolled-over commitment and re-activate its NFT.
+    fn renew(
+        e: Env,
+        token_id: u32,
+        duration_days: u32,
+        max_loss_percent: u32,
+        commitment_type: String,
+        initial_amount: i128,
+        early_exit_penalty: u32,
+    );
+
+    /// Current holder of `token_id`.
+    fn owner_of(e: Env, token_id: u32) -> Address;
+
+    /// Whether the commitment behind `token_id` is still active (locked).
+    fn is_active(e: Env, token_id: u32) -> bool;
+
+    /// Move `token_id` from `from` to `to`; `from` must authorize.
+    fn transfer(e: Env, from: Address, to: Address, token_id: u32);
+
+    /// Whether `token_id` may be transferred while its commitment is active.
+    fn is_transferable(e: Env, token_id: u32) -> bool;
+
+    /// Opt `token_id` in or out of transferable position mode; `owner` must
+    /// hold the token and authorize.
+    fn set_transferable(e: Env, owner: Address, token_id: u32, transferable: bool);
+}
+
+/// allocation_logic entry points used by commitment_core.
+#[contractclient(name = "AllocationClient")]
+pub trait AllocationInterface {
+    /// Return all of a commitment's pool allocations in `asset` to
+    /// commitment_core, with their unharvested yield, and report
+    /// `(principal, yield)`.
+    fn close_allocation(e: Env, commitment_id: String, asset: Address) -> (i128, i128);
+}
+
+/// price_oracle entry points used by commitment_core.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    /// Price of `asset`, failing if it is missing, negative or older than
+    /// `max_staleness_override` (or the oracle's default) seconds.
+    fn get_price_valid(e: Env, asset: Address, max_staleness_override: Option<u64>) -> PriceData;
+}
+
+/// Spec entries generated from `CommitmentCoreInternal`, used for
+/// conformance checks (`CommitmentCoreInternalSpec::spec_xdr_<fn>()`).
+pub struct CommitmentCoreInternalSpec;
+
+/// commitment_core entry points reserved for allocation_logic and valuers.
+#[contractspecfn(name = "CommitmentCoreInternalSpec", export = false)]
+#[contractclient(name = "CommitmentCoreInternalClient")]
+pub trait CommitmentCoreInternal {
+    /// Move `amount` of a commitment's asset to `target_pool` (allocation contract only).
+    fn allocate(e: Env, commitment_id: String, target_pool: Address, amount: i128);
+
+    /// Credit `amount` of principal returned from pools to an open commitment
+    /// (allocation contract only).
+    fn deallocate(e: Env, commitment_id: String, amount: i128);
+
+    /// Credit `amount` of harvested pool yield to an open commitment
+    /// (allocation contract only).
+    fn credit_yield(e: Env, commitment_id: String, amount: i128);
+
+    /// Set an open commitment's `current_value`; a loss past the limit on
+    /// `current_value` plus `allocated` marks it `Breaching`, then `Violated`
+    /// once its grace period runs out. `caller` must be an authorized valuer.
+    fn update_value(e: Env, caller: Address, commitment_id: String, new_value: i128);
+}
+
+/// Spec entries (XDR `ScSpecEntry`) of every `CommitmentCoreInternal` function.
+pub static INTERNAL_SPEC: &[&[u8]] = &[
+    &CommitmentCoreInternalSpec::spec_xdr_allocate(),
+    &CommitmentCoreInternalSpec::spec_xdr_deallocate(),
+    &CommitmentCoreInternalSpec::spec_xdr_credit_yield(),
+    &CommitmentCoreInternalSpec::spec_xdr_update_value(),
+];
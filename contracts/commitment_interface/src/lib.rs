@@ -1,52 +1,113 @@
 #![no_std]
 
+//! Stable public ABI of commitment_core.
+//!
+//! `CommitmentInterface` describes the entry points integrators can rely on
+//! across upgrades; `contractclient` generates `CommitmentCoreClient` for
+//! calling them from other contracts. commitment_core's exported spec is
+//! checked against this trait in its conformance test.
+//!
+//! Calls reserved for the protocol's own contracts (allocation_logic and
+//! valuers) live in `CommitmentCoreInternal` instead and do not affect
+//! `INTERFACE_VERSION`.
+
+pub mod client;
 pub mod error;
 pub mod types;
 
-use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Symbol};
+use soroban_sdk::{
+    contractclient, contractspecfn, symbol_short, Address, Env, String, Symbol, Vec,
+};
+
+pub use crate::client::{
+    AllocationClient, AllocationInterface, CommitmentCoreInternal, CommitmentCoreInternalClient,
+    CommitmentCoreInternalSpec, CommitmentNftClient, CommitmentNftInterface, PriceOracleClient,
+    PriceOracleInterface, INTERNAL_SPEC,
+};
+pub use crate::types::{
+    Commitment, CommitmentFilter, CommitmentRules, CommitmentStatus, CommitmentsPage, PriceData,
+};
+
+// =======================
+// Interface Metadata
+// =======================
+
+/// Version of `CommitmentInterface`. Bumped whenever a function in the
+/// interface is added, removed or changes signature, or a type it
+/// returns changes shape.
+pub const INTERFACE_VERSION: u32 = 3;
 
-use crate::error::Error;
-use crate::types::CommitmentSpec;
+// =======================
+// Events
+// =======================
 
-/// =======================
-/// Interface Metadata
-/// =======================
+pub const COMMITMENT_CREATED: Symbol = symbol_short!("Created");
+pub const COMMITMENT_SETTLED: Symbol = symbol_short!("Settled");
+pub const COMMITMENT_EARLY_EXIT: Symbol = symbol_short!("EarlyExt");
 
-pub const INTERFACE_VERSION: u32 = 1;
+// =======================
+// Interface
+// =======================
 
-/// =======================
-/// Events
-/// =======================
+/// Spec entries generated from `CommitmentInterface`, used for conformance
+/// checks (`CommitmentInterfaceSpec::spec_xdr_<fn>()`).
+pub struct CommitmentInterfaceSpec;
 
-pub const COMMITMENT_CREATED: Symbol = symbol_short!("created");
-pub const COMMITMENT_REVOKED: Symbol = symbol_short!("revoked");
+/// Stable public ABI of commitment_core.
+#[contractspecfn(name = "CommitmentInterfaceSpec", export = false)]
+#[contractclient(name = "CommitmentCoreClient")]
+pub trait CommitmentInterface {
+    /// Interface version implemented by the contract (`INTERFACE_VERSION`).
+    fn interface_version(e: Env) -> u32;
 
-/// =======================
-/// Interface Contract
-/// =======================
+    /// Lock `amount` of `asset_address` from `owner` and mint its NFT.
+    fn create_commitment(
+        e: Env,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+    ) -> String;
 
-#[contract]
-pub struct CommitmentInterface;
+    /// Fetch a commitment; fails if it does not exist.
+    fn get_commitment(e: Env, commitment_id: String) -> Commitment;
 
-#[contractimpl]
-impl CommitmentInterface {
-    /// Initialize the commitment system
-    pub fn initialize(_env: Env, _admin: Address) -> Result<(), Error> {
-        unimplemented!("interface only")
-    }
+    /// Commitment ids owned by `owner`.
+    fn get_owner_commitments(e: Env, owner: Address) -> Vec<String>;
 
-    /// Create a new commitment
-    pub fn create_commitment(_env: Env, _spec: CommitmentSpec) -> Result<u64, Error> {
-        unimplemented!("interface only")
-    }
+    /// Total number of commitments ever created.
+    fn get_total_commitments(e: Env) -> u64;
 
-    /// Fetch an existing commitment
-    pub fn get_commitment(_env: Env, _id: u64) -> Result<CommitmentSpec, Error> {
-        unimplemented!("interface only")
-    }
+    /// Page of commitments matching `filter`, oldest first.
+    fn list_commitments(
+        e: Env,
+        cursor: u64,
+        limit: u32,
+        filter: CommitmentFilter,
+    ) -> CommitmentsPage;
 
-    /// Revoke a commitment
-    pub fn revoke_commitment(_env: Env, _id: u64) -> Result<(), Error> {
-        unimplemented!("interface only")
-    }
+    /// Settle an expired commitment and return its value to the owner.
+    fn settle(e: Env, commitment_id: String);
+
+    /// Exit an active commitment early, paying the early exit penalty.
+    fn early_exit(e: Env, commitment_id: String, caller: Address);
+
+    /// Reassign a commitment to a new owner (NFT contract only).
+    fn transfer_position(e: Env, commitment_id: String, from: Address, to: Address);
 }
+
+/// Spec entries (XDR `ScSpecEntry`) of every `CommitmentInterface` function.
+pub static INTERFACE_SPEC: &[&[u8]] = &[
+    &CommitmentInterfaceSpec::spec_xdr_interface_version(),
+    &CommitmentInterfaceSpec::spec_xdr_create_commitment(),
+    &CommitmentInterfaceSpec::spec_xdr_get_commitment(),
+    &CommitmentInterfaceSpec::spec_xdr_get_owner_commitments(),
+    &CommitmentInterfaceSpec::spec_xdr_get_total_commitments(),
+    &CommitmentInterfaceSpec::spec_xdr_list_commitments(),
+    &CommitmentInterfaceSpec::spec_xdr_settle(),
+    &CommitmentInterfaceSpec::spec_xdr_early_exit(),
+    &CommitmentInterfaceSpec::spec_xdr_transfer_position(),
+];
+
+#[cfg(test)]
+mod tests;
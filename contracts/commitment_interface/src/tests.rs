@@ -0,0 +1,81 @@
+use crate::types::CommitmentStatus;
+use soroban_sdk::{Env, String};
+
+const ALL: [CommitmentStatus; 5] = [
+    CommitmentStatus::Active,
+    CommitmentStatus::Settled,
+    CommitmentStatus::Violated,
+    CommitmentStatus::EarlyExit,
+    CommitmentStatus::Breaching,
+];
+
+#[test]
+fn test_active_transitions() {
+    let active = CommitmentStatus::Active;
+    assert!(active.can_transition_to(&CommitmentStatus::Settled));
+    assert!(active.can_transition_to(&CommitmentStatus::Violated));
+    assert!(active.can_transition_to(&CommitmentStatus::EarlyExit));
+    assert!(active.can_transition_to(&CommitmentStatus::Breaching));
+    assert!(!active.can_transition_to(&CommitmentStatus::Active));
+}
+
+#[test]
+fn test_breaching_transitions() {
+    let breaching = CommitmentStatus::Breaching;
+    for next in [
+        CommitmentStatus::Active,
+        CommitmentStatus::Settled,
+        CommitmentStatus::Violated,
+        CommitmentStatus::EarlyExit,
+    ] {
+        assert!(breaching.can_transition_to(&next));
+    }
+    assert!(!breaching.can_transition_to(&CommitmentStatus::Breaching));
+    assert!(!breaching.is_terminal());
+}
+
+#[test]
+fn test_is_open() {
+    for status in ALL {
+        let open = matches!(
+            status,
+            CommitmentStatus::Active | CommitmentStatus::Breaching
+        );
+        assert_eq!(status.is_open(), open);
+    }
+}
+
+#[test]
+fn test_violated_can_only_settle() {
+    let violated = CommitmentStatus::Violated;
+    assert!(violated.can_transition_to(&CommitmentStatus::Settled));
+    assert!(!violated.can_transition_to(&CommitmentStatus::Active));
+    assert!(!violated.can_transition_to(&CommitmentStatus::Violated));
+    assert!(!violated.can_transition_to(&CommitmentStatus::EarlyExit));
+    assert!(!violated.can_transition_to(&CommitmentStatus::Breaching));
+}
+
+#[test]
+fn test_terminal_states_reject_all_transitions() {
+    for terminal in [CommitmentStatus::Settled, CommitmentStatus::EarlyExit] {
+        assert!(terminal.is_terminal());
+        for next in ALL {
+            assert!(!terminal.can_transition_to(&next));
+        }
+    }
+    assert!(!CommitmentStatus::Active.is_terminal());
+    assert!(!CommitmentStatus::Violated.is_terminal());
+}
+
+#[test]
+fn test_from_legacy_round_trip() {
+    let e = Env::default();
+    for status in ALL {
+        let legacy = String::from_str(&e, status.as_str());
+        assert_eq!(CommitmentStatus::from_legacy(&e, &legacy), Some(status));
+    }
+    assert_eq!(
+        CommitmentStatus::from_legacy(&e, &String::from_str(&e, "unknown")),
+        None
+    );
+}
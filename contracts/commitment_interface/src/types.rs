@@ -1,10 +1,142 @@
-use soroban_sdk::{contracttype, Address, BytesN};
+use soroban_sdk::{contracttype, Address, Env, String, Vec};
 
-#[derive(Clone, Debug, PartialEq)]
+/// Rules a commitment is created with.
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
-pub struct CommitmentSpec {
-    pub provider: Address,
+pub struct CommitmentRules {
+    pub duration_days: u32,
+    pub max_loss_percent: u32,
+    pub commitment_type: String, // "safe", "balanced", "aggressive"
+    pub early_exit_penalty: u32,
+    pub min_fee_threshold: i128,
+    pub grace_period_days: u32,
+}
+
+/// A commitment as stored and returned by commitment_core.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Commitment {
+    pub commitment_id: String,
+    pub owner: Address,
+    pub nft_token_id: u32,
+    pub rules: CommitmentRules,
     pub amount: i128,
-    pub unlock_date: u64,
-    pub metadata_hash: BytesN<32>,
+    pub asset_address: Address,
+    pub created_at: u64,
+    pub expires_at: u64,
+    /// Value held by commitment_core; excludes `allocated`
+    pub current_value: i128,
+    /// Principal moved to allocation pools and not yet returned
+    pub allocated: i128,
+    pub status: CommitmentStatus,
+}
+
+/// Optional filters for `list_commitments`. `None` fields and an empty
+/// `statuses` list match everything; timestamp bounds are inclusive.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CommitmentFilter {
+    pub owner: Option<Address>,
+    /// Match any of these statuses
+    pub statuses: Vec<CommitmentStatus>,
+    pub asset_address: Option<Address>,
+    pub commitment_type: Option<String>,
+    pub created_from: Option<u64>,
+    pub created_to: Option<u64>,
+    pub expires_from: Option<u64>,
+    pub expires_to: Option<u64>,
+}
+
+/// Paginated result for `list_commitments`.
+/// Ordering is by creation (oldest first).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CommitmentsPage {
+    pub commitments: Vec<Commitment>,
+    /// Cursor to use for the following page; 0 means no more pages.
+    pub next_cursor: u64,
+}
+
+/// Lifecycle state of a commitment.
+///
+/// Allowed transitions:
+/// - `Active` -> `Settled`, `Violated`, `EarlyExit`, `Breaching`
+/// - `Breaching` -> `Active`, `Settled`, `Violated`, `EarlyExit`
+/// - `Violated` -> `Settled`
+///
+/// `Settled` and `EarlyExit` are terminal.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum CommitmentStatus {
+    Active,
+    Settled,
+    Violated,
+    EarlyExit,
+    /// Loss exceeds the limit but the grace period has not run out yet
+    Breaching,
+}
+
+impl CommitmentStatus {
+    /// Whether a commitment in this state may move to `next`.
+    pub fn can_transition_to(&self, next: &CommitmentStatus) -> bool {
+        matches!(
+            (self, next),
+            (CommitmentStatus::Active, CommitmentStatus::Settled)
+                | (CommitmentStatus::Active, CommitmentStatus::Violated)
+                | (CommitmentStatus::Active, CommitmentStatus::EarlyExit)
+                | (CommitmentStatus::Active, CommitmentStatus::Breaching)
+                | (CommitmentStatus::Breaching, CommitmentStatus::Active)
+                | (CommitmentStatus::Breaching, CommitmentStatus::Settled)
+                | (CommitmentStatus::Breaching, CommitmentStatus::Violated)
+                | (CommitmentStatus::Breaching, CommitmentStatus::EarlyExit)
+                | (CommitmentStatus::Violated, CommitmentStatus::Settled)
+        )
+    }
+
+    /// Whether the commitment is still running (`Active` or `Breaching`):
+    /// its value can be updated and its owner can exit early.
+    pub fn is_open(&self) -> bool {
+        matches!(self, CommitmentStatus::Active | CommitmentStatus::Breaching)
+    }
+
+    /// Whether no further transitions are possible from this state.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            CommitmentStatus::Settled | CommitmentStatus::EarlyExit
+        )
+    }
+
+    /// Legacy string form ("active", "settled", "violated", "early_exit").
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommitmentStatus::Active => "active",
+            CommitmentStatus::Settled => "settled",
+            CommitmentStatus::Violated => "violated",
+            CommitmentStatus::EarlyExit => "early_exit",
+            CommitmentStatus::Breaching => "breaching",
+        }
+    }
+
+    /// Parse a legacy string status stored by pre-enum contract versions.
+    pub fn from_legacy(e: &Env, status: &String) -> Option<CommitmentStatus> {
+        [
+            CommitmentStatus::Active,
+            CommitmentStatus::Settled,
+            CommitmentStatus::Violated,
+            CommitmentStatus::EarlyExit,
+            CommitmentStatus::Breaching,
+        ]
+        .into_iter()
+        .find(|s| *status == String::from_str(e, s.as_str()))
+    }
+}
+
+/// Asset price as reported by price_oracle's `get_price_valid`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PriceData {
+    pub price: i128,
+    pub updated_at: u64,
+    pub decimals: u32,
 }
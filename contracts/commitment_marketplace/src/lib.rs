@@ -1,5 +1,6 @@
 #![no_std]
 
+use commitment_interface::CommitmentNftClient;
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol,
     Vec,
@@ -56,6 +57,10 @@ pub enum MarketplaceError {
     ReentrancyDetected = 20,
     /// Transfer failed
     TransferFailed = 21,
+    /// Caller does not own the NFT
+    NotOwner = 22,
+    /// NFT backs an active commitment and cannot be traded
+    NFTLocked = 23,
 }
 
 // ============================================================================
@@ -124,6 +129,98 @@ pub enum DataKey {
     ReentrancyGuard,
 }
 
+// ============================================================================
+// NFT Contract Helpers
+// ============================================================================
+
+/// Read the configured CommitmentNFT contract address
+fn read_nft_contract(e: &Env) -> Result<Address, MarketplaceError> {
+    e.storage()
+        .instance()
+        .get(&DataKey::NFTContract)
+        .ok_or(MarketplaceError::NotInitialized)
+}
+
+/// Move a commitment NFT through `CommitmentNFTContract::transfer`.
+///
+/// Used both to take custody (seller -> marketplace) and to release it
+/// (marketplace -> buyer/winner/seller). Any failure reported by the NFT
+/// contract is surfaced as `NFTContractError`.
+///
+/// Moving an active transferable position also moves its commitment in
+/// commitment_core and clears the token's transferable flag, so the
+/// marketplace opts an escrowed position back in to be able to release it.
+fn transfer_nft(
+    e: &Env,
+    nft_contract: &Address,
+    from: &Address,
+    to: &Address,
+    token_id: u32,
+) -> Result<(), MarketplaceError> {
+    let client = CommitmentNftClient::new(e, nft_contract);
+    if !matches!(client.try_transfer(from, to, &token_id), Ok(Ok(()))) {
+        return Err(MarketplaceError::NFTContractError);
+    }
+
+    if *to == e.current_contract_address() && nft_is_active(e, nft_contract, token_id)? {
+        match client.try_set_transferable(to, &token_id, &true) {
+            Ok(Ok(())) => {}
+            _ => return Err(MarketplaceError::NFTContractError),
+        }
+    }
+    Ok(())
+}
+
+/// Query `CommitmentNFTContract::owner_of`
+fn nft_owner_of(
+    e: &Env,
+    nft_contract: &Address,
+    token_id: u32,
+) -> Result<Address, MarketplaceError> {
+    match CommitmentNftClient::new(e, nft_contract).try_owner_of(&token_id) {
+        Ok(Ok(owner)) => Ok(owner),
+        _ => Err(MarketplaceError::NFTContractError),
+    }
+}
+
+/// Query `CommitmentNFTContract::is_active`
+fn nft_is_active(e: &Env, nft_contract: &Address, token_id: u32) -> Result<bool, MarketplaceError> {
+    match CommitmentNftClient::new(e, nft_contract).try_is_active(&token_id) {
+        Ok(Ok(active)) => Ok(active),
+        _ => Err(MarketplaceError::NFTContractError),
+    }
+}
+
+/// Reject NFTs that back an active (locked) commitment, unless the holder
+/// opted them into transferable position mode
+fn require_unlocked(
+    e: &Env,
+    nft_contract: &Address,
+    token_id: u32,
+) -> Result<(), MarketplaceError> {
+    if nft_is_active(e, nft_contract, token_id)? {
+        match CommitmentNftClient::new(e, nft_contract).try_is_transferable(&token_id) {
+            Ok(Ok(true)) => {}
+            Ok(Ok(false)) => return Err(MarketplaceError::NFTLocked),
+            _ => return Err(MarketplaceError::NFTContractError),
+        }
+    }
+    Ok(())
+}
+
+/// Verify `seller` currently owns `token_id` and that it is tradeable
+fn require_owner_unlocked(
+    e: &Env,
+    nft_contract: &Address,
+    seller: &Address,
+    token_id: u32,
+) -> Result<(), MarketplaceError> {
+    if nft_owner_of(e, nft_contract, token_id)? != *seller {
+        return Err(MarketplaceError::NotOwner);
+    }
+    require_unlocked(e, nft_contract, token_id)
+}
+
 #[cfg(test)]
 mod tests;
 
@@ -213,6 +310,13 @@ impl CommitmentMarketplace {
 
     /// List an NFT for sale
     ///
+    /// The NFT is transferred into marketplace custody until it is sold or
+    /// the listing is cancelled. Fails with `NotOwner` if `seller` does not
+    /// own the NFT and `NFTLocked` while its commitment is still active,
+    /// unless the seller opted it into transferable position mode. Escrowing
+    /// a transferable position makes the marketplace its commitment_core
+    /// owner until the NFT is released.
+    ///
     /// # Arguments
     /// * `seller` - The seller's address (must be NFT owner)
     /// * `token_id` - The NFT token ID to list
@@ -257,21 +361,18 @@ impl CommitmentMarketplace {
             return Err(MarketplaceError::ListingExists);
         }
 
-        // Verify seller owns the NFT (external call - after checks)
-        let _nft_contract: Address = e
-            .storage()
-            .instance()
-            .get(&DataKey::NFTContract)
-            .ok_or_else(|| {
-                e.storage()
-                    .instance()
-                    .set(&DataKey::ReentrancyGuard, &false);
-                MarketplaceError::NotInitialized
-            })?;
+        let nft_contract = read_nft_contract(&e).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
 
-        // Note: This would require the NFT contract client
-        // For now, we assume the caller has verified ownership
-        // In production, you'd call: nft_contract.owner_of(&token_id)
+        // Verify seller owns the NFT and it is not locked by an active commitment
+        require_owner_unlocked(&e, &nft_contract, &seller, token_id).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
 
         // EFFECTS
         let listing = Listing {
@@ -297,6 +398,20 @@ impl CommitmentMarketplace {
             .instance()
             .set(&DataKey::ActiveListings, &active_listings);
 
+        // INTERACTIONS - Take custody of the NFT until it is sold or cancelled
+        transfer_nft(
+            &e,
+            &nft_contract,
+            &seller,
+            &e.current_contract_address(),
+            token_id,
+        )
+        .inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+
         // Clear reentrancy guard
         e.storage()
             .instance()
@@ -311,7 +426,7 @@ impl CommitmentMarketplace {
         Ok(())
     }
 
-    /// Cancel a listing
+    /// Cancel a listing and return the escrowed NFT to the seller
     ///
     /// # Reentrancy Protection
     /// Uses checks-effects-interactions pattern
@@ -348,6 +463,12 @@ impl CommitmentMarketplace {
             return Err(MarketplaceError::NotSeller);
         }
 
+        let nft_contract = read_nft_contract(&e).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+
         // EFFECTS
         // Remove listing
         e.storage().persistent().remove(&DataKey::Listing(token_id));
@@ -365,6 +486,20 @@ impl CommitmentMarketplace {
             .instance()
             .set(&DataKey::ActiveListings, &active_listings);
 
+        // INTERACTIONS - Return the escrowed NFT to the seller
+        transfer_nft(
+            &e,
+            &nft_contract,
+            &e.current_contract_address(),
+            &seller,
+            token_id,
+        )
+        .inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+
         // Clear reentrancy guard
         e.storage()
             .instance()
@@ -379,6 +514,9 @@ impl CommitmentMarketplace {
 
     /// Buy an NFT
     ///
+    /// Payment and delivery of the escrowed NFT happen in the same invocation,
+    /// so the buyer either receives the NFT or the whole purchase reverts.
+    ///
     /// # Arguments
     /// * `buyer` - The buyer's address
     /// * `token_id` - The NFT token ID to buy
@@ -435,16 +573,11 @@ impl CommitmentMarketplace {
                 MarketplaceError::NotInitialized
             })?;
 
-        let _nft_contract: Address = e
-            .storage()
-            .instance()
-            .get(&DataKey::NFTContract)
-            .ok_or_else(|| {
-                e.storage()
-                    .instance()
-                    .set(&DataKey::ReentrancyGuard, &false);
-                MarketplaceError::NotInitialized
-            })?;
+        let nft_contract = read_nft_contract(&e).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
 
         // Calculate fee and seller proceeds
         let marketplace_fee = (listing.price * fee_basis_points as i128) / 10000;
@@ -477,11 +610,19 @@ impl CommitmentMarketplace {
             payment_token_client.transfer(&buyer, &fee_recipient, &marketplace_fee);
         }
 
-        // Transfer NFT from seller to buyer
-        // Note: In production, you'd use the NFT contract client:
-        // let nft_client = CommitmentNFTContractClient::new(&e, &nft_contract);
-        // nft_client.transfer(&listing.seller, &buyer, &token_id);
-        // For this implementation, we assume the transfer happens correctly
+        // Release the escrowed NFT to the buyer
+        transfer_nft(
+            &e,
+            &nft_contract,
+            &e.current_contract_address(),
+            &buyer,
+            token_id,
+        )
+        .inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
 
         // Clear reentrancy guard
         e.storage()
@@ -534,6 +675,9 @@ impl CommitmentMarketplace {
 
     /// Make an offer on an NFT
     ///
+    /// The NFT must exist, not belong to `offerer` and not be locked by an
+    /// active commitment that is not transferable (`NFTLocked`).
+    ///
     /// # Reentrancy Protection
     /// Protected with reentrancy guard
     pub fn make_offer(
@@ -564,6 +708,29 @@ impl CommitmentMarketplace {
             return Err(MarketplaceError::InvalidOfferAmount);
         }
 
+        // Verify the NFT exists, is not the offerer's own and is not locked
+        let nft_contract = read_nft_contract(&e).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+        let owner = nft_owner_of(&e, &nft_contract, token_id).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+        if owner == offerer {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+            return Err(MarketplaceError::CannotBuyOwnListing);
+        }
+        require_unlocked(&e, &nft_contract, token_id).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+
         // EFFECTS
         let offer = Offer {
             token_id,
@@ -673,6 +840,30 @@ impl CommitmentMarketplace {
                 MarketplaceError::NotInitialized
             })?;
 
+        let nft_contract = read_nft_contract(&e).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+
+        // A listed NFT is held in escrow, so only its seller may accept offers on it.
+        // Otherwise the seller must still own the NFT and it must not be locked.
+        let listing: Option<Listing> = e.storage().persistent().get(&DataKey::Listing(token_id));
+        if let Some(ref listing) = listing {
+            if listing.seller != seller {
+                e.storage()
+                    .instance()
+                    .set(&DataKey::ReentrancyGuard, &false);
+                return Err(MarketplaceError::NotSeller);
+            }
+        } else {
+            require_owner_unlocked(&e, &nft_contract, &seller, token_id).inspect_err(|_| {
+                e.storage()
+                    .instance()
+                    .set(&DataKey::ReentrancyGuard, &false);
+            })?;
+        }
+
         // Calculate fee and seller proceeds
         let marketplace_fee = (offer.amount * fee_basis_points as i128) / 10000;
         let seller_proceeds = offer.amount - marketplace_fee;
@@ -682,7 +873,7 @@ impl CommitmentMarketplace {
         e.storage().persistent().remove(&DataKey::Offers(token_id));
 
         // Remove listing if exists
-        if e.storage().persistent().has(&DataKey::Listing(token_id)) {
+        if listing.is_some() {
             e.storage().persistent().remove(&DataKey::Listing(token_id));
 
             let mut active_listings: Vec<u32> = e
@@ -707,8 +898,17 @@ impl CommitmentMarketplace {
             payment_token_client.transfer(&offerer, &fee_recipient, &marketplace_fee);
         }
 
-        // Transfer NFT
-        // Note: Use NFT contract client in production
+        // Transfer NFT - from escrow if it was listed, otherwise straight from the seller
+        let nft_holder = if listing.is_some() {
+            e.current_contract_address()
+        } else {
+            seller.clone()
+        };
+        transfer_nft(&e, &nft_contract, &nft_holder, &offerer, token_id).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
 
         // Clear reentrancy guard
         e.storage()
@@ -769,6 +969,9 @@ impl CommitmentMarketplace {
 
     /// Start an auction
     ///
+    /// The NFT is held by the marketplace until the auction ends. Fails with
+    /// `NotOwner` or `NFTLocked` under the same rules as `list_nft`.
+    ///
     /// # Reentrancy Protection
     /// Protected with reentrancy guard
     pub fn start_auction(
@@ -814,6 +1017,19 @@ impl CommitmentMarketplace {
             return Err(MarketplaceError::ListingExists);
         }
 
+        let nft_contract = read_nft_contract(&e).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+
+        // Verify seller owns the NFT and it is not locked by an active commitment
+        require_owner_unlocked(&e, &nft_contract, &seller, token_id).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+
         // EFFECTS
         let started_at = e.ledger().timestamp();
         let ends_at = started_at + duration_seconds;
@@ -844,6 +1060,20 @@ impl CommitmentMarketplace {
             .instance()
             .set(&DataKey::ActiveAuctions, &active_auctions);
 
+        // INTERACTIONS - Take custody of the NFT for the duration of the auction
+        transfer_nft(
+            &e,
+            &nft_contract,
+            &seller,
+            &e.current_contract_address(),
+            token_id,
+        )
+        .inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+
         // Clear reentrancy guard
         e.storage()
             .instance()
@@ -955,6 +1185,9 @@ impl CommitmentMarketplace {
 
     /// End an auction
     ///
+    /// Hands the escrowed NFT to the highest bidder, or back to the seller
+    /// if no bids were placed.
+    ///
     /// # Reentrancy Protection
     /// Critical - handles final settlement. Protected with reentrancy guard.
     pub fn end_auction(e: Env, token_id: u32) -> Result<(), MarketplaceError> {
@@ -1013,6 +1246,12 @@ impl CommitmentMarketplace {
                 MarketplaceError::NotInitialized
             })?;
 
+        let nft_contract = read_nft_contract(&e).inspect_err(|_| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+        })?;
+
         // EFFECTS
         auction.ended = true;
         e.storage()
@@ -1056,8 +1295,19 @@ impl CommitmentMarketplace {
                 );
             }
 
-            // Transfer NFT to winner
-            // Note: Use NFT contract client in production
+            // Release the escrowed NFT to the winner
+            transfer_nft(
+                &e,
+                &nft_contract,
+                &e.current_contract_address(),
+                &winner,
+                token_id,
+            )
+            .inspect_err(|_| {
+                e.storage()
+                    .instance()
+                    .set(&DataKey::ReentrancyGuard, &false);
+            })?;
 
             // Clear reentrancy guard
             e.storage()
@@ -1071,6 +1321,18 @@ impl CommitmentMarketplace {
             );
         } else {
             // No bids - return NFT to seller
+            transfer_nft(
+                &e,
+                &nft_contract,
+                &e.current_contract_address(),
+                &auction.seller,
+                token_id,
+            )
+            .inspect_err(|_| {
+                e.storage()
+                    .instance()
+                    .set(&DataKey::ReentrancyGuard, &false);
+            })?;
 
             // Clear reentrancy guard
             e.storage()
@@ -4,35 +4,131 @@ extern crate std;
 
 use crate::*;
 use soroban_sdk::{
-    symbol_short,
+    contract, contractimpl, contracttype, symbol_short,
     testutils::{Address as _, Events, Ledger},
+    token::{StellarAssetClient, TokenClient},
     vec, Address, Env, IntoVal,
 };
 
+// ============================================================================
+// Mock NFT Contract
+// ============================================================================
+
+/// Minimal stand-in for CommitmentNFTContract: tracks owners and enforces
+/// the same `transfer(from, to, token_id)` ownership rules.
+#[contract]
+pub struct MockNftContract;
+
+#[contracttype]
+enum MockNftKey {
+    Active(u32),
+    Transferable(u32),
+}
+
+#[contractimpl]
+impl MockNftContract {
+    pub fn mint(e: Env, owner: Address, token_id: u32) {
+        e.storage().persistent().set(&token_id, &owner);
+    }
+
+    pub fn set_active(e: Env, token_id: u32, active: bool) {
+        e.storage()
+            .persistent()
+            .set(&MockNftKey::Active(token_id), &active);
+    }
+
+    pub fn is_active(e: Env, token_id: u32) -> bool {
+        e.storage()
+            .persistent()
+            .get(&MockNftKey::Active(token_id))
+            .unwrap_or(false)
+    }
+
+    pub fn owner_of(e: Env, token_id: u32) -> Address {
+        e.storage()
+            .persistent()
+            .get(&token_id)
+            .unwrap_or_else(|| panic!("token not found"))
+    }
+
+    pub fn is_transferable(e: Env, token_id: u32) -> bool {
+        e.storage()
+            .persistent()
+            .get(&MockNftKey::Transferable(token_id))
+            .unwrap_or(false)
+    }
+
+    pub fn set_transferable(e: Env, owner: Address, token_id: u32, transferable: bool) {
+        owner.require_auth();
+        if Self::owner_of(e.clone(), token_id) != owner {
+            panic!("not owner");
+        }
+        e.storage()
+            .persistent()
+            .set(&MockNftKey::Transferable(token_id), &transferable);
+    }
+
+    /// Like commitment_nft: active tokens move only when transferable, and
+    /// a transfer clears the flag.
+    pub fn transfer(e: Env, from: Address, to: Address, token_id: u32) {
+        from.require_auth();
+        let owner: Address = Self::owner_of(e.clone(), token_id);
+        if owner != from {
+            panic!("not owner");
+        }
+        if Self::is_active(e.clone(), token_id) && !Self::is_transferable(e.clone(), token_id) {
+            panic!("locked");
+        }
+        e.storage().persistent().set(&token_id, &to);
+        e.storage()
+            .persistent()
+            .remove(&MockNftKey::Transferable(token_id));
+    }
+}
+
 // ============================================================================
 // Test Setup Helpers
 // ============================================================================
 
-fn setup_marketplace(e: &Env) -> (Address, Address, CommitmentMarketplaceClient<'_>) {
+fn setup_marketplace(
+    e: &Env,
+) -> (
+    Address,
+    Address,
+    CommitmentMarketplaceClient<'_>,
+    MockNftContractClient<'_>,
+) {
     let admin = Address::generate(e);
-    let nft_contract = Address::generate(e);
     let fee_recipient = Address::generate(e);
 
+    let nft_contract = e.register_contract(None, MockNftContract);
+    let nft_client = MockNftContractClient::new(e, &nft_contract);
+
     // Use register_contract for Soroban SDK
     let marketplace_id = e.register_contract(None, CommitmentMarketplace);
     let client = CommitmentMarketplaceClient::new(e, &marketplace_id);
 
     client.initialize(&admin, &nft_contract, &250, &fee_recipient); // 2.5% fee
 
-    (admin, fee_recipient, client)
+    (admin, fee_recipient, client, nft_client)
 }
 
 fn setup_test_token(e: &Env) -> Address {
-    // In a real implementation, you'd deploy a token contract
-    // For testing, we'll use a generated address
+    // Only used by flows that never move payment tokens
     Address::generate(e)
 }
 
+/// Deploy a Stellar asset contract and fund each account with `amount`.
+fn setup_funded_token(e: &Env, accounts: &[&Address], amount: i128) -> Address {
+    let token_admin = Address::generate(e);
+    let token = e.register_stellar_asset_contract_v2(token_admin).address();
+    let admin_client = StellarAssetClient::new(e, &token);
+    for account in accounts {
+        admin_client.mint(account, &amount);
+    }
+    token
+}
+
 // ============================================================================
 // Initialization Tests
 // ============================================================================
@@ -60,7 +156,7 @@ fn test_initialize_twice_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_admin, _, client) = setup_marketplace(&e);
+    let (_admin, _, client, _nft) = setup_marketplace(&e);
     let nft_contract = Address::generate(&e);
     let fee_recipient = Address::generate(&e);
     let new_admin = Address::generate(&e);
@@ -73,7 +169,7 @@ fn test_update_fee() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_admin, _, client) = setup_marketplace(&e);
+    let (_admin, _, client, _nft) = setup_marketplace(&e);
 
     client.update_fee(&500); // Update to 5%
 
@@ -94,7 +190,7 @@ fn test_list_nft_zero_price_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, _nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
@@ -108,11 +204,12 @@ fn test_list_nft_twice_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
+    nft.mint(&seller, &1);
     client.list_nft(&seller, &1, &1000, &payment_token);
     client.list_nft(&seller, &1, &2000, &payment_token); // Should fail
 }
@@ -122,12 +219,13 @@ fn test_cancel_listing() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
     let token_id = 1u32;
 
+    nft.mint(&seller, &token_id);
     client.list_nft(&seller, &token_id, &1000, &payment_token);
     client.cancel_listing(&seller, &token_id);
 
@@ -151,11 +249,12 @@ fn test_get_listing_after_cancel_panics() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let token_id = 1u32;
 
+    nft.mint(&seller, &token_id);
     client.list_nft(&seller, &token_id, &1000, &setup_test_token(&e));
     client.cancel_listing(&seller, &token_id);
 
@@ -169,7 +268,7 @@ fn test_cancel_nonexistent_listing_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, _nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     client.cancel_listing(&seller, &999);
@@ -181,12 +280,13 @@ fn test_cancel_listing_not_seller_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let not_seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
+    nft.mint(&seller, &1);
     client.list_nft(&seller, &1, &1000, &payment_token);
     client.cancel_listing(&not_seller, &1); // Should fail
 }
@@ -196,14 +296,17 @@ fn test_get_all_listings() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
     // List 3 NFTs
+    nft.mint(&seller, &1);
     client.list_nft(&seller, &1, &1000, &payment_token);
+    nft.mint(&seller, &2);
     client.list_nft(&seller, &2, &2000, &payment_token);
+    nft.mint(&seller, &3);
     client.list_nft(&seller, &3, &3000, &payment_token);
 
     let listings = client.get_all_listings();
@@ -219,31 +322,225 @@ fn test_buy_nft_flow() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, fee_recipient, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
-    let _buyer = Address::generate(&e);
-    let payment_token = setup_test_token(&e);
+    let buyer = Address::generate(&e);
     let token_id = 1u32;
     let price = 1000_0000000i128;
+    let payment_token = setup_funded_token(&e, &[&buyer], price);
 
     // List NFT
+    nft.mint(&seller, &token_id);
     client.list_nft(&seller, &token_id, &price, &payment_token);
 
-    // Note: In a real test, you'd need to:
-    // 1. Deploy a test token contract
-    // 2. Mint tokens to the buyer
-    // 3. Have buyer approve marketplace to spend tokens
-    // 4. Call buy_nft
-    // 5. Verify token and NFT transfers
+    client.buy_nft(&buyer, &token_id);
+
+    // NFT delivered to the buyer together with the payment
+    assert_eq!(nft.owner_of(&token_id), buyer);
 
-    // For this example, we're testing the flow logic only
-    // Uncomment when you have token contract set up:
-    // client.buy_nft(&buyer, &token_id);
+    let token = TokenClient::new(&e, &payment_token);
+    let fee = price * 250 / 10000;
+    assert_eq!(token.balance(&buyer), 0);
+    assert_eq!(token.balance(&seller), price - fee);
+    assert_eq!(token.balance(&fee_recipient), fee);
 
     // Verify listing is removed
-    // let result = client.try_get_listing(&token_id);
-    // assert!(result.is_err());
+    assert!(client.try_get_listing(&token_id).is_err());
+}
+
+#[test]
+fn test_list_nft_escrows_nft() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+    let token_id = 1u32;
+
+    nft.mint(&seller, &token_id);
+    client.list_nft(&seller, &token_id, &1000, &payment_token);
+
+    assert_eq!(nft.owner_of(&token_id), client.address);
+}
+
+#[test]
+fn test_cancel_listing_returns_nft() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+    let token_id = 1u32;
+
+    nft.mint(&seller, &token_id);
+    client.list_nft(&seller, &token_id, &1000, &payment_token);
+    client.cancel_listing(&seller, &token_id);
+
+    assert_eq!(nft.owner_of(&token_id), seller);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")] // NotOwner
+fn test_list_nft_not_held_by_seller_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let owner = Address::generate(&e);
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    nft.mint(&owner, &1);
+    client.list_nft(&seller, &1, &1000, &payment_token);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #23)")] // NFTLocked
+fn test_list_active_nft_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    nft.mint(&seller, &1);
+    nft.set_active(&1, &true);
+    client.list_nft(&seller, &1, &1000, &payment_token);
+}
+
+#[test]
+fn test_list_transferable_active_nft_sells_to_buyer() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let price = 1000i128;
+    let payment_token = setup_funded_token(&e, &[&buyer], price);
+
+    nft.mint(&seller, &1);
+    nft.set_active(&1, &true);
+    nft.set_transferable(&seller, &1, &true);
+    client.list_nft(&seller, &1, &price, &payment_token);
+
+    // Escrow cleared the seller's opt-in; the marketplace opts back in
+    assert_eq!(nft.owner_of(&1), client.address);
+    assert!(nft.is_transferable(&1));
+
+    client.buy_nft(&buyer, &1);
+
+    assert_eq!(nft.owner_of(&1), buyer);
+    assert!(!nft.is_transferable(&1));
+}
+
+#[test]
+fn test_cancel_transferable_active_listing_returns_nft() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    nft.mint(&seller, &1);
+    nft.set_active(&1, &true);
+    nft.set_transferable(&seller, &1, &true);
+    client.list_nft(&seller, &1, &1000, &payment_token);
+    client.cancel_listing(&seller, &1);
+
+    assert_eq!(nft.owner_of(&1), seller);
+}
+
+#[test]
+fn test_auction_transferable_active_nft() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &[&bidder], 5000);
+
+    nft.mint(&seller, &1);
+    nft.set_active(&1, &true);
+    nft.set_transferable(&seller, &1, &true);
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token);
+    client.place_bid(&bidder, &1, &1500);
+
+    e.ledger().with_mut(|li| {
+        li.timestamp = 86400 + 1;
+    });
+    client.end_auction(&1);
+
+    assert_eq!(nft.owner_of(&1), bidder);
+}
+
+#[test]
+fn test_offer_on_transferable_active_nft() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let owner = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &[&offerer], 500);
+
+    nft.mint(&owner, &1);
+    nft.set_active(&1, &true);
+    nft.set_transferable(&owner, &1, &true);
+    client.make_offer(&offerer, &1, &500, &payment_token);
+    client.accept_offer(&owner, &1, &offerer);
+
+    assert_eq!(nft.owner_of(&1), offerer);
+}
+
+#[test]
+fn test_list_nft_after_commitment_settles() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    nft.mint(&seller, &1);
+    nft.set_active(&1, &true);
+    assert_eq!(
+        client.try_list_nft(&seller, &1, &1000, &payment_token),
+        Err(Ok(MarketplaceError::NFTLocked))
+    );
+
+    nft.set_active(&1, &false);
+    client.list_nft(&seller, &1, &1000, &payment_token);
+    assert_eq!(nft.owner_of(&1), client.address);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")] // NFTContractError
+fn test_list_nonexistent_nft_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, _nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.list_nft(&seller, &1, &1000, &payment_token);
 }
 
 #[test]
@@ -252,11 +549,12 @@ fn test_buy_own_listing_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
+    nft.mint(&seller, &1);
     client.list_nft(&seller, &1, &1000, &payment_token);
     client.buy_nft(&seller, &1); // Seller trying to buy their own listing
 }
@@ -271,7 +569,7 @@ fn test_make_offer_zero_amount_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, _nft) = setup_marketplace(&e);
 
     let offerer = Address::generate(&e);
     let payment_token = setup_test_token(&e);
@@ -285,11 +583,13 @@ fn test_make_duplicate_offer_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
+    let owner = Address::generate(&e);
     let offerer = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
+    nft.mint(&owner, &1);
     client.make_offer(&offerer, &1, &500, &payment_token);
     client.make_offer(&offerer, &1, &600, &payment_token); // Should fail
 }
@@ -299,13 +599,15 @@ fn test_multiple_offers_same_token() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
+    let owner = Address::generate(&e);
     let offerer1 = Address::generate(&e);
     let offerer2 = Address::generate(&e);
     let payment_token = setup_test_token(&e);
     let token_id = 1u32;
 
+    nft.mint(&owner, &token_id);
     client.make_offer(&offerer1, &token_id, &500, &payment_token);
     client.make_offer(&offerer2, &token_id, &600, &payment_token);
 
@@ -313,17 +615,131 @@ fn test_multiple_offers_same_token() {
     assert_eq!(offers.len(), 2);
 }
 
+#[test]
+fn test_accept_offer_on_listed_nft_releases_escrow() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let token_id = 1u32;
+    let payment_token = setup_funded_token(&e, &[&offerer], 800);
+
+    nft.mint(&seller, &token_id);
+    client.list_nft(&seller, &token_id, &1000, &payment_token);
+    client.make_offer(&offerer, &token_id, &800, &payment_token);
+    client.accept_offer(&seller, &token_id, &offerer);
+
+    assert_eq!(nft.owner_of(&token_id), offerer);
+    assert!(client.try_get_listing(&token_id).is_err());
+    assert_eq!(client.get_offers(&token_id).len(), 0);
+}
+
+#[test]
+fn test_accept_offer_on_unlisted_nft() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let token_id = 1u32;
+    let payment_token = setup_funded_token(&e, &[&offerer], 800);
+
+    nft.mint(&seller, &token_id);
+    client.make_offer(&offerer, &token_id, &800, &payment_token);
+    client.accept_offer(&seller, &token_id, &offerer);
+
+    assert_eq!(nft.owner_of(&token_id), offerer);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")] // NotSeller
+fn test_accept_offer_on_listing_not_seller_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let other = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let token_id = 1u32;
+    let payment_token = setup_funded_token(&e, &[&offerer], 800);
+
+    nft.mint(&seller, &token_id);
+    client.list_nft(&seller, &token_id, &1000, &payment_token);
+    client.make_offer(&offerer, &token_id, &800, &payment_token);
+    client.accept_offer(&other, &token_id, &offerer);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #23)")] // NFTLocked
+fn test_make_offer_on_active_nft_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let owner = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    nft.mint(&owner, &1);
+    nft.set_active(&1, &true);
+    client.make_offer(&offerer, &1, &500, &payment_token);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")] // CannotBuyOwnListing
+fn test_make_offer_on_own_nft_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let owner = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    nft.mint(&owner, &1);
+    client.make_offer(&owner, &1, &500, &payment_token);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")] // NotOwner
+fn test_accept_offer_unlisted_not_owner_fails() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let owner = Address::generate(&e);
+    let impostor = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &[&offerer], 10_000);
+    let token_id = 1u32;
+
+    nft.mint(&owner, &token_id);
+    client.make_offer(&offerer, &token_id, &800, &payment_token);
+    client.accept_offer(&impostor, &token_id, &offerer);
+}
+
 #[test]
 fn test_cancel_offer() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
+    let owner = Address::generate(&e);
     let offerer = Address::generate(&e);
     let payment_token = setup_test_token(&e);
     let token_id = 1u32;
 
+    nft.mint(&owner, &token_id);
     client.make_offer(&offerer, &token_id, &500, &payment_token);
     client.cancel_offer(&offerer, &token_id);
 
@@ -337,7 +753,7 @@ fn test_cancel_nonexistent_offer_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, _nft) = setup_marketplace(&e);
 
     let offerer = Address::generate(&e);
     client.cancel_offer(&offerer, &999);
@@ -353,7 +769,7 @@ fn test_start_auction_zero_price_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, _nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
@@ -367,7 +783,7 @@ fn test_start_auction_zero_duration_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, _nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
@@ -376,26 +792,116 @@ fn test_start_auction_zero_duration_fails() {
 }
 
 #[test]
-fn test_place_bid() {
+#[should_panic(expected = "Error(Contract, #22)")] // NotOwner
+fn test_start_auction_not_owner_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
+    let owner = Address::generate(&e);
     let seller = Address::generate(&e);
-    let _bidder = Address::generate(&e);
     let payment_token = setup_test_token(&e);
+
+    nft.mint(&owner, &1);
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #23)")] // NFTLocked
+fn test_start_auction_active_nft_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    nft.mint(&seller, &1);
+    nft.set_active(&1, &true);
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token);
+}
+
+#[test]
+fn test_place_bid() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
     let token_id = 1u32;
     let starting_price = 1000_0000000i128;
-    let _bid_amount = 1200_0000000i128;
+    let bid_amount = 1200_0000000i128;
+    let payment_token = setup_funded_token(&e, &[&bidder], bid_amount);
 
+    nft.mint(&seller, &token_id);
     client.start_auction(&seller, &token_id, &starting_price, &86400, &payment_token);
 
-    // Note: In real test, setup token contract and balances
-    // client.place_bid(&bidder, &token_id, &bid_amount);
-    // let auction = client.get_auction(&token_id);
-    // assert_eq!(auction.current_bid, bid_amount);
-    // assert_eq!(auction.highest_bidder, Some(bidder));
+    client.place_bid(&bidder, &token_id, &bid_amount);
+    let auction = client.get_auction(&token_id);
+    assert_eq!(auction.current_bid, bid_amount);
+    assert_eq!(auction.highest_bidder, Some(bidder));
+}
+
+#[test]
+fn test_end_auction_transfers_nft_to_winner() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, fee_recipient, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder1 = Address::generate(&e);
+    let bidder2 = Address::generate(&e);
+    let token_id = 1u32;
+    let payment_token = setup_funded_token(&e, &[&bidder1, &bidder2], 5000);
+
+    nft.mint(&seller, &token_id);
+    client.start_auction(&seller, &token_id, &1000, &86400, &payment_token);
+    assert_eq!(nft.owner_of(&token_id), client.address);
+
+    client.place_bid(&bidder1, &token_id, &1500);
+    client.place_bid(&bidder2, &token_id, &2000);
+
+    e.ledger().with_mut(|li| {
+        li.timestamp = 86400 + 1;
+    });
+    client.end_auction(&token_id);
+
+    assert_eq!(nft.owner_of(&token_id), bidder2);
+
+    let token = TokenClient::new(&e, &payment_token);
+    let fee = 2000 * 250 / 10000;
+    assert_eq!(token.balance(&bidder1), 5000); // outbid and refunded
+    assert_eq!(token.balance(&bidder2), 3000);
+    assert_eq!(token.balance(&seller), 2000 - fee);
+    assert_eq!(token.balance(&fee_recipient), fee);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_end_auction_no_bids_returns_nft() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client, nft) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+    let token_id = 1u32;
+
+    nft.mint(&seller, &token_id);
+    client.start_auction(&seller, &token_id, &1000, &86400, &payment_token);
+
+    e.ledger().with_mut(|li| {
+        li.timestamp = 86400 + 1;
+    });
+    client.end_auction(&token_id);
+
+    assert_eq!(nft.owner_of(&token_id), seller);
 }
 
 #[test]
@@ -404,13 +910,14 @@ fn test_place_bid_too_low_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let bidder = Address::generate(&e);
     let payment_token = setup_test_token(&e);
     let token_id = 1u32;
 
+    nft.mint(&seller, &token_id);
     client.start_auction(&seller, &token_id, &1000, &86400, &payment_token);
     client.place_bid(&bidder, &token_id, &500); // Lower than starting price
 }
@@ -421,7 +928,7 @@ fn test_place_bid_after_auction_ends_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let bidder = Address::generate(&e);
@@ -429,6 +936,7 @@ fn test_place_bid_after_auction_ends_fails() {
     let token_id = 1u32;
     let duration = 86400u64; // 1 day
 
+    nft.mint(&seller, &token_id);
     client.start_auction(&seller, &token_id, &1000, &duration, &payment_token);
 
     // Fast forward time past auction end
@@ -445,11 +953,12 @@ fn test_end_auction_before_time_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
+    nft.mint(&seller, &1);
     client.start_auction(&seller, &1, &1000, &86400, &payment_token);
     client.end_auction(&1); // Try to end immediately
 }
@@ -460,11 +969,12 @@ fn test_end_auction_twice_fails() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
+    nft.mint(&seller, &1);
     client.start_auction(&seller, &1, &1000, &86400, &payment_token);
 
     e.ledger().with_mut(|li| {
@@ -480,14 +990,17 @@ fn test_get_all_auctions() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
     // Start 3 auctions
+    nft.mint(&seller, &1);
     client.start_auction(&seller, &1, &1000, &86400, &payment_token);
+    nft.mint(&seller, &2);
     client.start_auction(&seller, &2, &2000, &86400, &payment_token);
+    nft.mint(&seller, &3);
     client.start_auction(&seller, &3, &3000, &86400, &payment_token);
 
     let auctions = client.get_all_auctions();
@@ -503,13 +1016,14 @@ fn test_list_then_start_auction_same_token() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
     let token_id = 1u32;
 
     // List NFT
+    nft.mint(&seller, &token_id);
     client.list_nft(&seller, &token_id, &1000, &payment_token);
 
     // Cancel listing
@@ -527,7 +1041,7 @@ fn test_reentrancy_protection() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, _client) = setup_marketplace(&e);
+    let (_, _, _client, _nft) = setup_marketplace(&e);
 
     // The reentrancy guard prevents nested calls
     // This is tested implicitly in the token transfer flows
@@ -543,7 +1057,7 @@ fn test_gas_listing_operations() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let (_, _, client) = setup_marketplace(&e);
+    let (_, _, client, nft) = setup_marketplace(&e);
 
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
@@ -552,6 +1066,7 @@ fn test_gas_listing_operations() {
     let start = e.ledger().sequence();
 
     for i in 0..10 {
+        nft.mint(&seller, &i);
         client.list_nft(&seller, &i, &1000, &payment_token);
     }
 
@@ -1,4 +1,5 @@
 #![no_std]
+use commitment_interface::CommitmentCoreClient;
 use shared_utils::{EmergencyControl, Pausable};
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
@@ -127,6 +128,8 @@ pub enum DataKey {
     ReentrancyGuard,
     /// Contract version
     Version,
+    /// Transferable position opt-in (token_id -> bool)
+    Transferable(u32),
 }
 
 // Events
@@ -519,9 +522,13 @@ impl CommitmentNFTContract {
 
     /// Transfer NFT to new owner
     ///
+    /// Active NFTs can only be transferred once the holder has enabled
+    /// `set_transferable`; commitment_core is then told about the new owner
+    /// and the token's transferable flag is cleared.
+    ///
     /// # Reentrancy Protection
-    /// Uses checks-effects-interactions pattern. This function only writes to storage
-    /// and doesn't make external calls, but still protected for consistency.
+    /// Uses checks-effects-interactions pattern. The only external call is the
+    /// `transfer_position` callback into commitment_core for transferable tokens.
     pub fn transfer(
         e: Env,
         from: Address,
@@ -575,19 +582,46 @@ impl CommitmentNFTContract {
             return Err(ContractError::NotOwner);
         }
 
-        // Active (locked) commitment NFTs cannot be transferred (#145)
-        if nft.is_active {
+        // Active (locked) commitment NFTs cannot be transferred (#145) unless
+        // the holder opted into transferable position mode
+        let transferable = read_transferable(&e, token_id);
+        if nft.is_active && !transferable {
             e.storage()
                 .instance()
                 .set(&DataKey::ReentrancyGuard, &false);
             return Err(ContractError::NFTLocked);
         }
 
+        // Active transferable positions are mirrored in commitment_core
+        let core_contract: Option<Address> = if nft.is_active && transferable {
+            let core: Address = e
+                .storage()
+                .instance()
+                .get(&DataKey::CoreContract)
+                .ok_or_else(|| {
+                    e.storage()
+                        .instance()
+                        .set(&DataKey::ReentrancyGuard, &false);
+                    ContractError::NotInitialized
+                })?;
+            Some(core)
+        } else {
+            None
+        };
+
         // EFFECTS: Update state
         // Update owner
         nft.owner = to.clone();
         e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
 
+        // The transferable opt-in was the previous holder's choice; the new
+        // holder must opt in again before passing an active position on
+        if transferable {
+            e.storage()
+                .persistent()
+                .remove(&DataKey::Transferable(token_id));
+        }
+
         // OPTIMIZATION: Batch read balances before updating
         let (from_balance, to_balance) = {
             let from_bal = e
@@ -636,6 +670,15 @@ impl CommitmentNFTContract {
             .persistent()
             .set(&DataKey::OwnerTokens(to.clone()), &to_tokens);
 
+        // INTERACTIONS: Reassign the commitment so settle/early_exit pay the new holder
+        if let Some(core_contract) = core_contract {
+            CommitmentCoreClient::new(&e, &core_contract).transfer_position(
+                &nft.metadata.commitment_id,
+                &from,
+                &to,
+            );
+        }
+
         // Clear reentrancy guard
         e.storage()
             .instance()
@@ -650,6 +693,143 @@ impl CommitmentNFTContract {
         Ok(())
     }
 
+    /// Opt a token in or out of transferable position mode (owner only).
+    ///
+    /// While enabled, transferring the NFT before maturity is allowed and
+    /// moves ownership of the underlying commitment in commitment_core.
+    /// The flag is cleared by the transfer, so it only ever covers a
+    /// holder's own transfer, and by `settle` and `mark_inactive`.
+    pub fn set_transferable(
+        e: Env,
+        owner: Address,
+        token_id: u32,
+        transferable: bool,
+    ) -> Result<(), ContractError> {
+        Pausable::require_not_paused(&e);
+        owner.require_auth();
+
+        let nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+        if nft.owner != owner {
+            return Err(ContractError::NotOwner);
+        }
+
+        e.storage()
+            .persistent()
+            .set(&DataKey::Transferable(token_id), &transferable);
+
+        e.events().publish(
+            (Symbol::new(&e, "TransferableSet"), token_id),
+            (transferable, e.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    /// Record a commitment's reduced locked amount after a partial early
+    /// exit (core contract only).
+    pub fn update_amount(e: Env, token_id: u32, new_amount: i128) -> Result<(), ContractError> {
+        Pausable::require_not_paused(&e);
+        let core_contract: Address = e
+            .storage()
+            .instance()
+            .get(&DataKey::CoreContract)
+            .ok_or(ContractError::NotInitialized)?;
+        core_contract.require_auth();
+
+        if new_amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        let mut nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+        if !nft.is_active {
+            return Err(ContractError::AlreadySettled);
+        }
+
+        nft.metadata.initial_amount = new_amount;
+        e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+
+        e.events().publish(
+            (symbol_short!("AmtUpd"), token_id),
+            (new_amount, e.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    /// Start a new term for a rolled-over commitment (core contract only):
+    /// resets the timestamps from the current ledger time, records the new
+    /// rules and amount, and re-activates the token.
+    pub fn renew(
+        e: Env,
+        token_id: u32,
+        duration_days: u32,
+        max_loss_percent: u32,
+        commitment_type: String,
+        initial_amount: i128,
+        early_exit_penalty: u32,
+    ) -> Result<(), ContractError> {
+        Pausable::require_not_paused(&e);
+        let core_contract: Address = e
+            .storage()
+            .instance()
+            .get(&DataKey::CoreContract)
+            .ok_or(ContractError::NotInitialized)?;
+        core_contract.require_auth();
+
+        if duration_days == 0 {
+            return Err(ContractError::InvalidDuration);
+        }
+        if max_loss_percent > 100 {
+            return Err(ContractError::InvalidMaxLoss);
+        }
+        if !Self::is_valid_commitment_type(&e, &commitment_type) {
+            return Err(ContractError::InvalidCommitmentType);
+        }
+        if initial_amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        let mut nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+
+        let created_at = e.ledger().timestamp();
+        let expires_at = (duration_days as u64)
+            .checked_mul(86400)
+            .and_then(|seconds| created_at.checked_add(seconds))
+            .ok_or(ContractError::ExpirationOverflow)?;
+
+        nft.metadata.duration_days = duration_days;
+        nft.metadata.max_loss_percent = max_loss_percent;
+        nft.metadata.commitment_type = commitment_type;
+        nft.metadata.created_at = created_at;
+        nft.metadata.expires_at = expires_at;
+        nft.metadata.initial_amount = initial_amount;
+        nft.early_exit_penalty = early_exit_penalty;
+        nft.is_active = true;
+        e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+
+        e.events().publish(
+            (symbol_short!("Renewed"), token_id),
+            (initial_amount, expires_at, created_at),
+        );
+
+        Ok(())
+    }
+
+    /// Check if a token is in transferable position mode
+    pub fn is_transferable(e: Env, token_id: u32) -> bool {
+        read_transferable(&e, token_id)
+    }
+
     /// Check if NFT is active
     pub fn is_active(e: Env, token_id: u32) -> Result<bool, ContractError> {
         let nft: CommitmentNFT = e
@@ -772,6 +952,10 @@ impl CommitmentNFTContract {
         // Mark as inactive
         nft.is_active = false;
         e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+        // A closed position is no longer mirrored in commitment_core
+        e.storage()
+            .persistent()
+            .remove(&DataKey::Transferable(token_id));
 
         // Clear reentrancy guard
         e.storage()
@@ -840,6 +1024,10 @@ impl CommitmentNFTContract {
         // Mark as inactive (settled)
         nft.is_active = false;
         e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+        // A closed position is no longer mirrored in commitment_core
+        e.storage()
+            .persistent()
+            .remove(&DataKey::Transferable(token_id));
 
         // Clear reentrancy guard
         e.storage()
@@ -895,6 +1083,13 @@ fn read_version(e: &Env) -> u32 {
         .unwrap_or(0)
 }
 
+fn read_transferable(e: &Env, token_id: u32) -> bool {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Transferable(token_id))
+        .unwrap_or(false)
+}
+
 fn require_admin(e: &Env, caller: &Address) -> Result<(), ContractError> {
     caller.require_auth();
     let admin: Address = e
@@ -1247,6 +1247,188 @@ fn test_transfer_after_settlement() {
     assert_eq!(client.balance_of(&recipient), 1);
 }
 
+// ============================================
+// Transferable Position Tests
+// ============================================
+
+/// Records the last `transfer_position` callback from the NFT contract.
+#[soroban_sdk::contract]
+struct MockCoreContract;
+
+#[soroban_sdk::contractimpl]
+impl MockCoreContract {
+    pub fn transfer_position(e: Env, commitment_id: String, from: Address, to: Address) {
+        e.storage()
+            .instance()
+            .set(&symbol_short!("last"), &(commitment_id, from, to));
+    }
+
+    pub fn last(e: Env) -> Option<(String, Address, Address)> {
+        e.storage().instance().get(&symbol_short!("last"))
+    }
+}
+
+fn mint_active(e: &Env, client: &CommitmentNFTContractClient, owner: &Address) -> u32 {
+    let asset_address = Address::generate(e);
+    let (commitment_id, duration, max_loss, commitment_type, amount, asset, penalty) =
+        create_test_metadata(e, &asset_address);
+    client.mint(
+        owner,
+        &commitment_id,
+        &duration,
+        &max_loss,
+        &commitment_type,
+        &amount,
+        &asset,
+        &penalty,
+    )
+}
+
+#[test]
+fn test_transfer_transferable_active_nft_syncs_core() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let core_id = e.register_contract(None, MockCoreContract);
+    let core = MockCoreContractClient::new(&e, &core_id);
+    client.set_core_contract(&core_id);
+
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let token_id = mint_active(&e, &client, &owner);
+
+    assert!(!client.is_transferable(&token_id));
+    client.set_transferable(&owner, &token_id, &true);
+    assert!(client.is_transferable(&token_id));
+
+    client.transfer(&owner, &recipient, &token_id);
+
+    assert_eq!(client.owner_of(&token_id), recipient);
+    assert!(client.is_active(&token_id));
+    // The new holder has to opt in again
+    assert!(!client.is_transferable(&token_id));
+    assert_eq!(
+        core.last(),
+        Some((
+            String::from_str(&e, "commitment_001"),
+            owner.clone(),
+            recipient.clone()
+        ))
+    );
+}
+
+#[test]
+fn test_disable_transferable_relocks_nft() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let token_id = mint_active(&e, &client, &owner);
+
+    client.set_transferable(&owner, &token_id, &true);
+    client.set_transferable(&owner, &token_id, &false);
+
+    let result = client.try_transfer(&owner, &recipient, &token_id);
+    assert_eq!(result, Err(Ok(ContractError::NFTLocked)));
+}
+
+#[test]
+fn test_transferred_nft_is_locked_for_new_holder() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    client.set_core_contract(&e.register_contract(None, MockCoreContract));
+
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let token_id = mint_active(&e, &client, &owner);
+
+    client.set_transferable(&owner, &token_id, &true);
+    client.transfer(&owner, &recipient, &token_id);
+
+    let result = client.try_transfer(&recipient, &owner, &token_id);
+    assert_eq!(result, Err(Ok(ContractError::NFTLocked)));
+}
+
+#[test]
+fn test_inactive_nft_transfer_does_not_sync_core() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let core_id = e.register_contract(None, MockCoreContract);
+    let core = MockCoreContractClient::new(&e, &core_id);
+    client.set_core_contract(&core_id);
+
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let token_id = mint_active(&e, &client, &owner);
+
+    client.set_transferable(&owner, &token_id, &true);
+    client.mark_inactive(&token_id);
+    assert!(!client.is_transferable(&token_id));
+
+    // Opting in again after the position closed does not reach core either
+    client.set_transferable(&owner, &token_id, &true);
+    client.transfer(&owner, &recipient, &token_id);
+
+    assert_eq!(client.owner_of(&token_id), recipient);
+    assert_eq!(core.last(), None);
+}
+
+#[test]
+fn test_settle_clears_transferable() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    client.set_core_contract(&e.register_contract(None, MockCoreContract));
+
+    let owner = Address::generate(&e);
+    let token_id = mint_active(&e, &client, &owner);
+    client.set_transferable(&owner, &token_id, &true);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&token_id);
+
+    assert!(!client.is_active(&token_id));
+    assert!(!client.is_transferable(&token_id));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")] // NotOwner
+fn test_set_transferable_not_owner_fails() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let stranger = Address::generate(&e);
+    let token_id = mint_active(&e, &client, &owner);
+
+    client.set_transferable(&stranger, &token_id, &true);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")] // NotInitialized
+fn test_transfer_transferable_without_core_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let token_id = mint_active(&e, &client, &owner);
+
+    client.set_transferable(&owner, &token_id, &true);
+    client.transfer(&owner, &recipient, &token_id);
+}
+
 // ============================================
 // Transfer Edge Cases Tests
 // ============================================
@@ -1554,6 +1736,110 @@ fn test_transfer_edge_cases_comprehensive() {
     );
 }
 
+// ============================================
+// Update Amount and Renew Tests
+// ============================================
+
+#[test]
+fn test_update_amount() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    let (commitment_id, duration, max_loss, commitment_type, amount, asset, penalty) =
+        create_test_metadata(&e, &asset_address);
+    let token_id = client.mint(
+        &owner,
+        &commitment_id,
+        &duration,
+        &max_loss,
+        &commitment_type,
+        &amount,
+        &asset,
+        &penalty,
+    );
+
+    client.update_amount(&token_id, &600);
+
+    let nft = client.get_metadata(&token_id);
+    assert_eq!(nft.metadata.initial_amount, 600);
+    assert!(nft.is_active);
+
+    let events = e.events().all();
+    let last_event = events.last().unwrap();
+    assert_eq!(
+        last_event.1,
+        vec![
+            &e,
+            symbol_short!("AmtUpd").into_val(&e),
+            token_id.into_val(&e)
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")] // InvalidAmount
+fn test_update_amount_zero_fails() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    let (commitment_id, duration, max_loss, commitment_type, amount, asset, penalty) =
+        create_test_metadata(&e, &asset_address);
+    let token_id = client.mint(
+        &owner,
+        &commitment_id,
+        &duration,
+        &max_loss,
+        &commitment_type,
+        &amount,
+        &asset,
+        &penalty,
+    );
+
+    client.update_amount(&token_id, &0);
+}
+
+#[test]
+fn test_renew_starts_new_term() {
+    let e = Env::default();
+    let (_admin, client, _core_id) = setup_contract_with_core(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+    let (commitment_id, duration, max_loss, commitment_type, amount, asset, penalty) =
+        create_test_metadata(&e, &asset_address);
+    let token_id = client.mint(
+        &owner,
+        &commitment_id,
+        &duration,
+        &max_loss,
+        &commitment_type,
+        &amount,
+        &asset,
+        &penalty,
+    );
+
+    e.ledger().with_mut(|li| li.timestamp = 31 * 86400);
+    client.renew(
+        &token_id,
+        &60,
+        &20,
+        &String::from_str(&e, "aggressive"),
+        &1_100,
+        &3,
+    );
+
+    let nft = client.get_metadata(&token_id);
+    assert!(nft.is_active);
+    assert_eq!(nft.metadata.created_at, 31 * 86400);
+    assert_eq!(nft.metadata.expires_at, 91 * 86400);
+    assert_eq!(nft.metadata.duration_days, 60);
+    assert_eq!(nft.metadata.max_loss_percent, 20);
+    assert_eq!(nft.metadata.initial_amount, 1_100);
+    assert_eq!(nft.early_exit_penalty, 3);
+    assert!(!client.is_expired(&token_id));
+}
+
 // ============================================
 // Settle Tests
 // ============================================
@@ -5,7 +5,8 @@
 
 #![no_std]
 
-use shared_utils::{emit_error_event, Validation};
+use commitment_interface::CommitmentCoreClient;
+use shared_utils::{emit_error_event, CommitmentId, Validation};
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, String,
     Vec,
@@ -173,6 +174,22 @@ fn require_authorized(e: &Env, caller: &Address) {
     }
 }
 
+/// Fail unless the core contract knows `commitment_id`.
+fn require_commitment(e: &Env, commitment_id: &String, context: &str) {
+    if !CommitmentId::is_valid(commitment_id) {
+        fail(e, TransformationError::CommitmentNotFound, context);
+    }
+    let core_contract = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::CoreContract)
+        .unwrap_or_else(|| fail(e, TransformationError::NotInitialized, context));
+    let core = CommitmentCoreClient::new(e, &core_contract);
+    if !matches!(core.try_get_commitment(commitment_id), Ok(Ok(_))) {
+        fail(e, TransformationError::CommitmentNotFound, context);
+    }
+}
+
 fn require_no_reentrancy(e: &Env) {
     let guard: bool = e
         .storage()
@@ -270,6 +287,7 @@ impl CommitmentTransformationContract {
         fee_asset: Address,
     ) -> String {
         require_authorized(&e, &caller);
+        require_commitment(&e, &commitment_id, "create_tranches");
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
 
@@ -383,6 +401,7 @@ impl CommitmentTransformationContract {
         asset_address: Address,
     ) -> String {
         require_authorized(&e, &caller);
+        require_commitment(&e, &commitment_id, "collateralize");
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
 
@@ -442,6 +461,7 @@ impl CommitmentTransformationContract {
         amount: i128,
     ) -> String {
         require_authorized(&e, &caller);
+        require_commitment(&e, &commitment_id, "create_secondary_instrument");
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
 
@@ -503,6 +523,7 @@ impl CommitmentTransformationContract {
         terms_hash: String,
     ) -> String {
         require_authorized(&e, &caller);
+        require_commitment(&e, &commitment_id, "add_protocol_guarantee");
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
 
@@ -1,12 +1,46 @@
 #![cfg(test)]
 
 use super::*;
+use commitment_interface::{Commitment, CommitmentRules, CommitmentStatus};
 use soroban_sdk::testutils::Address as _;
-use soroban_sdk::{vec, Address, Env, String, Vec};
+use soroban_sdk::{contract, contractimpl, vec, Address, Env, String, Vec};
+
+/// Core stand-in that only knows commitment "c_1".
+#[contract]
+struct MockCoreContract;
+
+#[contractimpl]
+impl MockCoreContract {
+    pub fn get_commitment(e: Env, commitment_id: String) -> Commitment {
+        if commitment_id != String::from_str(&e, "c_1") {
+            panic!("Commitment not found");
+        }
+        Commitment {
+            commitment_id,
+            owner: e.current_contract_address(),
+            nft_token_id: 1,
+            rules: CommitmentRules {
+                duration_days: 30,
+                max_loss_percent: 10,
+                commitment_type: String::from_str(&e, "balanced"),
+                early_exit_penalty: 5,
+                min_fee_threshold: 0,
+                grace_period_days: 0,
+            },
+            amount: 1_000_000,
+            asset_address: e.current_contract_address(),
+            created_at: 0,
+            expires_at: 30 * 86400,
+            current_value: 1_000_000,
+            allocated: 0,
+            status: CommitmentStatus::Active,
+        }
+    }
+}
 
 fn setup(e: &Env) -> (Address, Address, Address) {
     let admin = Address::generate(e);
-    let core = Address::generate(e);
+    let core = e.register_contract(None, MockCoreContract);
     let user = Address::generate(e);
     (admin, core, user)
 }
@@ -150,6 +184,21 @@ fn test_collateralize() {
     assert_eq!(client.get_commitment_collateral(&commitment_id).len(), 1);
 }
 
+#[test]
+#[should_panic(expected = "Commitment not found")]
+fn test_collateralize_unknown_commitment_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, core, user) = setup(&e);
+    let contract_id = e.register_contract(None, CommitmentTransformationContract);
+    let client = CommitmentTransformationContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &core);
+    client.set_authorized_transformer(&admin, &user, &true);
+
+    let commitment_id = String::from_str(&e, "missing");
+    client.collateralize(&user, &commitment_id, &500_000i128, &Address::generate(&e));
+}
+
 #[test]
 fn test_create_secondary_instrument() {
     let e = Env::default();
@@ -0,0 +1,135 @@
+//! Commitment identifier helpers
+//!
+//! commitment_core identifies commitments as `"c_<n>"`, where `n` is the
+//! creation counter. Every contract that refers to a commitment uses this
+//! string form; these helpers convert between it and the counter value.
+
+use soroban_sdk::{Env, String};
+
+/// Prefix of every commitment identifier
+const PREFIX: &[u8] = b"c_";
+
+/// Longest valid identifier: prefix plus the 20 digits of `u64::MAX`
+const MAX_LEN: usize = 22;
+
+/// Commitment identifier utility functions
+pub struct CommitmentId;
+
+impl CommitmentId {
+    /// Build the identifier for the `n`th commitment
+    ///
+    /// # Arguments
+    /// * `e` - The environment
+    /// * `n` - Commitment counter value
+    ///
+    /// # Returns
+    /// `"c_<n>"`
+    pub fn from_number(e: &Env, n: u64) -> String {
+        let mut buf = [0u8; MAX_LEN];
+        buf[..PREFIX.len()].copy_from_slice(PREFIX);
+
+        let mut digits = [0u8; MAX_LEN - 2];
+        let mut count = 0;
+        let mut rest = n;
+        loop {
+            digits[count] = b'0' + (rest % 10) as u8;
+            count += 1;
+            rest /= 10;
+            if rest == 0 {
+                break;
+            }
+        }
+        for i in 0..count {
+            buf[PREFIX.len() + i] = digits[count - 1 - i];
+        }
+
+        String::from_bytes(e, &buf[..PREFIX.len() + count])
+    }
+
+    /// Parse the counter value out of an identifier
+    ///
+    /// # Arguments
+    /// * `id` - Commitment identifier
+    ///
+    /// # Returns
+    /// `Some(n)` for a well-formed `"c_<n>"` (no leading zeros), `None` otherwise
+    pub fn to_number(id: &String) -> Option<u64> {
+        let len = id.len() as usize;
+        if len <= PREFIX.len() || len > MAX_LEN {
+            return None;
+        }
+
+        let mut buf = [0u8; MAX_LEN];
+        id.copy_into_slice(&mut buf[..len]);
+        if &buf[..PREFIX.len()] != PREFIX {
+            return None;
+        }
+
+        let digits = &buf[PREFIX.len()..len];
+        if digits.len() > 1 && digits[0] == b'0' {
+            return None;
+        }
+
+        let mut n: u64 = 0;
+        for &digit in digits {
+            if !digit.is_ascii_digit() {
+                return None;
+            }
+            n = n.checked_mul(10)?.checked_add((digit - b'0') as u64)?;
+        }
+        Some(n)
+    }
+
+    /// Check that an identifier is a well-formed `"c_<n>"`
+    pub fn is_valid(id: &String) -> bool {
+        Self::to_number(id).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_number() {
+        let e = Env::default();
+        assert_eq!(CommitmentId::from_number(&e, 0), String::from_str(&e, "c_0"));
+        assert_eq!(
+            CommitmentId::from_number(&e, 1234),
+            String::from_str(&e, "c_1234")
+        );
+        assert_eq!(
+            CommitmentId::from_number(&e, u64::MAX),
+            String::from_str(&e, "c_18446744073709551615")
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let e = Env::default();
+        for n in [0u64, 7, 10, 99_999, u64::MAX] {
+            let id = CommitmentId::from_number(&e, n);
+            assert_eq!(CommitmentId::to_number(&id), Some(n));
+        }
+    }
+
+    #[test]
+    fn test_to_number_rejects_malformed_ids() {
+        let e = Env::default();
+        for id in [
+            "",
+            "c_",
+            "c0",
+            "x_1",
+            "c_01",
+            "c_1a",
+            "c_-1",
+            "commitment_1",
+            "c_18446744073709551616",
+        ] {
+            let id = String::from_str(&e, id);
+            assert_eq!(CommitmentId::to_number(&id), None);
+            assert!(!CommitmentId::is_valid(&id));
+        }
+    }
+}
@@ -4,6 +4,7 @@
 //!
 //! This library provides common functions, helpers, and patterns used across
 //! all CommitLabs contracts including:
+//! - Commitment identifier helpers
 //! - Math utilities (safe math, percentages)
 //! - Time utilities (timestamps, durations)
 //! - Validation utilities
@@ -12,13 +13,16 @@
 //! - Access control patterns
 //! - Event emission patterns
 //! - Rate limiting helpers
+//! - Fee helpers (basis points)
 
 pub mod access_control;
 pub mod batch;
+pub mod commitment_id;
 pub mod emergency;
 pub mod error_codes;
 pub mod errors;
 pub mod events;
+pub mod fees;
 pub mod math;
 pub mod pausable;
 pub mod rate_limiting;
@@ -32,6 +36,7 @@ mod tests;
 // Re-export commonly used items
 pub use access_control::*;
 pub use batch::*;
+pub use commitment_id::CommitmentId;
 pub use emergency::EmergencyControl;
 pub use error_codes::*;
 pub use errors::*;
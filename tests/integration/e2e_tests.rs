@@ -12,7 +12,7 @@ use soroban_sdk::{
     Address, Env, String,
 };
 
-use commitment_core::{CommitmentCoreContract, CommitmentRules};
+use commitment_core::{CommitmentCoreContract, CommitmentRules, CommitmentStatus};
 use commitment_nft::CommitmentNFTContract;
 use attestation_engine::AttestationEngineContract;
 use allocation_logic::{AllocationStrategiesContract, RiskLevel, Strategy};
@@ -68,7 +68,7 @@ fn test_e2e_complete_commitment_lifecycle() {
             CommitmentCoreContract::get_commitment(harness.env.clone(), commitment_id.clone())
         });
     assert_eq!(commitment.owner, *user);
-    assert_eq!(commitment.status, String::from_str(&harness.env, "active"));
+    assert_eq!(commitment.status, CommitmentStatus::Active);
 
     // Verify NFT minted
     let nft_balance = harness
@@ -142,7 +142,7 @@ fn test_e2e_complete_commitment_lifecycle() {
         });
     assert_eq!(
         settled_commitment.status,
-        String::from_str(&harness.env, "settled")
+        CommitmentStatus::Settled
     );
 
     // Verify NFT is inactive
@@ -220,7 +220,7 @@ fn test_e2e_early_exit_with_penalty() {
         });
     assert_eq!(
         commitment.status,
-        String::from_str(&harness.env, "early_exit")
+        CommitmentStatus::EarlyExit
     );
 
     // Verify penalty was applied
@@ -328,19 +328,7 @@ fn test_e2e_commitment_with_allocation() {
     harness.setup_default_pools();
 
     // Create commitment
-    harness.approve_tokens(user, &harness.contracts.commitment_core, amount);
-
-    let commitment_id = harness
-        .env
-        .as_contract(&harness.contracts.commitment_core, || {
-            CommitmentCoreContract::create_commitment(
-                harness.env.clone(),
-                user.clone(),
-                amount,
-                harness.contracts.token.clone(),
-                harness.default_rules(),
-            )
-        });
+    let allocation_id = harness.create_allocatable_commitment(user, amount);
 
     // Allocate funds using balanced strategy
     let allocation_result = harness
@@ -349,7 +337,7 @@ fn test_e2e_commitment_with_allocation() {
             AllocationStrategiesContract::allocate(
                 harness.env.clone(),
                 user.clone(),
-                1u64,
+                allocation_id.clone(),
                 amount,
                 Strategy::Balanced,
             )
@@ -363,10 +351,21 @@ fn test_e2e_commitment_with_allocation() {
     let allocation = harness
         .env
         .as_contract(&harness.contracts.allocation_logic, || {
-            AllocationStrategiesContract::get_allocation(harness.env.clone(), 1u64)
+            AllocationStrategiesContract::get_allocation(harness.env.clone(), allocation_id.clone())
         });
     assert_eq!(allocation.strategy, Strategy::Balanced);
     assert!(allocation.allocations.len() > 0);
+
+    // Funds left commitment_core for the pools
+    let commitment = harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            let ids =
+                CommitmentCoreContract::get_owner_commitments(harness.env.clone(), user.clone());
+            CommitmentCoreContract::get_commitment(harness.env.clone(), ids.get(0).unwrap())
+        });
+    assert_eq!(commitment.current_value, 0);
+    assert_eq!(harness.balance(&harness.contracts.commitment_core), 0);
 }
 
 /// Test: Violation detection and handling flow
@@ -524,6 +523,89 @@ fn test_e2e_nft_transfer_between_users() {
     assert_eq!(buyer_balance, 1);
 }
 
+/// Test: Transferable position moves commitment ownership and settlement payout
+#[test]
+fn test_e2e_transferable_position_settles_to_new_holder() {
+    let harness = TestHarness::new();
+    let seller = &harness.accounts.user1;
+    let buyer = &harness.accounts.user2;
+    let amount = 1_000_000_000_000i128;
+
+    harness.approve_tokens(seller, &harness.contracts.commitment_core, amount);
+    let rules = CommitmentRules {
+        duration_days: 1,
+        max_loss_percent: 10,
+        commitment_type: String::from_str(&harness.env, "balanced"),
+        early_exit_penalty: 5,
+        min_fee_threshold: 1000,
+        grace_period_days: 0,
+    };
+    let commitment_id =
+        harness.create_commitment(seller, amount, &harness.contracts.token, rules);
+    let token_id = harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            CommitmentCoreContract::get_commitment(harness.env.clone(), commitment_id.clone())
+        })
+        .nft_token_id;
+
+    // Opt in and transfer the still-active NFT
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_nft, || {
+            CommitmentNFTContract::set_transferable(
+                harness.env.clone(),
+                seller.clone(),
+                token_id,
+                true,
+            )
+            .unwrap();
+        });
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_nft, || {
+            CommitmentNFTContract::transfer(
+                harness.env.clone(),
+                seller.clone(),
+                buyer.clone(),
+                token_id,
+            )
+            .unwrap();
+        });
+
+    // commitment_core follows the NFT
+    let commitment = harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            CommitmentCoreContract::get_commitment(harness.env.clone(), commitment_id.clone())
+        });
+    assert_eq!(commitment.owner, *buyer);
+    let (seller_ids, buyer_ids) = harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            (
+                CommitmentCoreContract::get_owner_commitments(harness.env.clone(), seller.clone()),
+                CommitmentCoreContract::get_owner_commitments(harness.env.clone(), buyer.clone()),
+            )
+        });
+    assert_eq!(seller_ids.len(), 0);
+    assert_eq!(buyer_ids.len(), 1);
+    assert_eq!(buyer_ids.get(0).unwrap(), commitment_id);
+
+    // Settlement pays the new holder
+    let seller_before = harness.balance(seller);
+    let buyer_before = harness.balance(buyer);
+    harness.advance_days(2);
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            CommitmentCoreContract::settle(harness.env.clone(), commitment_id.clone())
+        });
+
+    assert_eq!(harness.balance(buyer), buyer_before + amount);
+    assert_eq!(harness.balance(seller), seller_before);
+}
+
 /// Test: Fee generation and tracking
 #[test]
 fn test_e2e_fee_generation_tracking() {
@@ -637,6 +719,7 @@ fn test_e2e_allocation_rebalancing_flow() {
 
     // Setup pools
     harness.setup_default_pools();
+    let commitment_id = harness.create_allocatable_commitment(user, amount);
 
     // Initial allocation
     let initial_result = harness
@@ -645,7 +728,7 @@ fn test_e2e_allocation_rebalancing_flow() {
             AllocationStrategiesContract::allocate(
                 harness.env.clone(),
                 user.clone(),
-                1u64,
+                commitment_id.clone(),
                 amount,
                 Strategy::Balanced,
             )
@@ -662,7 +745,11 @@ fn test_e2e_allocation_rebalancing_flow() {
     let rebalance_result = harness
         .env
         .as_contract(&harness.contracts.allocation_logic, || {
-            AllocationStrategiesContract::rebalance(harness.env.clone(), user.clone(), 1u64)
+            AllocationStrategiesContract::rebalance(
+                harness.env.clone(),
+                user.clone(),
+                commitment_id.clone(),
+            )
         });
     assert!(rebalance_result.is_ok());
 
@@ -673,7 +760,7 @@ fn test_e2e_allocation_rebalancing_flow() {
     let final_allocation = harness
         .env
         .as_contract(&harness.contracts.allocation_logic, || {
-            AllocationStrategiesContract::get_allocation(harness.env.clone(), 1u64)
+            AllocationStrategiesContract::get_allocation(harness.env.clone(), commitment_id.clone())
         });
     assert_eq!(final_allocation.total_allocated, amount);
 }
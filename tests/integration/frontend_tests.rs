@@ -13,7 +13,7 @@ use soroban_sdk::{
     Address, Env, String, IntoVal, Symbol,
 };
 
-use commitment_core::{CommitmentCoreContract, CommitmentRules};
+use commitment_core::{CommitmentCoreContract, CommitmentRules, CommitmentStatus};
 use commitment_nft::CommitmentNFTContract;
 
 /// Test: Simulate frontend wallet connection and basic interaction
@@ -420,7 +420,7 @@ fn test_frontend_early_exit_flow() {
         });
     assert_eq!(
         commitment.status,
-        String::from_str(&harness.env, "early_exit")
+        CommitmentStatus::EarlyExit
     );
 }
 
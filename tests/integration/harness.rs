@@ -14,15 +14,19 @@ use soroban_sdk::{
     Address, Env, String, Map,
 };
 
-use commitment_core::{CommitmentCoreContract, CommitmentRules};
+use commitment_core::{CommitmentCoreContract, CommitmentRules, Role};
 use commitment_nft::CommitmentNFTContract;
 use attestation_engine::AttestationEngineContract;
 use allocation_logic::{AllocationStrategiesContract, RiskLevel, Strategy};
 use mock_oracle::MockOracleContract;
+use commitment_marketplace::CommitmentMarketplace;
 
 /// Default staleness threshold for oracle (1 hour in seconds)
 pub const DEFAULT_STALENESS_THRESHOLD: u64 = 3600;
 
+/// Default marketplace fee (2.5%)
+pub const DEFAULT_MARKETPLACE_FEE_BPS: u32 = 250;
+
 /// Default test token decimals
 pub const TOKEN_DECIMALS: u32 = 7;
 
@@ -42,6 +46,8 @@ pub struct TestAccounts {
     pub user2: Address,
     pub attacker: Address,
     pub verifier: Address,
+    /// Valuation keeper granted `Role::Valuer` on commitment_core
+    pub keeper: Address,
 }
 
 impl TestAccounts {
@@ -53,6 +59,7 @@ impl TestAccounts {
             user2: Address::generate(e),
             attacker: Address::generate(e),
             verifier: Address::generate(e),
+            keeper: Address::generate(e),
         }
     }
 }
@@ -64,6 +71,7 @@ pub struct DeployedContracts {
     pub attestation_engine: Address,
     pub allocation_logic: Address,
     pub mock_oracle: Address,
+    pub commitment_marketplace: Address,
     pub token: Address,
 }
 
@@ -105,6 +113,7 @@ impl TestHarness {
         let attestation_engine = env.register_contract(None, AttestationEngineContract);
         let allocation_logic = env.register_contract(None, AllocationStrategiesContract);
         let mock_oracle = env.register_contract(None, MockOracleContract);
+        let commitment_marketplace = env.register_contract(None, CommitmentMarketplace);
 
         // Initialize NFT contract first (needed by commitment_core)
         env.as_contract(&commitment_nft, || {
@@ -120,6 +129,26 @@ impl TestHarness {
                 accounts.admin.clone(),
                 commitment_nft.clone(),
             );
+            CommitmentCoreContract::set_allocation_contract(
+                env.clone(),
+                accounts.admin.clone(),
+                allocation_logic.clone(),
+            );
+        });
+        env.as_contract(&commitment_core, || {
+            CommitmentCoreContract::set_marketplace_contract(
+                env.clone(),
+                accounts.admin.clone(),
+                commitment_marketplace.clone(),
+            );
+        });
+        env.as_contract(&commitment_core, || {
+            CommitmentCoreContract::grant_role(
+                env.clone(),
+                accounts.admin.clone(),
+                Role::Valuer,
+                accounts.keeper.clone(),
+            );
         });
 
         // Initialize attestation_engine
@@ -159,6 +188,18 @@ impl TestHarness {
             .unwrap();
         });
 
+        // Initialize marketplace against the real NFT contract
+        env.as_contract(&commitment_marketplace, || {
+            CommitmentMarketplace::initialize(
+                env.clone(),
+                accounts.admin.clone(),
+                commitment_nft.clone(),
+                DEFAULT_MARKETPLACE_FEE_BPS,
+                accounts.admin.clone(),
+            )
+            .unwrap();
+        });
+
         // Mint tokens to users
         let token_client = StellarAssetClient::new(&env, &token_address);
         token_client.mint(&accounts.user1, &DEFAULT_USER_BALANCE);
@@ -171,6 +212,7 @@ impl TestHarness {
             attestation_engine,
             allocation_logic,
             mock_oracle,
+            commitment_marketplace,
             token: token_address,
         };
 
@@ -210,6 +252,7 @@ impl TestHarness {
         let attestation_engine = env.register_contract(None, AttestationEngineContract);
         let allocation_logic = env.register_contract(None, AllocationStrategiesContract);
         let mock_oracle = env.register_contract(None, MockOracleContract);
+        let commitment_marketplace = env.register_contract(None, CommitmentMarketplace);
 
         // Mint tokens
         let token_client = StellarAssetClient::new(&env, &token_address);
@@ -222,6 +265,7 @@ impl TestHarness {
             attestation_engine,
             allocation_logic,
             mock_oracle,
+            commitment_marketplace,
             token: token_address,
         };
 
@@ -413,18 +457,39 @@ impl TestHarness {
     // ========================================================================
 
     /// Register a pool in allocation logic
+    ///
+    /// The pool address approves allocation logic to return its funds to core.
     pub fn register_pool(&self, pool_id: u32, risk_level: RiskLevel, apy: u32, max_capacity: i128) {
+        let pool_address = Address::generate(&self.env);
         self.env.as_contract(&self.contracts.allocation_logic, || {
             AllocationStrategiesContract::register_pool(
                 self.env.clone(),
                 self.accounts.admin.clone(),
                 pool_id,
+                pool_address.clone(),
                 risk_level,
                 apy,
                 max_capacity,
             )
             .unwrap();
         });
+        self.approve_tokens(&pool_address, &self.contracts.allocation_logic, i128::MAX);
+    }
+
+    /// Create a default-rules commitment for `owner` and return its id
+    pub fn create_allocatable_commitment(&self, owner: &Address, amount: i128) -> String {
+        self.approve_tokens(owner, &self.contracts.commitment_core, amount);
+        self.create_commitment(owner, amount, &self.contracts.token, self.default_rules())
+    }
+
+    /// Mint `amount` to every registered pool address, e.g. as yield
+    pub fn fund_pools(&self, amount: i128) {
+        let pools = self.env.as_contract(&self.contracts.allocation_logic, || {
+            AllocationStrategiesContract::get_all_pools(self.env.clone())
+        });
+        for pool in pools.iter() {
+            self.token_admin_client().mint(&pool.pool_address, &amount);
+        }
     }
 
     /// Setup default pools (one of each risk level)
@@ -0,0 +1,358 @@
+//! Marketplace Integration Tests
+//!
+//! These tests deploy the marketplace together with the real CommitmentNFT
+//! and CommitmentCore contracts and verify:
+//! - NFTs backing active commitments cannot be listed, auctioned or bid on
+//!   unless they are in transferable position mode
+//! - Only the current NFT owner can list or auction it
+//! - Settled commitment NFTs move through escrow to the buyer/winner
+//! - Transferable positions move their commitment through escrow as well,
+//!   and cannot be settled while escrowed
+
+use crate::harness::{TestHarness, DEFAULT_MARKETPLACE_FEE_BPS};
+use soroban_sdk::{Address, String};
+
+use commitment_core::{
+    CommitmentCoreContract, CommitmentCoreContractClient, CommitmentRules, CommitmentStatus,
+};
+use commitment_marketplace::{CommitmentMarketplace, MarketplaceError};
+use commitment_nft::CommitmentNFTContract;
+
+const COMMITMENT_AMOUNT: i128 = 1_000_000_000_000;
+const SALE_PRICE: i128 = 50_000_000_000;
+
+/// Create a one-day commitment for `owner` and return its NFT token id
+fn create_commitment_nft(harness: &TestHarness, owner: &Address) -> (String, u32) {
+    harness.approve_tokens(owner, &harness.contracts.commitment_core, COMMITMENT_AMOUNT);
+    let rules = CommitmentRules {
+        duration_days: 1,
+        max_loss_percent: 10,
+        commitment_type: String::from_str(&harness.env, "balanced"),
+        early_exit_penalty: 5,
+        min_fee_threshold: 1000,
+        grace_period_days: 0,
+    };
+    let commitment_id = harness.create_commitment(
+        owner,
+        COMMITMENT_AMOUNT,
+        &harness.contracts.token,
+        rules,
+    );
+    let commitment = harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            CommitmentCoreContract::get_commitment(harness.env.clone(), commitment_id.clone())
+        });
+    (commitment_id, commitment.nft_token_id)
+}
+
+/// Create a commitment and settle it so its NFT is unlocked
+fn create_settled_commitment_nft(harness: &TestHarness, owner: &Address) -> u32 {
+    let (commitment_id, token_id) = create_commitment_nft(harness, owner);
+    harness.advance_days(2);
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            CommitmentCoreContract::settle(harness.env.clone(), commitment_id.clone())
+        });
+    token_id
+}
+
+fn nft_owner(harness: &TestHarness, token_id: u32) -> Address {
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_nft, || {
+            CommitmentNFTContract::owner_of(harness.env.clone(), token_id).unwrap()
+        })
+}
+
+fn list(
+    harness: &TestHarness,
+    seller: &Address,
+    token_id: u32,
+    price: i128,
+) -> Result<(), MarketplaceError> {
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_marketplace, || {
+            CommitmentMarketplace::list_nft(
+                harness.env.clone(),
+                seller.clone(),
+                token_id,
+                price,
+                harness.contracts.token.clone(),
+            )
+        })
+}
+
+/// Test: NFT backing an active commitment cannot be listed
+#[test]
+fn test_list_active_commitment_nft_is_locked() {
+    let harness = TestHarness::new();
+    let owner = &harness.accounts.user1;
+
+    let (_, token_id) = create_commitment_nft(&harness, owner);
+
+    assert_eq!(
+        list(&harness, owner, token_id, SALE_PRICE),
+        Err(MarketplaceError::NFTLocked)
+    );
+    assert_eq!(nft_owner(&harness, token_id), *owner);
+}
+
+/// Test: Only the NFT owner can list it
+#[test]
+fn test_list_nft_not_owner_rejected() {
+    let harness = TestHarness::new();
+    let owner = &harness.accounts.user1;
+    let attacker = &harness.accounts.attacker;
+
+    let token_id = create_settled_commitment_nft(&harness, owner);
+
+    assert_eq!(
+        list(&harness, attacker, token_id, SALE_PRICE),
+        Err(MarketplaceError::NotOwner)
+    );
+    assert_eq!(nft_owner(&harness, token_id), *owner);
+}
+
+/// Test: Offers on NFTs backing active commitments are rejected
+#[test]
+fn test_make_offer_on_active_commitment_nft_is_locked() {
+    let harness = TestHarness::new();
+    let owner = &harness.accounts.user1;
+    let offerer = &harness.accounts.user2;
+
+    let (_, token_id) = create_commitment_nft(&harness, owner);
+
+    let result = harness
+        .env
+        .as_contract(&harness.contracts.commitment_marketplace, || {
+            CommitmentMarketplace::make_offer(
+                harness.env.clone(),
+                offerer.clone(),
+                token_id,
+                SALE_PRICE,
+                harness.contracts.token.clone(),
+            )
+        });
+    assert_eq!(result, Err(MarketplaceError::NFTLocked));
+}
+
+/// Test: Active NFT cannot be auctioned
+#[test]
+fn test_start_auction_active_commitment_nft_is_locked() {
+    let harness = TestHarness::new();
+    let owner = &harness.accounts.user1;
+
+    let (_, token_id) = create_commitment_nft(&harness, owner);
+
+    let result = harness
+        .env
+        .as_contract(&harness.contracts.commitment_marketplace, || {
+            CommitmentMarketplace::start_auction(
+                harness.env.clone(),
+                owner.clone(),
+                token_id,
+                SALE_PRICE,
+                86400,
+                harness.contracts.token.clone(),
+            )
+        });
+    assert_eq!(result, Err(MarketplaceError::NFTLocked));
+}
+
+/// Test: List a settled commitment NFT and sell it through escrow
+#[test]
+fn test_list_and_buy_settled_commitment_nft() {
+    let harness = TestHarness::new();
+    let seller = &harness.accounts.user1;
+    let buyer = &harness.accounts.user2;
+    let fee_recipient = &harness.accounts.admin;
+
+    let token_id = create_settled_commitment_nft(&harness, seller);
+
+    list(&harness, seller, token_id, SALE_PRICE).unwrap();
+    assert_eq!(
+        nft_owner(&harness, token_id),
+        harness.contracts.commitment_marketplace
+    );
+
+    let seller_before = harness.balance(seller);
+    let buyer_before = harness.balance(buyer);
+
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_marketplace, || {
+            CommitmentMarketplace::buy_nft(harness.env.clone(), buyer.clone(), token_id)
+        })
+        .unwrap();
+
+    let fee = SALE_PRICE * DEFAULT_MARKETPLACE_FEE_BPS as i128 / 10000;
+    assert_eq!(nft_owner(&harness, token_id), *buyer);
+    assert_eq!(harness.balance(buyer), buyer_before - SALE_PRICE);
+    assert_eq!(harness.balance(seller), seller_before + SALE_PRICE - fee);
+    assert_eq!(harness.balance(fee_recipient), fee);
+}
+
+/// Test: A transferable active position is sold through escrow, and its
+/// commitment follows the NFT to the buyer
+#[test]
+fn test_list_and_buy_transferable_position() {
+    let harness = TestHarness::new();
+    let seller = &harness.accounts.user1;
+    let buyer = &harness.accounts.user2;
+
+    let (commitment_id, token_id) = create_commitment_nft(&harness, seller);
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_nft, || {
+            CommitmentNFTContract::set_transferable(
+                harness.env.clone(),
+                seller.clone(),
+                token_id,
+                true,
+            )
+            .unwrap();
+        });
+
+    let core_owner = || {
+        harness
+            .env
+            .as_contract(&harness.contracts.commitment_core, || {
+                CommitmentCoreContract::get_commitment(harness.env.clone(), commitment_id.clone())
+            })
+            .owner
+    };
+
+    list(&harness, seller, token_id, SALE_PRICE).unwrap();
+    assert_eq!(
+        nft_owner(&harness, token_id),
+        harness.contracts.commitment_marketplace
+    );
+    assert_eq!(core_owner(), harness.contracts.commitment_marketplace);
+
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_marketplace, || {
+            CommitmentMarketplace::buy_nft(harness.env.clone(), buyer.clone(), token_id)
+        })
+        .unwrap();
+
+    assert_eq!(nft_owner(&harness, token_id), *buyer);
+    assert_eq!(core_owner(), *buyer);
+
+    // Settlement pays the buyer
+    let buyer_before = harness.balance(buyer);
+    harness.advance_days(2);
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_core, || {
+            CommitmentCoreContract::settle(harness.env.clone(), commitment_id.clone())
+        });
+    assert_eq!(harness.balance(buyer), buyer_before + COMMITMENT_AMOUNT);
+}
+
+/// Test: A listed position that matures cannot be settled into the
+/// marketplace; it pays whoever holds it once it leaves escrow
+#[test]
+fn test_settle_listed_position_waits_for_escrow_release() {
+    let harness = TestHarness::new();
+    let seller = &harness.accounts.user1;
+    let buyer = &harness.accounts.user2;
+
+    let (commitment_id, token_id) = create_commitment_nft(&harness, seller);
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_nft, || {
+            CommitmentNFTContract::set_transferable(
+                harness.env.clone(),
+                seller.clone(),
+                token_id,
+                true,
+            )
+            .unwrap();
+        });
+    list(&harness, seller, token_id, SALE_PRICE).unwrap();
+
+    harness.advance_days(2);
+    let core = CommitmentCoreContractClient::new(&harness.env, &harness.contracts.commitment_core);
+    assert!(core.try_settle(&commitment_id).is_err());
+
+    // Nothing moved: the funds stay in core and the NFT in escrow
+    assert_eq!(
+        harness.balance(&harness.contracts.commitment_core),
+        COMMITMENT_AMOUNT
+    );
+    assert_eq!(harness.balance(&harness.contracts.commitment_marketplace), 0);
+    assert_eq!(
+        nft_owner(&harness, token_id),
+        harness.contracts.commitment_marketplace
+    );
+    assert_eq!(
+        core.get_commitment(&commitment_id).status,
+        CommitmentStatus::Active
+    );
+
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_marketplace, || {
+            CommitmentMarketplace::buy_nft(harness.env.clone(), buyer.clone(), token_id)
+        })
+        .unwrap();
+
+    let buyer_before = harness.balance(buyer);
+    core.settle(&commitment_id);
+    assert_eq!(harness.balance(buyer), buyer_before + COMMITMENT_AMOUNT);
+    assert_eq!(harness.balance(&harness.contracts.commitment_marketplace), 0);
+}
+
+/// Test: Auction a settled commitment NFT to the highest bidder
+#[test]
+fn test_auction_settled_commitment_nft() {
+    let harness = TestHarness::new();
+    let seller = &harness.accounts.user1;
+    let bidder = &harness.accounts.user2;
+
+    let token_id = create_settled_commitment_nft(&harness, seller);
+
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_marketplace, || {
+            CommitmentMarketplace::start_auction(
+                harness.env.clone(),
+                seller.clone(),
+                token_id,
+                SALE_PRICE,
+                86400,
+                harness.contracts.token.clone(),
+            )
+        })
+        .unwrap();
+    assert_eq!(
+        nft_owner(&harness, token_id),
+        harness.contracts.commitment_marketplace
+    );
+
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_marketplace, || {
+            CommitmentMarketplace::place_bid(
+                harness.env.clone(),
+                bidder.clone(),
+                token_id,
+                SALE_PRICE + 1,
+            )
+        })
+        .unwrap();
+
+    harness.advance_days(2);
+    harness
+        .env
+        .as_contract(&harness.contracts.commitment_marketplace, || {
+            CommitmentMarketplace::end_auction(harness.env.clone(), token_id)
+        })
+        .unwrap();
+
+    assert_eq!(nft_owner(&harness, token_id), *bidder);
+}